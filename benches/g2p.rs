@@ -0,0 +1,62 @@
+use {
+    kokoro_tts::{G2PError, Phonemizer, PhonemizerChain, clear_g2p_caches},
+    std::time::{Duration, Instant},
+};
+
+/// 流式合成时常见的短句
+const SENTENCES: [&str; 6] = [
+    "你好，我们是一群追逐梦想的人。",
+    "今天天气如何？",
+    "你在使用Rust编程语言吗？",
+    "Hello world, this is a streaming test.",
+    "I paid $5 for 3个苹果.",
+    "The quick brown fox jumps over the lazy dog.",
+];
+
+/// 重复转换所有句子，返回每句的平均耗时
+///
+/// `cold`为真时每句之前都清空单词转换结果的缓存，测量没有缓存时的耗时。
+fn measure(chain: &PhonemizerChain, rounds: u32, cold: bool) -> Result<Duration, G2PError> {
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..rounds {
+        for sentence in SENTENCES {
+            if cold {
+                clear_g2p_caches();
+            }
+            let start = Instant::now();
+            chain.phonemize(sentence)?;
+            elapsed += start.elapsed();
+        }
+    }
+    Ok(elapsed / (rounds * SENTENCES.len() as u32))
+}
+
+fn main() -> Result<(), G2PError> {
+    let chain = PhonemizerChain::new(false);
+    // 第一轮包括加载词典的时间
+    println!("first round: {:?}/sentence", measure(&chain, 1, false)?);
+    let warm = measure(&chain, 50, false)?;
+    println!("warm: {:?}/sentence", warm);
+    let cold = measure(&chain, 10, true)?;
+    println!("cold: {:?}/sentence", cold);
+    println!("cold/warm: {:.1}x", cold.as_secs_f64() / warm.as_secs_f64());
+    // 新建前端链不应重新加载词典或编译正则表达式，缓存在前端链之间共享
+    let start = Instant::now();
+    for sentence in SENTENCES {
+        PhonemizerChain::new(false).phonemize(sentence)?;
+    }
+    let fresh = start.elapsed() / SENTENCES.len() as u32;
+    println!("fresh chain: {:?}/sentence", fresh);
+    println!(
+        "fresh/warm: {:.1}x",
+        fresh.as_secs_f64() / warm.as_secs_f64()
+    );
+
+    // v1.1的前端
+    let chain = PhonemizerChain::new(true);
+    measure(&chain, 1, false)?;
+    let warm = measure(&chain, 50, false)?;
+    let cold = measure(&chain, 10, true)?;
+    println!("v1.1 warm: {:?}/sentence, cold: {:?}/sentence", warm, cold);
+    Ok(())
+}
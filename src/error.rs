@@ -13,14 +13,22 @@ use std::{
 pub enum KokoroError {
     Decode(DecodeError),
     G2P(G2PError),
+    InvalidToken(i64),
     Io(IoError),
     ModelReleased,
+    #[cfg(feature = "opus")]
+    Opus(audiopus::Error),
     Ort(OrtError),
+    #[cfg(feature = "playback")]
+    Playback(String),
     Send(String),
     Shape(ShapeError),
     SystemTime(SystemTimeError),
+    TooManyTokens(usize, usize),
+    UnknownPhoneme(char, usize),
     VoiceNotFound(String),
     VoiceVersionInvalid(String),
+    WavTooLarge,
 }
 
 impl Display for KokoroError {
@@ -29,14 +37,24 @@ impl Display for KokoroError {
         match self {
             Self::Decode(e) => Display::fmt(e, f),
             Self::G2P(e) => Display::fmt(e, f),
+            Self::InvalidToken(t) => write!(f, "InvalidToken({})", t),
             Self::Io(e) => Display::fmt(e, f),
             Self::Ort(e) => Display::fmt(e, f),
             Self::ModelReleased => write!(f, "ModelReleased"),
+            #[cfg(feature = "opus")]
+            Self::Opus(e) => Display::fmt(e, f),
+            #[cfg(feature = "playback")]
+            Self::Playback(e) => write!(f, "Playback({})", e),
             Self::Send(e) => Display::fmt(e, f),
             Self::Shape(e) => Display::fmt(e, f),
             Self::SystemTime(e) => Display::fmt(e, f),
+            Self::TooManyTokens(len, max) => write!(f, "TooManyTokens({} > {})", len, max),
+            Self::UnknownPhoneme(c, position) => {
+                write!(f, "UnknownPhoneme({:?} at {})", c, position)
+            }
             Self::VoiceNotFound(name) => write!(f, "VoiceNotFound({})", name),
             Self::VoiceVersionInvalid(msg) => write!(f, "VoiceVersionInvalid({})", msg),
+            Self::WavTooLarge => write!(f, "WavTooLarge"),
         }
     }
 }
@@ -78,3 +96,10 @@ impl From<SystemTimeError> for KokoroError {
         Self::SystemTime(value)
     }
 }
+
+#[cfg(feature = "opus")]
+impl From<audiopus::Error> for KokoroError {
+    fn from(value: audiopus::Error) -> Self {
+        Self::Opus(value)
+    }
+}
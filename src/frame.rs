@@ -0,0 +1,209 @@
+use {
+    crate::SAMPLE_RATE,
+    futures::Stream,
+    pin_project::pin_project,
+    std::{
+        collections::VecDeque,
+        pin::Pin,
+        task::{Context, Poll},
+        time::Duration,
+    },
+};
+
+/// 不足一帧的剩余音频的处理方式
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum FrameFlush {
+    /// 各段合成结果首尾相接，只在流结束时补零输出最后一帧
+    #[default]
+    OnEnd,
+    /// 每段合成结果结束时都补零输出最后一帧，下一段从新的帧开始
+    EachItem,
+    /// 流结束时丢弃不足一帧的剩余音频
+    Drop,
+}
+
+/// 定长分帧器
+///
+/// 该结构体把任意长度的音频切分为固定时长的帧，例如实时传输常用的10毫秒或20毫秒。
+pub struct Packetizer {
+    frame_len: usize,
+    buffer: Vec<f32>,
+}
+
+impl Packetizer {
+    /// 创建分帧器
+    ///
+    /// # 参数
+    ///
+    /// * `frame_duration` - 每帧的时长，按模型采样率换算为样本数。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::Packetizer;
+    /// use std::time::Duration;
+    ///
+    /// let mut packetizer = Packetizer::new(Duration::from_millis(20));
+    /// assert_eq!(packetizer.frame_len(), 480);
+    /// assert_eq!(packetizer.push(&[0.; 1000]).len(), 2);
+    /// assert_eq!(packetizer.flush().map(|i| i.len()), Some(480));
+    /// ```
+    ///
+    pub fn new(frame_duration: Duration) -> Self {
+        let frame_len = (frame_duration.as_secs_f64() * SAMPLE_RATE as f64).round() as usize;
+        Self {
+            frame_len: frame_len.max(1),
+            buffer: Vec::new(),
+        }
+    }
+
+    /// 每帧的样本数
+    pub fn frame_len(&self) -> usize {
+        self.frame_len
+    }
+
+    /// 输入一段音频，返回其中所有完整的帧，剩余部分留到下次
+    pub fn push(&mut self, audio: &[f32]) -> Vec<Vec<f32>> {
+        self.buffer.extend_from_slice(audio);
+        let count = self.buffer.len() / self.frame_len;
+        let frames = self
+            .buffer
+            .chunks_exact(self.frame_len)
+            .map(<[f32]>::to_vec)
+            .collect();
+        self.buffer.drain(..count * self.frame_len);
+        frames
+    }
+
+    /// 用静音补齐剩余的音频并输出最后一帧，没有剩余时返回`None`
+    pub fn flush(&mut self) -> Option<Vec<f32>> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let mut frame = std::mem::take(&mut self.buffer);
+        frame.resize(self.frame_len, 0.);
+        Some(frame)
+    }
+
+    /// 丢弃剩余的音频
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+/// 定长音频帧流
+///
+/// 该结构体包装一个语音合成流，把合成结果重新切分为固定时长的帧，便于对接WebRTC、SIP等实时传输。
+#[pin_project]
+pub struct FrameStream<S> {
+    #[pin]
+    inner: S,
+    packetizer: Packetizer,
+    flush: FrameFlush,
+    pending: VecDeque<Vec<f32>>,
+    finished: bool,
+}
+
+impl<S> FrameStream<S> {
+    pub(super) fn new(inner: S, frame_duration: Duration, flush: FrameFlush) -> Self {
+        Self {
+            inner,
+            packetizer: Packetizer::new(frame_duration),
+            flush,
+            pending: Default::default(),
+            finished: false,
+        }
+    }
+
+    /// 每帧的样本数
+    pub fn frame_len(&self) -> usize {
+        self.packetizer.frame_len()
+    }
+}
+
+impl<S> Stream for FrameStream<S>
+where
+    S: Stream<Item = (Vec<f32>, Duration)>,
+{
+    type Item = Vec<f32>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if let Some(frame) = this.pending.pop_front() {
+                return Poll::Ready(Some(frame));
+            }
+            if *this.finished {
+                return Poll::Ready(None);
+            }
+
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some((data, _))) => {
+                    this.pending.extend(this.packetizer.push(&data));
+                    if *this.flush == FrameFlush::EachItem {
+                        this.pending.extend(this.packetizer.flush());
+                    }
+                }
+                Poll::Ready(None) => {
+                    *this.finished = true;
+                    match this.flush {
+                        FrameFlush::Drop => this.packetizer.clear(),
+                        _ => this.pending.extend(this.packetizer.flush()),
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        futures::{StreamExt, executor::block_on, stream::iter},
+    };
+
+    #[test]
+    fn test_packetizer() {
+        let mut packetizer = Packetizer::new(Duration::from_millis(10));
+        assert_eq!(240, packetizer.frame_len());
+        assert!(packetizer.push(&[1.; 100]).is_empty());
+        let frames = packetizer.push(&[2.; 400]);
+        assert_eq!(2, frames.len());
+        assert_eq!(1., frames[0][99]);
+        assert_eq!(2., frames[0][100]);
+        let last = packetizer.flush().unwrap();
+        assert_eq!(240, last.len());
+        assert_eq!(2., last[19]);
+        assert_eq!(0., last[20]);
+        assert!(packetizer.flush().is_none());
+    }
+
+    #[test]
+    fn test_frame_stream() {
+        let items = || {
+            iter(vec![
+                (vec![1.; 300], Duration::ZERO),
+                (vec![2.; 300], Duration::ZERO),
+            ])
+        };
+        let frame = Duration::from_millis(10);
+
+        let frames =
+            block_on(FrameStream::new(items(), frame, FrameFlush::OnEnd).collect::<Vec<_>>());
+        assert_eq!(3, frames.len());
+        assert_eq!(2., frames[1][60]);
+        assert_eq!(0., frames[2][120]);
+
+        let frames =
+            block_on(FrameStream::new(items(), frame, FrameFlush::EachItem).collect::<Vec<_>>());
+        assert_eq!(4, frames.len());
+        assert_eq!(0., frames[1][60]);
+
+        let frames =
+            block_on(FrameStream::new(items(), frame, FrameFlush::Drop).collect::<Vec<_>>());
+        assert_eq!(2, frames.len());
+    }
+}
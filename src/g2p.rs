@@ -1,16 +1,41 @@
 /// 文本到国际音标的转换
+mod acronym;
+mod analysis;
+mod cache;
+mod contraction;
+mod dialect;
+mod fr;
+mod heteronym;
+mod hi;
+mod ja;
+mod lexicon;
+mod markup;
+mod misaki;
+mod normalize;
+mod phonemizer;
+mod romance;
+mod translit;
 mod v10;
 mod v11;
 
+pub use {
+    acronym::*,
+    analysis::*,
+    lexicon::*,
+    normalize::{ChineseVariant, EmojiReading},
+    phonemizer::*,
+};
+
 use super::PinyinError;
-use chinese_number::{ChineseCase, ChineseCountMethod, ChineseVariant, NumberToChinese};
+use cache::Cache;
 #[cfg(feature = "use-cmudict")]
 use cmudict_fast::{Cmudict, Error as CmudictError};
 use pinyin::ToPinyin;
-use regex::{Captures, Error as RegexError, Regex};
+use regex::Error as RegexError;
 use std::{
     error::Error,
     fmt::{Display, Formatter, Result as FmtResult},
+    sync::{LazyLock, Mutex},
 };
 
 #[derive(Debug)]
@@ -18,6 +43,7 @@ pub enum G2PError {
     #[cfg(feature = "use-cmudict")]
     CmudictError(CmudictError),
     EnptyData,
+    Lexicon(String),
     #[cfg(not(feature = "use-cmudict"))]
     Nul(std::ffi::NulError),
     Pinyin(PinyinError),
@@ -33,6 +59,7 @@ impl Display for G2PError {
             #[cfg(feature = "use-cmudict")]
             Self::CmudictError(e) => Display::fmt(e, f),
             Self::EnptyData => Display::fmt("EmptyData", f),
+            Self::Lexicon(e) => write!(f, "Lexicon({})", e),
             #[cfg(not(feature = "use-cmudict"))]
             Self::Nul(e) => Display::fmt(e, f),
             Self::Pinyin(e) => Display::fmt(e, f),
@@ -78,7 +105,43 @@ impl From<std::str::Utf8Error> for G2PError {
     }
 }
 
+/// 汉语词语转换结果的缓存
+static CACHE_ZH: LazyLock<Mutex<Cache<String, String>>> =
+    LazyLock::new(|| Mutex::new(Cache::new(cache::CAPACITY)));
+/// v1.1的汉语前端转换结果的缓存，以整段文本为键
+static CACHE_ZH_V11: LazyLock<Mutex<Cache<String, String>>> =
+    LazyLock::new(|| Mutex::new(Cache::new(cache::CAPACITY)));
+/// 英语单词转换结果的缓存
+static CACHE_EN: LazyLock<Mutex<Cache<(String, PronunciationSelection), String>>> =
+    LazyLock::new(|| Mutex::new(Cache::new(cache::CAPACITY)));
+
+/// 清空各个前端的转换结果缓存
+///
+/// 只用于测量没有缓存时的耗时，正常使用时不需要调用。
+#[doc(hidden)]
+pub fn clear_g2p_caches() {
+    if let Ok(mut cache) = CACHE_ZH.lock() {
+        cache.clear();
+    }
+    if let Ok(mut cache) = CACHE_ZH_V11.lock() {
+        cache.clear();
+    }
+    if let Ok(mut cache) = CACHE_EN.lock() {
+        cache.clear();
+    }
+}
+
+/// 把汉语词语转换为国际音标，最近使用的词语的结果会被缓存
 fn word2ipa_zh(word: &str) -> Result<String, G2PError> {
+    cache::memoize(&CACHE_ZH, word.to_owned(), || pinyin_to_ipa(word))
+}
+
+/// 用v1.1的前端把汉语文本转换为注音，最近转换过的文本的结果会被缓存
+fn text2zhuyin_v11(text: &str) -> Result<String, G2PError> {
+    cache::memoize(&CACHE_ZH_V11, text.to_owned(), || Ok(v11::g2p(text, true)))
+}
+
+fn pinyin_to_ipa(word: &str) -> Result<String, G2PError> {
     let iter = word.chars().map(|i| match i.to_pinyin() {
         None => Ok(i.to_string()),
         Some(p) => v10::py2ipa(p.with_tone_num_end()),
@@ -91,32 +154,86 @@ fn word2ipa_zh(word: &str) -> Result<String, G2PError> {
     Ok(result)
 }
 
+/// 把以空格分隔的ARPAbet音素转换为国际音标，例如`R IY1 D`
+fn arpabet_to_ipa(arpa: &str) -> Result<String, G2PError> {
+    let mut result = String::new();
+    for i in arpa.split(' ') {
+        result.push_str(&super::arpa_to_ipa(i)?);
+    }
+    Ok(result)
+}
+
+/// 为单词和种子计算一个稳定的哈希值，不随Rust版本和运行次数变化
 #[cfg(feature = "use-cmudict")]
-fn word2ipa_en(word: &str) -> Result<String, G2PError> {
-    use super::{arpa_to_ipa, letters_to_ipa};
+fn seeded_hash(seed: u64, word: &str) -> u64 {
+    // FNV-1a
+    let mut hash = 0xcbf29ce484222325 ^ seed;
+    for b in word.to_lowercase().bytes() {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// 把英语单词转换为国际音标，最近使用的单词的结果会被缓存
+fn word2ipa_en(word: &str, selection: PronunciationSelection) -> Result<String, G2PError> {
+    cache::memoize(&CACHE_EN, (word.to_owned(), selection), || {
+        backend_en(word, selection)
+    })
+}
+
+#[cfg(feature = "use-cmudict")]
+fn get_cmudict<'a>() -> Result<&'a Cmudict, CmudictError> {
     use std::{
         io::{Error as IoError, ErrorKind},
         str::FromStr,
-        sync::LazyLock,
     };
 
-    fn get_cmudict<'a>() -> Result<&'a Cmudict, CmudictError> {
-        static CMUDICT: LazyLock<Result<Cmudict, CmudictError>> =
-            LazyLock::new(|| Cmudict::from_str(include_str!("../dict/cmudict.dict")));
-        CMUDICT.as_ref().map_err(|i| match i {
-            CmudictError::IoErr(e) => CmudictError::IoErr(IoError::new(ErrorKind::Other, e)),
-            CmudictError::InvalidLine(e) => CmudictError::InvalidLine(*e),
-            CmudictError::RuleParseError(e) => CmudictError::RuleParseError(e.clone()),
-        })
-    }
+    static CMUDICT: LazyLock<Result<Cmudict, CmudictError>> =
+        LazyLock::new(|| Cmudict::from_str(include_str!("../dict/cmudict.dict")));
+    CMUDICT.as_ref().map_err(|i| match i {
+        CmudictError::IoErr(e) => CmudictError::IoErr(IoError::new(ErrorKind::Other, e)),
+        CmudictError::InvalidLine(e) => CmudictError::InvalidLine(*e),
+        CmudictError::RuleParseError(e) => CmudictError::RuleParseError(e.clone()),
+    })
+}
+
+/// 小写的单词是否在CMU词典中，用于判断全部大写的单词是缩略词还是强调
+#[cfg(feature = "use-cmudict")]
+fn is_word_en(word: &str) -> bool {
+    get_cmudict().is_ok_and(|i| i.get(word).is_some())
+}
 
+/// 小写的单词能否按普通单词拼读，用于判断全部大写的单词是缩略词还是强调
+///
+/// espeak的词典只收录拼写规则的例外，这里与espeak一样近似地要求前四个字母中有元音，并且没有三个相连的相同字母（IEEE）。
+#[cfg(not(feature = "use-cmudict"))]
+fn is_word_en(word: &str) -> bool {
+    let chars = word.chars().collect::<Vec<_>>();
+    chars.iter().take(4).any(|c| "aeiouy".contains(*c))
+        && !chars.windows(3).any(|i| i[0] == i[1] && i[1] == i[2])
+}
+
+#[cfg(feature = "use-cmudict")]
+fn backend_en(word: &str, selection: PronunciationSelection) -> Result<String, G2PError> {
+    use super::{arpa_to_ipa, letters_to_ipa};
+
+    // 缩约形式和所有格先作为一个单词查询，词典中没有时再拆分
     let Some(rules) = get_cmudict()?.get(word) else {
+        if let Some(result) = contraction::decompose(word, |i| word2ipa_en(i, selection))? {
+            return Ok(result);
+        }
         return Ok(letters_to_ipa(word));
     };
     if rules.is_empty() {
         return Ok(word.to_owned());
     }
-    let i = rand::random_range(0..rules.len());
+    let i = match selection {
+        PronunciationSelection::First => 0,
+        PronunciationSelection::Seeded(seed) => {
+            (seeded_hash(seed, word) % rules.len() as u64) as usize
+        }
+    };
     let result = rules[i]
         .pronunciation()
         .iter()
@@ -125,34 +242,68 @@ fn word2ipa_en(word: &str) -> Result<String, G2PError> {
     Ok(result)
 }
 
+/// espeak对每个单词只给出一个发音，因此忽略`_selection`
 #[cfg(not(feature = "use-cmudict"))]
-fn word2ipa_en(word: &str) -> Result<String, G2PError> {
-    use super::letters_to_ipa;
+fn backend_en(word: &str, _selection: PronunciationSelection) -> Result<String, G2PError> {
     use std::{
-        ffi::{CStr, CString, c_char},
-        sync::Once,
+        cell::RefCell,
+        ffi::{CStr, CString, c_char, c_void},
+        ptr::NonNull,
     };
 
-    if word.chars().count() < 4 && word.chars().all(|c| c.is_ascii_uppercase()) {
-        return Ok(letters_to_ipa(word));
-    }
-
     unsafe extern "C" {
-        fn TextToPhonemes(text: *const c_char) -> *const ::std::os::raw::c_char;
-        fn Initialize(data_dictlist: *const c_char);
+        fn CreateTranslator(data_dictlist: *const c_char) -> *mut c_void;
+        fn DestroyTranslator(tr: *mut c_void);
+        fn TextToPhonemes(tr: *mut c_void, text: *const c_char) -> *const c_char;
     }
 
-    unsafe {
-        static INIT: Once = Once::new();
-        INIT.call_once(|| {
+    /// espeak的转换器，保存词典索引和转换时的临时数据，同一时间只能被一个线程使用
+    struct Translator(NonNull<c_void>);
+
+    impl Translator {
+        fn new() -> Result<Self, G2PError> {
             static DATA: &[u8] = include_bytes!("../dict/espeak.dict");
-            Initialize(DATA.as_ptr() as _);
-        });
+            // SAFETY: DATA是静态数据，在转换器的整个生命周期内有效
+            let tr = unsafe { CreateTranslator(DATA.as_ptr() as _) };
+            NonNull::new(tr).map(Self).ok_or(G2PError::EnptyData)
+        }
 
-        let word = CString::new(word.to_lowercase())?.into_raw() as *const c_char;
-        let res = TextToPhonemes(word);
-        Ok(CStr::from_ptr(res).to_str()?.to_string())
+        fn phonemize(&mut self, word: &str) -> Result<String, G2PError> {
+            let word = CString::new(word)?;
+            // SAFETY: 返回的字符串属于转换器，在下一次调用前有效，这里立即复制出来
+            unsafe {
+                let res = TextToPhonemes(self.0.as_ptr(), word.as_ptr());
+                Ok(CStr::from_ptr(res).to_str()?.to_owned())
+            }
+        }
+    }
+
+    impl Drop for Translator {
+        fn drop(&mut self) {
+            // SAFETY: 指针由CreateTranslator创建，只释放一次
+            unsafe { DestroyTranslator(self.0.as_ptr()) }
+        }
     }
+
+    thread_local! {
+        static TRANSLATOR: RefCell<Option<Translator>> = const { RefCell::new(None) };
+    }
+
+    // espeak的词典缺少大多数所有格和缩约形式，因此拆分后再转换
+    if let Some(result) =
+        contraction::decompose(word, |i| word2ipa_en(i, PronunciationSelection::First))?
+    {
+        return Ok(result);
+    }
+
+    // 每个线程使用自己的转换器，线程结束时释放
+    TRANSLATOR.with_borrow_mut(|tr| {
+        let tr = match tr {
+            Some(tr) => tr,
+            None => tr.insert(Translator::new()?),
+        };
+        tr.phonemize(&word.to_lowercase())
+    })
 }
 
 fn to_half_shape(text: &str) -> String {
@@ -162,10 +313,18 @@ fn to_half_shape(text: &str) -> String {
     for c in chars {
         match c {
             // 处理需要后看的情况
-            '«' | '《' => result.push('“'),
-            '»' | '》' => result.push('”'),
-            '（' => result.push('('),
-            '）' => result.push(')'),
+            '«' | '《' | '「' | '『' | '‘' | '‚' | '„' | '‟' | '‹' => {
+                result.push('“')
+            }
+            '»' | '》' | '」' | '』' | '’' | '›' => result.push('”'),
+            '（' | '【' | '〖' | '〔' | '〈' | '［' | '｛' => result.push('('),
+            '）' | '】' | '〗' | '〕' | '〉' | '］' | '｝' => result.push(')'),
+            '–' | '‒' | '―' => result.push('—'),
+            '‥' => result.push('…'),
+            '।' | '॥' => result.push('.'),
+            // 零宽字符不发音
+            '\u{200B}'..='\u{200F}' | '\u{2060}' | '\u{FEFF}' => (),
+            _ if c.is_whitespace() => result.push(' '),
             // 简单替换规则
             '、' | '，' => result.push(','),
             '。' => result.push('.'),
@@ -182,91 +341,44 @@ fn to_half_shape(text: &str) -> String {
     result
 }
 
-fn num_repr(text: &str) -> Result<String, G2PError> {
-    let regex = Regex::new(r#"\d+(\.\d+)?"#)?;
-    Ok(regex
-        .replace(text, |caps: &Captures| {
-            let text = &caps[0];
-            if let Ok(num) = text.parse::<f64>() {
-                num.to_chinese(
-                    ChineseVariant::Traditional,
-                    ChineseCase::Lower,
-                    ChineseCountMethod::Low,
-                )
-                .map_or(text.to_owned(), |i| i)
-            } else if let Ok(num) = text.parse::<i64>() {
-                num.to_chinese(
-                    ChineseVariant::Traditional,
-                    ChineseCase::Lower,
-                    ChineseCountMethod::Low,
-                )
-                .map_or(text.to_owned(), |i| i)
-            } else {
-                text.to_owned()
-            }
-        })
-        .to_string())
+/// 把文本转换为音标，使用内置的英语、普通话和日语前端，汉字读作普通话
+pub fn g2p(text: &str, use_v11: bool) -> Result<String, G2PError> {
+    PhonemizerChain::new(use_v11).phonemize(text)
 }
 
-pub fn g2p(text: &str, use_v11: bool) -> Result<String, G2PError> {
-    let text = num_repr(text)?;
-    let sentence_pattern = Regex::new(
-        r#"([\u4E00-\u9FFF]+)|([，。：·？、！《》（）【】〖〗〔〕“”‘’〈〉…—　]+)|([\u0000-\u00FF]+)+"#,
-    )?;
-    let en_word_pattern = Regex::new("\\w+|\\W+")?;
-    let jieba = jieba_rs::Jieba::new();
-    let mut result = String::new();
-    for i in sentence_pattern.captures_iter(&text) {
-        match (i.get(1), i.get(2), i.get(3)) {
-            (Some(text), _, _) => {
-                let text = to_half_shape(text.as_str());
-                if use_v11 {
-                    if !result.is_empty() && !result.ends_with(' ') {
-                        result.push(' ');
-                    }
-                    result.push_str(&v11::g2p(&text, true));
-                    result.push(' ');
-                } else {
-                    for i in jieba.cut(&text, true) {
-                        result.push_str(&word2ipa_zh(i)?);
-                        result.push(' ');
-                    }
-                }
-            }
-            (_, Some(text), _) => {
-                let text = to_half_shape(text.as_str());
-                result = result.trim_end().to_string();
-                result.push_str(&text);
-                result.push(' ');
-            }
-            (_, _, Some(text)) => {
-                for i in en_word_pattern.captures_iter(text.as_str()) {
-                    let c = (i[0]).chars().next().unwrap_or_default();
-                    if c == '\''
-                        || c == '_'
-                        || c == '-'
-                        || c.is_ascii_lowercase()
-                        || c.is_ascii_uppercase()
-                    {
-                        let i = &i[0];
-                        if result.trim_end().ends_with(['.', ',', '!', '?'])
-                            && !result.ends_with(' ')
-                        {
-                            result.push(' ');
-                        }
-                        result.push_str(&word2ipa_en(i)?);
-                    } else if c == ' ' && result.ends_with(' ') {
-                        result.push_str((i[0]).trim_start());
-                    } else {
-                        result.push_str(&i[0]);
-                    }
-                }
-            }
-            _ => (),
-        };
-    }
+/// 把文本转换为音标，英语使用指定的口音
+///
+/// # 参数
+///
+/// * `text` - 要转换的文本。
+/// * `use_v11` - 是否为v1.1模型生成音标。
+/// * `dialect` - 英语的口音。
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::{EnglishDialect, g2p_with_dialect};
+///
+/// let us = g2p_with_dialect("tomato", false, EnglishDialect::American).unwrap();
+/// let gb = g2p_with_dialect("tomato", false, EnglishDialect::British).unwrap();
+/// assert_ne!(us, gb);
+/// ```
+///
+pub fn g2p_with_dialect(
+    text: &str,
+    use_v11: bool,
+    dialect: EnglishDialect,
+) -> Result<String, G2PError> {
+    PhonemizerChain::new(use_v11)
+        .set_english_dialect(dialect)
+        .phonemize(text)
+}
 
-    Ok(result.trim().to_string())
+/// 分析文本的音素化过程，使用内置的英语、普通话和日语前端
+///
+/// 详见`PhonemizerChain::analyze`。
+pub fn analyze(text: &str, use_v11: bool) -> Result<Analysis, G2PError> {
+    PhonemizerChain::new(use_v11).analyze(text)
 }
 
 #[cfg(test)]
@@ -274,16 +386,70 @@ mod tests {
     #[cfg(not(feature = "use-cmudict"))]
     #[test]
     fn test_word2ipa_en() -> Result<(), super::G2PError> {
-        use super::word2ipa_en;
+        use super::{PronunciationSelection, word2ipa_en};
 
         // println!("{:?}", espeak_rs::text_to_phonemes("days", "en", None, true, false));
-        assert_eq!("kjˌuːkjˈuː", word2ipa_en("qq")?);
-        assert_eq!("həlˈəʊ", word2ipa_en("hello")?);
-        assert_eq!("wˈɜːld", word2ipa_en("world")?);
-        assert_eq!("ˈapəl", word2ipa_en("apple")?);
-        assert_eq!("tʃˈɪldɹɛn", word2ipa_en("children")?);
-        assert_eq!("ˈaʊə", word2ipa_en("hour")?);
-        assert_eq!("dˈeɪz", word2ipa_en("days")?);
+        assert_eq!(
+            "kjˌuːkjˈuː",
+            word2ipa_en("qq", PronunciationSelection::First)?
+        );
+        assert_eq!(
+            "həlˈəʊ",
+            word2ipa_en("hello", PronunciationSelection::First)?
+        );
+        assert_eq!(
+            "wˈɜːld",
+            word2ipa_en("world", PronunciationSelection::First)?
+        );
+        assert_eq!(
+            "ˈapəl",
+            word2ipa_en("apple", PronunciationSelection::First)?
+        );
+        assert_eq!(
+            "tʃˈɪldɹɛn",
+            word2ipa_en("children", PronunciationSelection::First)?
+        );
+        assert_eq!("ˈaʊə", word2ipa_en("hour", PronunciationSelection::First)?);
+        assert_eq!("dˈeɪz", word2ipa_en("days", PronunciationSelection::First)?);
+        // 多个单词的词条（a while、I had）不匹配单个单词
+        assert_eq!("ˈeɪ", word2ipa_en("a", PronunciationSelection::First)?);
+        assert_eq!("ˈaɪ", word2ipa_en("i", PronunciationSelection::First)?);
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "use-cmudict"))]
+    #[test]
+    fn test_backend_en_threads() -> Result<(), super::G2PError> {
+        use super::{PronunciationSelection, backend_en};
+        use std::thread;
+
+        const WORDS: [&str; 8] = [
+            "hello", "world", "apple", "children", "hour", "days", "language", "through",
+        ];
+        let expected = WORDS
+            .iter()
+            .map(|i| backend_en(i, PronunciationSelection::First))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 每个线程以不同的顺序转换，绕过缓存直接调用espeak
+        let handles = (0..16)
+            .map(|t| {
+                thread::spawn(move || {
+                    (0..200)
+                        .map(|i| {
+                            let j = (i * 3 + t) % WORDS.len();
+                            backend_en(WORDS[j], PronunciationSelection::First).map(|r| (j, r))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            for (j, result) in handle.join().unwrap()? {
+                assert_eq!(expected[j], result, "{}", WORDS[j]);
+            }
+        }
 
         Ok(())
     }
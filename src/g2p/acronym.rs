@@ -0,0 +1,359 @@
+use {
+    super::{G2PError, arpabet_to_ipa},
+    crate::letters_to_ipa,
+    std::collections::HashMap,
+};
+
+/// 按单词读出的常见缩略词，ARPAbet
+const WORDS: [(&str, &str); 46] = [
+    ("AIDS", "EY1 D Z"),
+    ("ASAP", "EY1 S AE2 P"),
+    ("ASCII", "AE1 S K IY0"),
+    ("CAPTCHA", "K AE1 P CH AH0"),
+    ("CERN", "S ER1 N"),
+    ("COVID", "K OW1 V IH0 D"),
+    ("DOS", "D AO1 S"),
+    ("FEMA", "F IY1 M AH0"),
+    ("FIFA", "F IY1 F AH0"),
+    ("GIF", "G IH1 F"),
+    ("GUI", "G UW1 IY0"),
+    ("IKEA", "AY0 K IY1 AH0"),
+    ("IMAX", "AY1 M AE2 K S"),
+    ("INTERPOL", "IH1 N T ER0 P OW2 L"),
+    ("ISO", "AY1 S OW0"),
+    ("JPEG", "JH EY1 P EH2 G"),
+    ("JSON", "JH EY1 S AH0 N"),
+    ("LAN", "L AE1 N"),
+    ("LASER", "L EY1 Z ER0"),
+    ("LEGO", "L EH1 G OW0"),
+    ("NAFTA", "N AE1 F T AH0"),
+    ("NASA", "N AE1 S AH0"),
+    ("NASDAQ", "N AE1 Z D AE2 K"),
+    ("NATO", "N EY1 T OW0"),
+    ("NIMBY", "N IH1 M B IY0"),
+    ("NOAA", "N OW1 AH0"),
+    ("OPEC", "OW1 P EH2 K"),
+    ("PIN", "P IH1 N"),
+    ("POTUS", "P OW1 T AH0 S"),
+    ("RADAR", "R EY1 D AA2 R"),
+    ("RAM", "R AE1 M"),
+    ("ROM", "R AA1 M"),
+    ("SARS", "S AA1 R Z"),
+    ("SCOTUS", "S K OW1 T AH0 S"),
+    ("SCUBA", "S K UW1 B AH0"),
+    ("SIM", "S IH1 M"),
+    ("SONAR", "S OW1 N AA2 R"),
+    ("SWAT", "S W AA1 T"),
+    ("TED", "T EH1 D"),
+    ("TOEFL", "T OW1 F AH0 L"),
+    ("UEFA", "Y UW0 EY1 F AH0"),
+    ("UNESCO", "Y UW0 N EH1 S K OW0"),
+    ("UNICEF", "Y UW1 N AH0 S EH2 F"),
+    ("WAN", "W AE1 N"),
+    ("WiFi", "W AY1 F AY2"),
+    ("YAML", "Y AE1 M AH0 L"),
+];
+
+/// 缩略词的读法
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AcronymReading {
+    /// 按单词读出，例如NASA
+    Word,
+    /// 逐个字母读出，例如HTTPS
+    Spell,
+}
+
+/// 英语缩略词的读法设置
+///
+/// 内置英语前端把全部大写的缩略词（GPU、HTTPS）逐个字母读出，内置列表中的缩略词（NASA、NATO、COVID）按单词读出；小写形式是普通单词、超过三个字母并且有元音的大写单词视为强调，按普通单词读出，例如This is VERY important。大小写混合的单词按大小写和数字拆分后分别转换，例如iPhone读作i和phone，macOS读作mac和OS。整段文本都是大写时视为强调，按普通单词读出。
+///
+/// 设置的缩略词区分大小写，优先于内置列表。
+#[derive(Clone, Debug, Default)]
+pub struct Acronyms {
+    entries: HashMap<String, AcronymReading>,
+}
+
+impl Acronyms {
+    /// 创建只使用内置列表的设置
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{AcronymReading, Acronyms};
+    ///
+    /// let mut acronyms = Acronyms::new();
+    /// acronyms
+    ///     .insert("SIGGRAPH", AcronymReading::Word)
+    ///     .insert("NASA", AcronymReading::Spell);
+    /// assert_eq!(acronyms.get("NATO"), Some(AcronymReading::Word));
+    /// assert_eq!(acronyms.get("NASA"), Some(AcronymReading::Spell));
+    /// assert_eq!(acronyms.get("nasa"), None);
+    /// ```
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加或替换一个缩略词的读法
+    ///
+    /// # 参数
+    ///
+    /// * `acronym` - 缩略词，区分大小写。
+    /// * `reading` - 读法。
+    pub fn insert<W>(&mut self, acronym: W, reading: AcronymReading) -> &mut Self
+    where
+        W: Into<String>,
+    {
+        self.entries.insert(acronym.into(), reading);
+        self
+    }
+
+    /// 查找设置的或内置的读法，没有设置时返回`None`
+    pub fn get(&self, acronym: &str) -> Option<AcronymReading> {
+        self.entries.get(acronym).copied().or_else(|| {
+            WORDS
+                .iter()
+                .any(|(i, _)| *i == acronym)
+                .then_some(AcronymReading::Word)
+        })
+    }
+
+    /// 按设置读出一个缩略词，没有设置时返回`None`
+    fn lookup<F>(&self, acronym: &str, convert: &F) -> Result<Option<String>, G2PError>
+    where
+        F: Fn(&str) -> Result<String, G2PError>,
+    {
+        match self.entries.get(acronym) {
+            Some(AcronymReading::Word) => return convert(&acronym.to_lowercase()).map(Some),
+            Some(AcronymReading::Spell) => return Ok(Some(spell(acronym))),
+            None => (),
+        }
+        match WORDS.iter().find(|(i, _)| *i == acronym) {
+            Some((_, arpa)) => arpabet_to_ipa(arpa).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 全部大写的单词是否为缩略词：设置过读法的、不超过三个字母的、没有元音的，或者小写形式不是普通单词的
+    fn is_acronym<W>(&self, word: &str, is_word: &W) -> bool
+    where
+        W: Fn(&str) -> bool,
+    {
+        self.get(word).is_some()
+            || word.len() <= 3
+            || !word.contains(['A', 'E', 'I', 'O', 'U', 'Y'])
+            || !is_word(&word.to_lowercase())
+    }
+
+    /// 读出全部大写的部分，没有设置时逐个字母读出
+    fn upper<F>(&self, acronym: &str, convert: &F) -> Result<String, G2PError>
+    where
+        F: Fn(&str) -> Result<String, G2PError>,
+    {
+        Ok(self
+            .lookup(acronym, convert)?
+            .unwrap_or_else(|| spell(acronym)))
+    }
+
+    /// 转换可能是缩略词的单词
+    ///
+    /// # 参数
+    ///
+    /// * `word` - 单词，撇号应已统一为`'`。
+    /// * `shouting` - 整段文本是否都是大写，此时没有设置的大写单词按普通单词读出。
+    /// * `convert` - 转换普通单词的函数。
+    /// * `is_word` - 小写的单词是否为普通单词，是则把全部大写的写法视为强调。
+    ///
+    /// # 返回值
+    ///
+    /// 不是缩略词，应按普通单词转换时返回`None`。
+    pub(super) fn pronounce<F, W>(
+        &self,
+        word: &str,
+        shouting: bool,
+        convert: F,
+        is_word: W,
+    ) -> Result<Option<String>, G2PError>
+    where
+        F: Fn(&str) -> Result<String, G2PError>,
+        W: Fn(&str) -> bool,
+    {
+        if let Some(result) = self.lookup(word, &convert)? {
+            return Ok(Some(result));
+        }
+        // 复数和所有格：GPUs、NASA's
+        if let Some(base) = word.strip_suffix("'s").or_else(|| word.strip_suffix('s'))
+            && base.len() > 1
+            && is_upper(base)
+            && if shouting {
+                self.get(base).is_some()
+            } else {
+                self.is_acronym(base, &is_word)
+            }
+        {
+            return Ok(Some(plural(self.upper(base, &convert)?)));
+        }
+        if word.contains('\'') {
+            return Ok(None);
+        }
+
+        let parts = split(word);
+        if let [part] = parts[..] {
+            // 单独的A和I是冠词和代词
+            if !is_upper(part)
+                || shouting
+                || part == "A"
+                || part == "I"
+                || !self.is_acronym(part, &is_word)
+            {
+                return Ok(None);
+            }
+            return self.upper(part, &convert).map(Some);
+        }
+        let mut result = Vec::with_capacity(parts.len());
+        for part in parts {
+            result.push(if is_upper(part) {
+                self.upper(part, &convert)?
+            } else if part.chars().count() == 1 && part.starts_with(char::is_alphabetic) {
+                // 与其他部分相连的单个小写字母读作字母，例如iPhone的i
+                spell(part)
+            } else {
+                convert(part)?
+            });
+        }
+        Ok(Some(result.concat()))
+    }
+}
+
+/// 是否全部由大写字母组成
+fn is_upper(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// 逐个字母读出，只有最后一个字母带主重音
+fn spell(letters: &str) -> String {
+    let names = letters
+        .chars()
+        .map(|c| letters_to_ipa(&c.to_string()))
+        .filter(|i| !i.is_empty())
+        .collect::<Vec<_>>();
+    let last = names.len().saturating_sub(1);
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| match i < last {
+            true => name.replace('ˈ', "ˌ"),
+            false => name.to_owned(),
+        })
+        .collect()
+}
+
+/// 在读音后加上复数或所有格的`s`
+fn plural(mut phonemes: String) -> String {
+    let last = phonemes
+        .chars()
+        .rev()
+        .find(|c| !['ˈ', 'ˌ', 'ː'].contains(c))
+        .unwrap_or_default();
+    phonemes.push_str(match last {
+        c if "szʃʒʧʤ".contains(c) => "ᵻz",
+        c if "ptkfθ".contains(c) => "s",
+        _ => "z",
+    });
+    phonemes
+}
+
+/// 按大小写和数字把单词拆分为几个部分
+///
+/// 小写字母后面的大写字母（macOS）、大写字母串中最后一个后面跟着小写字母的大写字母（XMLHttp）、字母和数字之间都是分界；Mc与后面的部分不拆分（McDonald）。
+fn split(word: &str) -> Vec<&str> {
+    let chars = word.char_indices().collect::<Vec<_>>();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (position, c) = chars[i];
+        let prev = chars[i - 1].1;
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        let boundary = (prev.is_lowercase() && c.is_uppercase())
+            || (prev.is_uppercase() && c.is_uppercase() && next.is_some_and(char::is_lowercase))
+            || prev.is_ascii_digit() != c.is_ascii_digit();
+        if boundary && &word[start..position] != "Mc" {
+            parts.push(&word[start..position]);
+            start = position;
+        }
+    }
+    parts.push(&word[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pronounce(acronyms: &Acronyms, word: &str, shouting: bool) -> Option<String> {
+        let convert = |i: &str| Ok(format!("<{}>", i));
+        let is_word = |i: &str| ["very", "hello", "year", "years", "ieee"].contains(&i);
+        acronyms
+            .pronounce(word, shouting, convert, is_word)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(vec!["i", "Phone"], split("iPhone"));
+        assert_eq!(vec!["mac", "OS"], split("macOS"));
+        assert_eq!(vec!["XML", "Http", "Request"], split("XMLHttpRequest"));
+        assert_eq!(vec!["MP", "3"], split("MP3"));
+        assert_eq!(vec!["McDonald"], split("McDonald"));
+        assert_eq!(vec!["HTTPS"], split("HTTPS"));
+    }
+
+    #[test]
+    fn test_spell() {
+        assert_eq!("ʤˌipˌijˈu", spell("GPU"));
+        assert_eq!("ˈA", spell("a"));
+        assert_eq!("ˈAʧᵻz", plural(spell("H")));
+    }
+
+    #[test]
+    fn test_pronounce() {
+        let mut acronyms = Acronyms::new();
+        assert_eq!(None, pronounce(&acronyms, "hello", false));
+        assert_eq!(None, pronounce(&acronyms, "Hello", false));
+        assert_eq!(None, pronounce(&acronyms, "A", false));
+        assert_eq!(None, pronounce(&acronyms, "I'm", false));
+        assert_eq!(Some(spell("HTTPS")), pronounce(&acronyms, "HTTPS", false));
+        assert_eq!(None, pronounce(&acronyms, "HTTPS", true));
+        // 小写形式是普通单词的大写单词是强调
+        assert_eq!(None, pronounce(&acronyms, "VERY", false));
+        assert_eq!(None, pronounce(&acronyms, "YEARS", false));
+        assert_eq!(Some(spell("CPU")), pronounce(&acronyms, "CPU", false));
+        assert_eq!(Some(spell("HTML")), pronounce(&acronyms, "HTML", false));
+        assert_eq!(Some(spell("AMEX")), pronounce(&acronyms, "AMEX", false));
+        assert_eq!(
+            arpabet_to_ipa("N EY1 T OW0").ok(),
+            pronounce(&acronyms, "NATO", false)
+        );
+        assert_eq!(
+            Some(format!("{}z", spell("GPU"))),
+            pronounce(&acronyms, "GPUs", false)
+        );
+        assert_eq!(
+            Some(format!("{}<Phone>", spell("i"))),
+            pronounce(&acronyms, "iPhone", false)
+        );
+        assert_eq!(
+            Some(format!("<mac>{}", spell("OS"))),
+            pronounce(&acronyms, "macOS", false)
+        );
+
+        acronyms
+            .insert("NATO", AcronymReading::Spell)
+            .insert("SIGGRAPH", AcronymReading::Word);
+        assert_eq!(Some(spell("NATO")), pronounce(&acronyms, "NATO", false));
+        assert_eq!(
+            Some("<siggraph>".to_owned()),
+            pronounce(&acronyms, "SIGGRAPH", true)
+        );
+    }
+}
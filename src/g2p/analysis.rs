@@ -0,0 +1,83 @@
+use super::Script;
+
+/// 音标的来源
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PhonemeSource {
+    /// 文本中的发音标记
+    Markup,
+    /// 用户词典
+    Lexicon,
+    /// 音素化前端
+    Phonemizer,
+    /// 标点，转换为半角后原样输出
+    Punctuation,
+    /// 没有注册前端，按`Fallback`转写或替换后读出
+    Fallback,
+}
+
+/// 单词的分析结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct Word {
+    /// 单词，数字会先转换为汉字，因此可能与原文不同
+    pub text: String,
+    /// 单词在输入文本中的字节偏移
+    pub offset: usize,
+    /// 分词器给出的词性，例如jieba的`n`、`v`，没有时为`None`
+    pub tag: Option<String>,
+    /// 单词的音标
+    pub phonemes: String,
+    /// 音标的来源
+    pub source: PhonemeSource,
+}
+
+/// 按文字类别切分后的一段文本
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    /// 原文中的这一段文本
+    pub text: String,
+    /// 在输入文本中的字节偏移
+    pub offset: usize,
+    /// 文字类别，标点为`None`
+    pub script: Option<Script>,
+    /// 分词结果
+    pub words: Vec<Word>,
+}
+
+/// 字符被丢弃的原因
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DropReason {
+    /// 不属于任何支持的文字类别
+    UnsupportedScript,
+    /// 该文字类别没有注册前端
+    NoPhonemizer,
+    /// 前端无法转换，输出了`❓`
+    Unknown,
+    /// 前端输出的音标符号不在模型的词表中，`character`是该音标符号，`offset`是所在单词的偏移
+    NotInVocab,
+}
+
+/// 被丢弃的字符
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Dropped {
+    /// 字符
+    pub character: char,
+    /// 在输入文本中的字节偏移
+    pub offset: usize,
+    /// 原因
+    pub reason: DropReason,
+}
+
+/// 音素化的分析结果
+///
+/// 通过`PhonemizerChain::analyze`或`KokoroTts::analyze`获得，用于排查合成结果发音不对的原因。
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Analysis {
+    /// 文本的切分结果
+    pub segments: Vec<Segment>,
+    /// 最终的音标，与合成时使用的音标相同
+    pub phonemes: String,
+    /// 最终的词元，包括首尾的填充符
+    pub tokens: Vec<i64>,
+    /// 被丢弃或转换为`❓`的字符
+    pub dropped: Vec<Dropped>,
+}
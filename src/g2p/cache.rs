@@ -0,0 +1,97 @@
+use {
+    super::G2PError,
+    std::{collections::HashMap, hash::Hash, mem::take, sync::Mutex},
+};
+
+/// 每个前端缓存的单词数
+pub(super) const CAPACITY: usize = 4096;
+
+/// 容量有限的近似LRU缓存
+///
+/// 条目分为新旧两代，新一代满了以后整体变为旧一代，原来的旧一代被丢弃；旧一代中被访问的条目会移回新一代。因此被丢弃的总是较久没有使用的条目，查找和插入都不需要遍历。
+#[derive(Debug)]
+pub(super) struct Cache<K, V> {
+    capacity: usize,
+    recent: HashMap<K, V>,
+    old: HashMap<K, V>,
+}
+
+impl<K: Eq + Hash, V: Clone> Cache<K, V> {
+    /// 创建最多保存`capacity`个条目的缓存
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            recent: HashMap::new(),
+            old: HashMap::new(),
+        }
+    }
+
+    pub(super) fn get(&mut self, key: &K) -> Option<V> {
+        if let Some(value) = self.recent.get(key) {
+            return Some(value.clone());
+        }
+        let (key, value) = self.old.remove_entry(key)?;
+        self.insert(key, value.clone());
+        Some(value)
+    }
+
+    pub(super) fn insert(&mut self, key: K, value: V) {
+        if self.recent.len() >= self.capacity.div_ceil(2) {
+            self.old = take(&mut self.recent);
+        }
+        self.recent.insert(key, value);
+    }
+
+    /// 丢弃所有条目
+    pub(super) fn clear(&mut self) {
+        self.recent.clear();
+        self.old.clear();
+    }
+}
+
+/// 先在缓存中查找，找不到时调用`convert`并缓存结果
+///
+/// 转换期间不持有锁，因此`convert`可以递归地使用同一个缓存。
+pub(super) fn memoize<K, F>(
+    cache: &Mutex<Cache<K, String>>,
+    key: K,
+    convert: F,
+) -> Result<String, G2PError>
+where
+    K: Eq + Hash,
+    F: FnOnce() -> Result<String, G2PError>,
+{
+    // 锁中毒时不使用缓存
+    if let Some(value) = cache.lock().ok().and_then(|mut i| i.get(&key)) {
+        return Ok(value);
+    }
+    let value = convert()?;
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(key, value.clone());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache() {
+        let mut cache = Cache::new(4);
+        for i in 0..4 {
+            cache.insert(i, i * 10);
+        }
+        // 访问过的条目移回新一代，不会被丢弃
+        assert_eq!(Some(0), cache.get(&0));
+        for i in 4..6 {
+            cache.insert(i, i * 10);
+        }
+        assert_eq!(Some(0), cache.get(&0));
+        assert_eq!(None, cache.get(&1));
+        assert_eq!(None, cache.get(&2));
+
+        cache.clear();
+        assert_eq!(None, cache.get(&0));
+    }
+}
@@ -0,0 +1,156 @@
+use super::{G2PError, arpabet_to_ipa};
+
+/// 常见的缩约形式，它们的读音不能由词干和后缀直接拼出，ARPAbet
+const CONTRACTIONS: [(&str, &str); 48] = [
+    ("i'm", "AY1 M"),
+    ("i've", "AY1 V"),
+    ("i'll", "AY1 L"),
+    ("i'd", "AY1 D"),
+    ("you're", "Y UH1 R"),
+    ("you've", "Y UW1 V"),
+    ("you'll", "Y UW1 L"),
+    ("you'd", "Y UW1 D"),
+    ("he's", "HH IY1 Z"),
+    ("he'll", "HH IY1 L"),
+    ("he'd", "HH IY1 D"),
+    ("she's", "SH IY1 Z"),
+    ("she'll", "SH IY1 L"),
+    ("she'd", "SH IY1 D"),
+    ("it's", "IH1 T S"),
+    ("it'll", "IH1 T AH0 L"),
+    ("we're", "W IH1 R"),
+    ("we've", "W IY1 V"),
+    ("we'll", "W IY1 L"),
+    ("we'd", "W IY1 D"),
+    ("they're", "DH EH1 R"),
+    ("they've", "DH EY1 V"),
+    ("they'll", "DH EY1 L"),
+    ("they'd", "DH EY1 D"),
+    ("that's", "DH AE1 T S"),
+    ("there's", "DH EH1 R Z"),
+    ("what's", "W AH1 T S"),
+    ("let's", "L EH1 T S"),
+    ("don't", "D OW1 N T"),
+    ("won't", "W OW1 N T"),
+    ("can't", "K AE1 N T"),
+    ("ain't", "EY1 N T"),
+    ("shan't", "SH AE1 N T"),
+    ("isn't", "IH1 Z AH0 N T"),
+    ("aren't", "AA1 R N T"),
+    ("wasn't", "W AA1 Z AH0 N T"),
+    ("weren't", "W ER1 N T"),
+    ("doesn't", "D AH1 Z AH0 N T"),
+    ("didn't", "D IH1 D AH0 N T"),
+    ("hasn't", "HH AE1 Z AH0 N T"),
+    ("haven't", "HH AE1 V AH0 N T"),
+    ("hadn't", "HH AE1 D AH0 N T"),
+    ("couldn't", "K UH1 D AH0 N T"),
+    ("wouldn't", "W UH1 D AH0 N T"),
+    ("shouldn't", "SH UH1 D AH0 N T"),
+    ("mustn't", "M AH1 S AH0 N T"),
+    ("needn't", "N IY1 D AH0 N T"),
+    ("o'clock", "AH0 K L AA1 K"),
+];
+
+/// 可以从词尾拆出的后缀
+const SUFFIXES: [&str; 7] = ["n't", "'s", "'ll", "'re", "'ve", "'d", "'m"];
+
+/// 查询常见缩约形式的读音
+///
+/// # 参数
+///
+/// * `word` - 单词，不区分大小写，撇号应已统一为`'`。
+///
+/// # 返回值
+///
+/// 不是常见的缩约形式时返回`None`。
+pub(super) fn lookup(word: &str) -> Result<Option<String>, G2PError> {
+    let word = word.to_lowercase();
+    match CONTRACTIONS.iter().find(|(i, _)| *i == word) {
+        Some((_, arpa)) => arpabet_to_ipa(arpa).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 把缩约形式或所有格拆分为词干和后缀，转换词干后拼上后缀的读音
+///
+/// 例如`John's`读作`John`加上`z`，`cat's`读作`cat`加上`s`。
+///
+/// # 参数
+///
+/// * `word` - 单词，撇号应已统一为`'`。
+/// * `stem` - 转换词干的函数。
+///
+/// # 返回值
+///
+/// 单词不以可拆分的后缀结尾时返回`None`。
+pub(super) fn decompose<F>(word: &str, stem: F) -> Result<Option<String>, G2PError>
+where
+    F: FnOnce(&str) -> Result<String, G2PError>,
+{
+    let lower = word.to_lowercase();
+    let Some(suffix) = SUFFIXES.iter().find(|i| lower.ends_with(*i)) else {
+        return Ok(None);
+    };
+    let base = &word[..word.len() - suffix.len()];
+    if base.is_empty() || base.contains('\'') {
+        return Ok(None);
+    }
+
+    let mut result = stem(base)?;
+    // 忽略重音和长音符号，取词干的最后一个音
+    let last = result
+        .chars()
+        .rev()
+        .find(|c| !['ˈ', 'ˌ', 'ː'].contains(c))
+        .unwrap_or_default();
+    result.push_str(match *suffix {
+        "'s" if "szʃʒ".contains(last) => "ɪz",
+        "'s" if "ptkfθ".contains(last) => "s",
+        "'s" => "z",
+        "n't" if "aeiouæɑɒɔəɛɜɪʊʌ".contains(last) => "nt",
+        "n't" => "ənt",
+        "'ll" => "l",
+        "'re" => "ɹ",
+        "'ve" => "v",
+        "'d" => "d",
+        _ => "m",
+    });
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() -> Result<(), G2PError> {
+        assert_eq!(Some("dˈoʊnt".to_owned()), lookup("Don't")?);
+        assert_eq!(lookup("we'll")?, lookup("We'll")?);
+        assert_eq!(None, lookup("John's")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompose() -> Result<(), G2PError> {
+        let stem = |i: &str| {
+            Ok(match i {
+                "John" => "dʒˈɒn",
+                "cat" => "kˈat",
+                "bus" => "bˈʌs",
+                "could" => "kˈʊd",
+                _ => "",
+            }
+            .to_owned())
+        };
+        assert_eq!(Some("dʒˈɒnz".to_owned()), decompose("John's", stem)?);
+        assert_eq!(Some("kˈats".to_owned()), decompose("cat's", stem)?);
+        assert_eq!(Some("bˈʌsɪz".to_owned()), decompose("bus's", stem)?);
+        assert_eq!(Some("kˈʊdənt".to_owned()), decompose("couldn't", stem)?);
+        assert_eq!(None, decompose("o'clock", stem)?);
+        assert_eq!(None, decompose("'s", stem)?);
+
+        Ok(())
+    }
+}
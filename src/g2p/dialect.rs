@@ -0,0 +1,310 @@
+use {
+    super::{EnglishDialect, Lexicon, misaki},
+    std::sync::LazyLock,
+};
+
+/// 英式英语读作ɑː、美式英语读作æ的单词（TRAP/BATH分化）
+const BATH: [&str; 57] = [
+    "advance",
+    "advantage",
+    "after",
+    "afternoon",
+    "answer",
+    "ask",
+    "aunt",
+    "banana",
+    "basket",
+    "bath",
+    "branch",
+    "brass",
+    "calf",
+    "can't",
+    "cast",
+    "castle",
+    "chance",
+    "chant",
+    "class",
+    "command",
+    "craft",
+    "dance",
+    "demand",
+    "disaster",
+    "draft",
+    "example",
+    "fast",
+    "flask",
+    "ghastly",
+    "glance",
+    "glass",
+    "graph",
+    "grass",
+    "half",
+    "laugh",
+    "last",
+    "mask",
+    "master",
+    "nasty",
+    "pass",
+    "past",
+    "pastor",
+    "path",
+    "plant",
+    "plaster",
+    "rather",
+    "raft",
+    "sample",
+    "shaft",
+    "slant",
+    "staff",
+    "task",
+    "vast",
+    "aghast",
+    "blast",
+    "grant",
+    "lance",
+];
+/// BATH词可以带的词尾
+const SUFFIXES: [&str; 8] = ["s", "es", "ed", "d", "ing", "er", "ers", "y"];
+
+/// 判断单词是否属于BATH词，包括带常见词尾的形式，例如asked、dancing
+fn is_bath(word: &str) -> bool {
+    let word = word.to_lowercase();
+    BATH.iter().any(|stem| {
+        let Some(rest) = word.strip_prefix(stem) else {
+            // 去掉词干末尾的e再加词尾，例如dance -> dancing
+            return stem.strip_suffix('e').is_some_and(|stem| {
+                word.strip_prefix(stem)
+                    .is_some_and(|rest| ["ing", "ed", "er", "ers"].contains(&rest))
+            });
+        };
+        rest.is_empty() || SUFFIXES.contains(&rest)
+    })
+}
+
+/// 按口音调整BATH词的元音
+///
+/// 英式英语把重读的`a`改为`ɑː`，美式英语把重读的`ɑ`改为`æ`，音标应已转换为对应口音的misaki音标。
+///
+/// # 参数
+///
+/// * `word` - 单词。
+/// * `phonemes` - 单词的音标。
+/// * `dialect` - 口音。
+pub(super) fn apply_bath(word: &str, phonemes: &str, dialect: EnglishDialect) -> String {
+    if !is_bath(word) {
+        return phonemes.to_owned();
+    }
+    let (from, to) = match dialect {
+        EnglishDialect::American => ("ɑ", "æ"),
+        EnglishDialect::British => ("a", "ɑː"),
+    };
+    // 优先修改主重音后面的第一个元音
+    let start = phonemes.find('ˈ').unwrap_or_default();
+    let Some(position) = phonemes[start..]
+        .find(from)
+        .map(|i| start + i)
+        .or_else(|| phonemes.find(from))
+    else {
+        return phonemes.to_owned();
+    };
+    let rest = &phonemes[position + from.len()..];
+    // 英式的ɑ后面已经有长音符号，美式的ɑ后面不会有
+    let rest = match dialect {
+        EnglishDialect::American => rest.strip_prefix('ː').unwrap_or(rest),
+        EnglishDialect::British => rest,
+    };
+    format!("{}{}{}", &phonemes[..position], to, rest)
+}
+
+fn lexicon_data(dialect: EnglishDialect) -> &'static str {
+    match dialect {
+        EnglishDialect::American => include_str!("../../dict/en_us.tsv"),
+        EnglishDialect::British => include_str!("../../dict/en_gb.tsv"),
+    }
+}
+
+/// 拼写中的元音字母，不在元音前的`y`也算元音
+fn is_vowel_letter(word: &[char], i: usize) -> bool {
+    match word[i] {
+        'a' | 'e' | 'i' | 'o' | 'u' => true,
+        'y' => i > 0 && !word.get(i + 1).is_some_and(|c| "aeiou".contains(*c)),
+        _ => false,
+    }
+}
+
+/// 拼写中的元音字母组，每组记录后面是否紧跟元音后的r（r后面是词尾、辅音或不发音的词尾e）
+fn spelling_groups(word: &[char]) -> Vec<bool> {
+    let mut groups = Vec::new();
+    let mut i = 0;
+    while i < word.len() {
+        if !is_vowel_letter(word, i) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < word.len() && is_vowel_letter(word, i) {
+            i += 1;
+        }
+        groups.push((start, i));
+    }
+    // 辅音后面的词尾e、es、ed不发音
+    let mut silent = word.len();
+    if let [.., (_, prev), (start, end)] = groups[..]
+        && end - start == 1
+        && word[start] == 'e'
+        && start > prev
+        && matches!(word[end..], [] | ['s'] | ['d'])
+    {
+        groups.pop();
+        silent = start;
+    }
+    groups
+        .iter()
+        .map(|&(_, end)| {
+            let mut j = end;
+            while word.get(j) == Some(&'r') {
+                j += 1;
+            }
+            j > end && (j >= silent || !is_vowel_letter(word, j))
+        })
+        .collect()
+}
+
+/// 美式英语在元音后补上拼写中的`r`
+///
+/// espeak的英语音标不带元音后的r，转换为美式misaki音标后car读作`kˈɑ`。按拼写中的元音字母组和音标中的元音逐个对应，在拼写为元音加r、后面没有元音的位置补上`ɹ`，aɪ、aʊ后面补`əɹ`；两者个数不同时只对应第一个和最后一个元音。
+///
+/// # 参数
+///
+/// * `word` - 单词。
+/// * `phonemes` - 单词的misaki音标。
+/// * `dialect` - 口音，英式英语不作修改。
+pub(super) fn apply_rhotic(word: &str, phonemes: &str, dialect: EnglishDialect) -> String {
+    if dialect == EnglishDialect::British {
+        return phonemes.to_owned();
+    }
+    let letters = word.to_lowercase().chars().collect::<Vec<_>>();
+    let groups = spelling_groups(&letters);
+    let chars = phonemes.chars().collect::<Vec<_>>();
+    let mut nuclei = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if misaki::VOWELS.contains(chars[i]) {
+            let start = i;
+            while i < chars.len() && misaki::VOWELS.contains(chars[i]) {
+                i += 1;
+            }
+            nuclei.push((start, i));
+        } else {
+            i += 1;
+        }
+    }
+    if groups.is_empty() || nuclei.is_empty() {
+        return phonemes.to_owned();
+    }
+
+    let pairs = if groups.len() == nuclei.len() {
+        groups
+            .iter()
+            .copied()
+            .zip(nuclei.iter().copied())
+            .collect::<Vec<_>>()
+    } else {
+        let mut pairs = vec![(groups[0], nuclei[0])];
+        if groups.len() > 1 && nuclei.len() > 1 {
+            pairs.push((groups[groups.len() - 1], nuclei[nuclei.len() - 1]));
+        }
+        pairs
+    };
+    let mut ret = chars.clone();
+    for (_, (start, end)) in pairs.into_iter().rev().filter(|&(r, _)| r) {
+        if chars.get(end) == Some(&'ɹ') {
+            continue;
+        }
+        if end - start > 1 && chars[end - 1] == 'ɜ' {
+            ret[end - 1] = 'ə';
+            ret.insert(end, 'ɹ');
+        } else if matches!(chars[end - 1], 'I' | 'W') {
+            ret.splice(end..end, ['ə', 'ɹ']);
+        } else {
+            ret.insert(end, 'ɹ');
+        }
+    }
+
+    ret.into_iter().collect()
+}
+
+/// 内置的口音词典，包括该口音特有的读法，例如英式的schedule、美式的tomato
+pub(super) fn builtin_lexicon(dialect: EnglishDialect) -> &'static Lexicon {
+    static US: LazyLock<Lexicon> = LazyLock::new(|| {
+        Lexicon::from_tsv(lexicon_data(EnglishDialect::American)).unwrap_or_default()
+    });
+    static GB: LazyLock<Lexicon> = LazyLock::new(|| {
+        Lexicon::from_tsv(lexicon_data(EnglishDialect::British)).unwrap_or_default()
+    });
+    match dialect {
+        EnglishDialect::American => &US,
+        EnglishDialect::British => &GB,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::G2PError, *};
+
+    #[test]
+    fn test_apply_bath() {
+        use EnglishDialect::*;
+
+        assert_eq!("bˈɑːθ", apply_bath("bath", "bˈaθ", British));
+        assert_eq!("bˈæθ", apply_bath("bath", "bˈɑθ", American));
+        assert_eq!("ɪɡzˈɑːmpəl", apply_bath("example", "ɪɡzˈampəl", British));
+        assert_eq!("dˈɑːnsɪŋ", apply_bath("Dancing", "dˈansɪŋ", British));
+        assert_eq!("ˈɑːsk", apply_bath("asked", "ˈɑːsk", British));
+        // 不属于BATH词
+        assert_eq!("kˈat", apply_bath("cat", "kˈat", British));
+        assert_eq!("mˈas", apply_bath("mass", "mˈas", British));
+    }
+
+    #[test]
+    fn test_apply_rhotic() {
+        use EnglishDialect::*;
+
+        assert_eq!("kˈɑɹ", apply_rhotic("car", "kˈɑ", American));
+        assert_eq!("mˈʌðəɹ", apply_rhotic("mother", "mˈʌðə", American));
+        assert_eq!("fˈɔɹwəɹd", apply_rhotic("forward", "fˈɔwəd", American));
+        assert_eq!("mˈɔɹ", apply_rhotic("more", "mˈɔ", American));
+        assert_eq!("fˈIəɹ", apply_rhotic("fire", "fˈIɜ", American));
+        assert_eq!("ˈWəɹ", apply_rhotic("hour", "ˈWə", American));
+        assert_eq!("kˈɛɹd", apply_rhotic("cared", "kˈɛd", American));
+        // r在元音前或已经有ɹ
+        assert_eq!("kˈæɹi", apply_rhotic("carry", "kˈæɹi", American));
+        assert_eq!("ɹˈɛd", apply_rhotic("red", "ɹˈɛd", American));
+        assert_eq!("wˈɔtəɹ", apply_rhotic("water", "wˈɔtəɹ", American));
+        assert_eq!("kˈɑː", apply_rhotic("car", "kˈɑː", British));
+    }
+
+    #[test]
+    fn test_builtin_lexicon() -> Result<(), G2PError> {
+        for (dialect, inventory) in [
+            (EnglishDialect::American, misaki::US_INVENTORY),
+            (EnglishDialect::British, misaki::GB_INVENTORY),
+        ] {
+            let data = lexicon_data(dialect);
+            assert_eq!(
+                Lexicon::from_tsv(data)?.len(),
+                builtin_lexicon(dialect).len()
+            );
+            for line in data.lines().filter(|i| !i.starts_with('#')) {
+                let (_, ipa) = line.split_once('\t').unwrap_or_default();
+                assert!(ipa.chars().all(|c| inventory.contains(c)), "{}", line);
+            }
+        }
+        assert_ne!(
+            builtin_lexicon(EnglishDialect::American).get("tomato"),
+            builtin_lexicon(EnglishDialect::British).get("tomato")
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,837 @@
+use {
+    super::{G2PError, Lexicon, Pronunciation, romance::Phone},
+    regex::Match,
+    std::sync::LazyLock,
+};
+
+/// 鼻化元音的附加符号
+const TILDE: char = '\u{0303}';
+
+/// 不重读的虚词
+const UNSTRESSED: [&str; 52] = [
+    "le", "la", "les", "de", "du", "des", "un", "une", "et", "ou", "à", "au", "aux", "en", "je",
+    "tu", "il", "elle", "on", "nous", "vous", "ils", "elles", "me", "te", "se", "ce", "ne", "que",
+    "qui", "mon", "ton", "son", "ma", "ta", "sa", "mes", "tes", "ses", "nos", "vos", "leur",
+    "leurs", "par", "pour", "sur", "sous", "dans", "sans", "chez", "mais", "cet",
+];
+
+/// 后面的单词以元音开头时必须连诵的词
+const LIAISON: [&str; 33] = [
+    "les", "des", "ces", "mes", "tes", "ses", "nos", "vos", "leurs", "aux", "un", "aucun", "mon",
+    "ton", "son", "en", "on", "nous", "vous", "ils", "elles", "dans", "chez", "sans", "sous",
+    "très", "plus", "tout", "quand", "dont", "est", "vingt", "bien",
+];
+
+/// 只在后面的名词或形容词前连诵的形容词和数词，包括复数和阴性复数形式，例如grand arbre、grands enfants连诵，grand et不连诵
+const PRENOMINAL: [&str; 42] = [
+    "petit",
+    "petits",
+    "petites",
+    "grand",
+    "grands",
+    "grandes",
+    "gros",
+    "grosses",
+    "bons",
+    "bonnes",
+    "mauvais",
+    "mauvaises",
+    "beaux",
+    "belles",
+    "vieux",
+    "vieilles",
+    "nouveaux",
+    "nouvelles",
+    "anciens",
+    "anciennes",
+    "autres",
+    "premier",
+    "premiers",
+    "premières",
+    "dernier",
+    "derniers",
+    "dernières",
+    "certains",
+    "certaines",
+    "plusieurs",
+    "quelques",
+    "nombreux",
+    "nombreuses",
+    "deux",
+    "trois",
+    "six",
+    "dix",
+    "cent",
+    "cents",
+    "quels",
+    "quelles",
+    "jeunes",
+];
+
+/// 以元音开头、不是名词或形容词的常用词
+const NOT_NOMINAL: [&str; 28] = [
+    "est", "a", "as", "ai", "ont", "avait", "avaient", "était", "étaient", "eu", "été", "ici",
+    "ainsi", "alors", "aussi", "encore", "avec", "après", "avant", "entre", "où", "y", "ou", "et",
+    "à", "en", "il", "elle",
+];
+
+/// 以嘘音h开头或习惯上不连诵的单词
+const ASPIRATED: [&str; 28] = [
+    "haut",
+    "haute",
+    "hauts",
+    "hautes",
+    "héros",
+    "hache",
+    "haricot",
+    "haricots",
+    "hibou",
+    "hiboux",
+    "honte",
+    "hors",
+    "huit",
+    "huitième",
+    "onze",
+    "onzième",
+    "oui",
+    "hasard",
+    "hall",
+    "hamac",
+    "hangar",
+    "hanche",
+    "handicap",
+    "harpe",
+    "hockey",
+    "homard",
+    "hongrie",
+    "yaourt",
+];
+
+/// 以s、x结尾但不是复数名词的副词和介词
+const ADVERBS: [&str; 10] = [
+    "jamais", "toujours", "moins", "assez", "après", "depuis", "vers", "alors", "ailleurs",
+    "parfois",
+];
+
+/// 省略了元音的虚词及其读音，例如l'ami、qu'il
+const ELISION: [(&str, &str); 13] = [
+    ("l", "l"),
+    ("d", "d"),
+    ("j", "ʒ"),
+    ("m", "m"),
+    ("t", "t"),
+    ("s", "s"),
+    ("c", "s"),
+    ("n", "n"),
+    ("qu", "k"),
+    ("jusqu", "ʒysk"),
+    ("lorsqu", "lɔʁsk"),
+    ("puisqu", "pɥisk"),
+    ("quoiqu", "kwak"),
+];
+
+/// 内置的例外词典，包括词尾辅音发音的词和读法不规则的词
+fn exceptions() -> &'static Lexicon {
+    static EXCEPTIONS: LazyLock<Lexicon> =
+        LazyLock::new(|| Lexicon::from_tsv(include_str!("../../dict/fr.tsv")).unwrap_or_default());
+    &EXCEPTIONS
+}
+
+/// 是否为元音字母，包括带附加符号的
+fn is_vowel(c: char) -> bool {
+    "aeiouyàâäéèêëîïôöùûüœæ".contains(c)
+}
+
+/// 是否为e、i、y及带附加符号的e、i，c、g在它们前面读作s、ʒ
+fn is_front(c: Option<&char>) -> bool {
+    c.is_some_and(|c| "eiyéèêëîï".contains(*c))
+}
+
+/// 去掉词尾不发音的辅音后的字母，以及词尾e的读音
+struct Spelling {
+    chars: Vec<char>,
+    /// 词尾的e因为后面不发音的辅音而读出的元音，例如-ez、-er读作e，-et读作ɛ
+    final_e: Option<&'static str>,
+    /// 词尾的e是否不发音
+    silent_e: bool,
+}
+
+impl Spelling {
+    /// `verb`表示词尾的-ent是动词的词尾，不发音
+    fn new(word: &str, verb: bool) -> Self {
+        let mut chars = word
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .collect::<Vec<_>>();
+        let mut final_e = None;
+        if verb && chars.len() > 3 && chars.ends_with(&['e', 'n', 't']) {
+            // ils parlent
+            chars.truncate(chars.len() - 2);
+        } else {
+            // 词尾的s、x、z、t、d、p、g不发音
+            let mut stripped = None;
+            while chars.len() > 1
+                && let Some(c) = chars.last().filter(|c| "sxztdpg".contains(**c))
+            {
+                stripped = Some(*c);
+                chars.pop();
+            }
+            if chars.ends_with(&['n', 'c']) {
+                chars.pop();
+            } else if chars.len() > 3
+                && chars.ends_with(&['e', 'r'])
+                && matches!(stripped, None | Some('s'))
+            {
+                // parler、premiers
+                chars.pop();
+                stripped = Some('r');
+            }
+            if chars.last() == Some(&'e') {
+                final_e = match stripped {
+                    Some('z' | 'd' | 'r') => Some("e"),
+                    Some('t') => Some("ɛ"),
+                    _ => None,
+                };
+            }
+        }
+        // 只有一个元音的词（le、que）中的e读作ə
+        let silent_e = chars.last() == Some(&'e')
+            && final_e.is_none()
+            && chars[..chars.len() - 1]
+                .iter()
+                .enumerate()
+                .any(|(i, c)| is_vowel(*c) && !(*c == 'u' && i > 0 && chars[i - 1] == 'q'));
+        Self {
+            chars,
+            final_e,
+            silent_e,
+        }
+    }
+
+    fn at(&self, i: usize, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(k, c)| self.chars.get(i + k) == Some(&c))
+    }
+
+    fn is_letter_vowel(&self, i: usize) -> bool {
+        self.chars.get(i).is_some_and(|c| is_vowel(*c))
+    }
+
+    /// 该位置是否为发音的元音字母
+    fn is_sounding_vowel(&self, i: usize) -> bool {
+        self.is_letter_vowel(i) && !(self.silent_e && i + 1 == self.chars.len())
+    }
+
+    /// 该位置的m、n后面没有元音时，前面的元音是鼻化元音
+    fn is_nasal(&self, i: usize) -> bool {
+        if !matches!(self.chars.get(i), Some('m' | 'n')) {
+            return false;
+        }
+        match self.chars.get(i + 1) {
+            None => true,
+            Some(c) => !is_vowel(*c) && !"mnh".contains(*c),
+        }
+    }
+
+    /// 从该位置开始到词尾只有辅音，即前面的元音处于闭音节
+    fn is_closed(&self, i: usize) -> bool {
+        i < self.chars.len() && (i..self.chars.len()).all(|k| !self.is_sounding_vowel(k))
+    }
+
+    /// 单独的e的读音，返回`None`表示不发音
+    fn e(&self, i: usize) -> Option<&'static str> {
+        let len = self.chars.len();
+        if i + 1 == len {
+            return match (self.final_e, self.silent_e) {
+                (Some(vowel), _) => Some(vowel),
+                (None, true) => None,
+                (None, false) => Some("ə"),
+            };
+        }
+        let consonants = self.chars[i + 1..]
+            .iter()
+            .copied()
+            .take_while(|c| !is_vowel(*c))
+            .collect::<Vec<_>>();
+        let open = match consonants[..] {
+            [] => true,
+            [_] => !self.is_closed(i + 1),
+            // 塞音或擦音加r、l以及ch、ph、th、gn属于后一个音节
+            [a, b, ..] => {
+                a != b
+                    && i + 3 < len
+                    && (("pbtdkgfvc".contains(a) && "rl".contains(b))
+                        || matches!((a, b), ('c' | 'p' | 't', 'h') | ('g', 'n')))
+            }
+        };
+        Some(if open && self.chars[i + 1] != 'x' {
+            "ə"
+        } else {
+            "ɛ"
+        })
+    }
+
+    /// 单独的o的读音，词尾和s+元音前面读作o
+    fn o(&self, i: usize) -> &'static str {
+        if i + 1 == self.chars.len() || (self.at(i + 1, "s") && self.is_letter_vowel(i + 2)) {
+            "o"
+        } else {
+            "ɔ"
+        }
+    }
+
+    /// eu的读音，闭音节中读作œ
+    fn eu(&self, i: usize) -> &'static str {
+        if self.is_closed(i) { "œ" } else { "ø" }
+    }
+
+    fn phones(&self) -> Vec<Phone> {
+        let chars = &self.chars;
+        let len = chars.len();
+        let mut phones = Vec::with_capacity(len);
+        let mut i = 0;
+        let vowel = |phones: &mut Vec<Phone>, ipa: &str| phones.push(Phone::vowel(ipa, false));
+        let consonant = |phones: &mut Vec<Phone>, ipa: &str| phones.push(Phone::consonant(ipa));
+        let nasal = |phones: &mut Vec<Phone>, ipa: &str| {
+            phones.push(Phone::vowel(&format!("{}{}", ipa, TILDE), false))
+        };
+        while i < len {
+            let c = chars[i];
+            let next = chars.get(i + 1);
+
+            // -ail、-eil、-euil、-ouil以及后面跟着ll时，i读作j
+            if let Some((group, ipa)) = [
+                ("euill", "œ"),
+                ("ouill", "u"),
+                ("aill", "a"),
+                ("eill", "ɛ"),
+                ("euil", "œ"),
+                ("ouil", "u"),
+                ("ail", "a"),
+                ("eil", "ɛ"),
+            ]
+            .iter()
+            .find(|(group, _)| {
+                self.at(i, group) && (group.ends_with("ll") || i + group.chars().count() == len)
+            }) {
+                vowel(&mut phones, ipa);
+                consonant(&mut phones, "j");
+                i += group.chars().count();
+                continue;
+            }
+            if self.at(i, "eau") {
+                vowel(&mut phones, "o");
+                i += 3;
+                continue;
+            }
+            if (self.at(i, "oi") || self.at(i, "oî")) && !self.is_nasal(i + 2) {
+                consonant(&mut phones, "w");
+                vowel(&mut phones, "a");
+                i += 2;
+                continue;
+            }
+            if self.at(i, "oin") && self.is_nasal(i + 2) {
+                consonant(&mut phones, "w");
+                nasal(&mut phones, "ɛ");
+                i += 3;
+                continue;
+            }
+            if self.at(i, "oy") {
+                consonant(&mut phones, "w");
+                vowel(&mut phones, "a");
+                i += 1;
+                continue;
+            }
+            if c == 'o' && next.is_some_and(|c| "uùû".contains(*c)) {
+                // oui、jouer中的ou读作w
+                if self.is_sounding_vowel(i + 2) {
+                    consonant(&mut phones, "w");
+                } else {
+                    vowel(&mut phones, "u");
+                }
+                i += 2;
+                continue;
+            }
+            if self.at(i, "oeu") || self.at(i, "œu") {
+                let n = if c == 'o' { 3 } else { 2 };
+                vowel(&mut phones, self.eu(i + n));
+                i += n;
+                continue;
+            }
+            if self.at(i, "eu") || self.at(i, "eû") {
+                vowel(&mut phones, self.eu(i + 2));
+                i += 2;
+                continue;
+            }
+            if (self.at(i, "ai") || self.at(i, "ei")) && self.is_nasal(i + 2) {
+                nasal(&mut phones, "ɛ");
+                i += 3;
+                continue;
+            }
+            if self.at(i, "ay") || self.at(i, "ey") {
+                vowel(&mut phones, "ɛ");
+                i += 1;
+                continue;
+            }
+            if self.at(i, "ai") || self.at(i, "aî") || self.at(i, "ei") {
+                vowel(&mut phones, "ɛ");
+                i += 2;
+                continue;
+            }
+            if self.at(i, "au") {
+                vowel(&mut phones, "o");
+                i += 2;
+                continue;
+            }
+            // bien、chien读作jɛ̃，science读作jɑ̃
+            if i > 0 && (c == 'i' || c == 'y') && self.at(i + 1, "en") && self.is_nasal(i + 2) {
+                if phones.last().is_none_or(|i| i.ipa != "j") {
+                    consonant(&mut phones, "j");
+                }
+                nasal(&mut phones, if i + 3 == len { "ɛ" } else { "ɑ" });
+                i += 3;
+                continue;
+            }
+            if self.is_nasal(i + 1) && is_vowel(c) {
+                let ipa = match c {
+                    'a' | 'â' => "ɑ",
+                    // européen、moyen
+                    'e' if i > 0 && "éy".contains(chars[i - 1]) => "ɛ",
+                    'e' => "ɑ",
+                    'i' | 'î' | 'y' => "ɛ",
+                    'o' | 'ô' => "ɔ",
+                    'u' | 'û' => "œ",
+                    _ => "",
+                };
+                if !ipa.is_empty() {
+                    nasal(&mut phones, ipa);
+                    i += 2;
+                    continue;
+                }
+            }
+            // ennui、emmener
+            if i == 0 && (self.at(0, "enn") || self.at(0, "emm")) {
+                nasal(&mut phones, "ɑ");
+                i += 1;
+                continue;
+            }
+
+            let ipa = match c {
+                'a' | 'à' | 'â' | 'ä' => "a",
+                'e' => match self.e(i) {
+                    Some(ipa) => ipa,
+                    None => {
+                        i += 1;
+                        continue;
+                    }
+                },
+                'é' | 'æ' => "e",
+                'è' | 'ê' | 'ë' => "ɛ",
+                'i' | 'î' if i > 0 && self.is_sounding_vowel(i + 1) => {
+                    consonant(&mut phones, "j");
+                    i += 1;
+                    continue;
+                }
+                // fille、famille
+                'i' if i > 0 && self.at(i + 1, "ll") => {
+                    vowel(&mut phones, "i");
+                    consonant(&mut phones, "j");
+                    i += 3;
+                    continue;
+                }
+                'i' | 'î' | 'ï' => "i",
+                'o' => self.o(i),
+                'ô' | 'ö' => "o",
+                'u' | 'û' | 'ù' | 'ü' if self.is_sounding_vowel(i + 1) => {
+                    consonant(&mut phones, "ɥ");
+                    i += 1;
+                    continue;
+                }
+                'u' | 'û' | 'ù' | 'ü' => "y",
+                'y' if self.is_sounding_vowel(i + 1) => {
+                    consonant(&mut phones, "j");
+                    i += 1;
+                    continue;
+                }
+                'y' => "i",
+                'œ' => "œ",
+                _ => "",
+            };
+            if !ipa.is_empty() {
+                vowel(&mut phones, ipa);
+                i += 1;
+                continue;
+            }
+
+            let ipa = match c {
+                'c' if next == Some(&'h') => {
+                    i += 1;
+                    // chrétien、chlore
+                    if chars.get(i + 1).is_some_and(|c| "rl".contains(*c)) {
+                        "k"
+                    } else {
+                        "ʃ"
+                    }
+                }
+                'c' if next == Some(&'k') => {
+                    i += 1;
+                    "k"
+                }
+                // accident
+                'c' if next == Some(&'c') && is_front(chars.get(i + 2)) => "k",
+                'c' if next == Some(&'c') => {
+                    i += 1;
+                    "k"
+                }
+                'c' if is_front(next) => "s",
+                'c' | 'k' => "k",
+                'ç' => "s",
+                'g' if next == Some(&'n') => {
+                    i += 1;
+                    "ɲ"
+                }
+                'g' if next == Some(&'u') && is_front(chars.get(i + 2)) => {
+                    i += 1;
+                    "ɡ"
+                }
+                // mangeons
+                'g' if next == Some(&'e')
+                    && chars.get(i + 2).is_some_and(|c| "aouâô".contains(*c)) =>
+                {
+                    i += 1;
+                    "ʒ"
+                }
+                'g' if next == Some(&'g') => {
+                    i += 1;
+                    "ɡ"
+                }
+                'g' if is_front(next) => "ʒ",
+                'g' => "ɡ",
+                'h' => "",
+                'j' => "ʒ",
+                'l' if next == Some(&'l') => {
+                    i += 1;
+                    "l"
+                }
+                'l' => "l",
+                'p' if next == Some(&'h') => {
+                    i += 1;
+                    "f"
+                }
+                'q' => {
+                    if next == Some(&'u') {
+                        i += 1;
+                    }
+                    "k"
+                }
+                'r' if next == Some(&'r') => {
+                    i += 1;
+                    "ʁ"
+                }
+                'r' => "ʁ",
+                's' if next == Some(&'s') => {
+                    i += 1;
+                    "s"
+                }
+                's' if self.at(i + 1, "ch") => {
+                    i += 2;
+                    "ʃ"
+                }
+                's' if next == Some(&'h') => {
+                    i += 1;
+                    "ʃ"
+                }
+                's' if next == Some(&'c') && is_front(chars.get(i + 2)) => {
+                    i += 1;
+                    "s"
+                }
+                // 元音之间的s读作z
+                's' if i > 0 && is_vowel(chars[i - 1]) && self.is_letter_vowel(i + 1) => "z",
+                's' => "s",
+                't' if next == Some(&'h') => {
+                    i += 1;
+                    "t"
+                }
+                // nation读作nasjɔ̃，question读作kɛstjɔ̃
+                't' if self.at(i + 1, "ion") && i > 0 && !"sx".contains(chars[i - 1]) => "s",
+                'x' if i == 1 && chars[0] == 'e' && self.is_letter_vowel(2) => {
+                    consonant(&mut phones, "ɡ");
+                    "z"
+                }
+                'x' => {
+                    consonant(&mut phones, "k");
+                    "s"
+                }
+                // 双写的辅音读作一个
+                c @ ('b' | 'd' | 'f' | 'm' | 'n' | 'p' | 't' | 'z') => {
+                    if next == Some(&c) {
+                        i += 1;
+                    }
+                    match c {
+                        'b' => "b",
+                        'd' => "d",
+                        'f' => "f",
+                        'm' => "m",
+                        'n' => "n",
+                        'p' => "p",
+                        't' => "t",
+                        _ => "z",
+                    }
+                }
+                'v' => "v",
+                'w' => "w",
+                _ => "",
+            };
+            if !ipa.is_empty() {
+                consonant(&mut phones, ipa);
+            }
+            i += 1;
+        }
+        phones
+    }
+}
+
+/// 按拼写规则转换一个单词，重音符号放在最后一个元音前面
+fn spell(word: &str, verb: bool) -> String {
+    let phones = Spelling::new(word, verb).phones();
+    let stressed = if UNSTRESSED.contains(&word) {
+        None
+    } else {
+        phones.iter().rposition(|i| i.vowel)
+    };
+    let mut result = String::new();
+    for (i, phone) in phones.iter().enumerate() {
+        if stressed == Some(i) {
+            result.push('ˈ');
+        }
+        result.push_str(&phone.ipa);
+    }
+    result
+}
+
+/// 转换一个不带连字符的小写单词，撇号前面省略了元音的虚词与后面的单词连读
+fn word_to_ipa(word: &str, verb: bool) -> String {
+    if let Some(Pronunciation::Ipa(ipa)) = exceptions().get(word) {
+        return ipa.to_owned();
+    }
+    if let Some((clitic, rest)) = word.split_once('\'')
+        && let Some((_, ipa)) = ELISION.iter().find(|(i, _)| *i == clitic)
+    {
+        return format!("{}{}", ipa, word_to_ipa(rest, verb));
+    }
+    spell(word, verb)
+}
+
+/// 转换一个小写单词，连字符连接的复合词先整体查找例外词典，找不到时逐个部分转换
+fn compound_to_ipa(word: &str, verb: bool) -> String {
+    if let Some(Pronunciation::Ipa(ipa)) = exceptions().get(word) {
+        return ipa.to_owned();
+    }
+    let parts = word.split('-').collect::<Vec<_>>();
+    let mut result = Vec::with_capacity(parts.len());
+    for (i, part) in parts.iter().enumerate() {
+        let next = parts.get(i + 1).copied();
+        let mut ipa = match *part {
+            // a-t-il中的t与后面的代词连读
+            "t" if next.is_some() => continue,
+            // vingt-deux中的t发音，quatre-vingt-deux中的不发音
+            "vingt" if next.is_some() && (i == 0 || parts[i - 1] != "quatre") => {
+                format!("vˈɛ{}t", TILDE)
+            }
+            part => word_to_ipa(part, verb),
+        };
+        if i > 0 && parts[i - 1] == "t" {
+            ipa.insert(0, 't');
+        }
+        // 主谓倒装时动词词尾的t、d发音，例如dit-elle、prend-il
+        if part.ends_with(['t', 'd'])
+            && next.is_some_and(|i| matches!(i, "il" | "elle" | "on" | "ils" | "elles"))
+            && !ipa.ends_with('t')
+        {
+            ipa.push('t');
+        }
+        result.push(ipa);
+    }
+    result.join(" ")
+}
+
+/// 后面的-ent结尾的词是否为动词，前面是主语代词、宾语代词或以s、x结尾的复数名词时词尾的-ent不发音
+fn before_verb(word: &str) -> bool {
+    let word = word.rsplit('\'').next().unwrap_or(word);
+    matches!(
+        word,
+        "ils" | "elles" | "qui" | "ne" | "se" | "les" | "leur" | "nous" | "vous"
+    ) || (word.ends_with(['s', 'x'])
+        && !LIAISON.contains(&word)
+        && !UNSTRESSED.contains(&word)
+        && !ADVERBS.contains(&word))
+}
+
+/// `word`后面是`next`时连诵读出的辅音
+fn liaison(word: &str, next: &str) -> Option<&'static str> {
+    let word = word.rsplit('\'').next()?;
+    if !LIAISON.contains(&word) && !PRENOMINAL.contains(&word) {
+        return None;
+    }
+    let first = next.split(['-', '\'']).next()?;
+    if ASPIRATED.contains(&first) {
+        return None;
+    }
+    if PRENOMINAL.contains(&word)
+        && (NOT_NOMINAL.contains(&first) || UNSTRESSED.contains(&first) || ADVERBS.contains(&first))
+    {
+        return None;
+    }
+    if !next.trim_start_matches('h').starts_with(is_vowel) {
+        return None;
+    }
+    Some(match word.chars().last()? {
+        's' | 'x' | 'z' => "z",
+        't' | 'd' => "t",
+        'n' => "n",
+        'p' => "p",
+        'r' => "ʁ",
+        _ => return None,
+    })
+}
+
+/// 把法语文本切分为单词和其他字符，单词附带音标
+///
+/// 必须连诵的词（les、un、deux等）与后面以元音或哑音h开头的单词之间只有空白时，在前一个单词的音标后面加上连诵的辅音，grand、deux等形容词和数词只在名词或形容词前连诵；six、dix词尾的s改为z。主语代词、宾语代词和复数名词后面的动词词尾-ent不发音，例如ils parlent、les oiseaux chantent。
+pub(super) fn words(text: &str) -> Result<Vec<(Match<'_>, Option<String>)>, G2PError> {
+    let word_pattern = regex!(r"\w+(?:['’]\w+)*(?:-\w+(?:['’]\w+)*)*|\W+")?;
+    let pieces = word_pattern.find_iter(text).collect::<Vec<_>>();
+    let is_word = |i: &Match| i.as_str().starts_with(char::is_alphabetic);
+    let lowercase = |i: &Match| i.as_str().to_lowercase().replace('’', "'");
+
+    let mut words = Vec::with_capacity(pieces.len());
+    for (i, piece) in pieces.iter().enumerate() {
+        if !is_word(piece) {
+            words.push((*piece, None));
+            continue;
+        }
+        let word = lowercase(piece);
+        // 与前后的单词之间只有空白
+        let prev = match i.checked_sub(2).map(|j| &pieces[j..i]) {
+            Some([prev, space]) if is_word(prev) && space.as_str().trim().is_empty() => {
+                Some(lowercase(prev))
+            }
+            _ => None,
+        };
+        let next = match pieces.get(i + 1..i + 3) {
+            Some([space, next]) if is_word(next) && space.as_str().trim().is_empty() => {
+                Some(lowercase(next))
+            }
+            _ => None,
+        };
+        let verb = prev.as_deref().is_some_and(before_verb);
+        let mut phonemes = compound_to_ipa(&word, verb);
+        if let Some(consonant) = next.and_then(|next| liaison(&word, &next)) {
+            if consonant == "z" && phonemes.ends_with('s') {
+                phonemes.pop();
+            }
+            phonemes.push_str(consonant);
+        }
+        words.push((*piece, Some(phonemes)));
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::tokenizer::is_in_vocab};
+
+    #[test]
+    fn test_spell() {
+        let ipa = |word| compound_to_ipa(word, false);
+        assert_eq!("bɔ̃ʒˈuʁ", ipa("bonjour"));
+        assert_eq!("paʁlˈe", ipa("parler"));
+        assert_eq!("pətˈi", ipa("petit"));
+        assert_eq!("tˈabl", ipa("table"));
+        assert_eq!("mɛʁsˈi", ipa("merci"));
+        assert_eq!("ʃatˈo", ipa("château"));
+        assert_eq!("fˈij", ipa("fille"));
+        assert_eq!("tʁavˈaj", ipa("travail"));
+        assert_eq!("nasjˈɔ̃", ipa("nation"));
+        assert_eq!("bjˈɛ̃", ipa("bien"));
+        assert_eq!("sjˈɑ̃s", ipa("science"));
+        assert_eq!("ɑ̃fˈɑ̃", ipa("enfant"));
+        assert_eq!("mwˈɛ̃", ipa("moins"));
+        assert_eq!("œ̃", ipa("un"));
+        assert_eq!("yn", ipa("une"));
+        assert_eq!("nɥˈi", ipa("nuit"));
+        assert_eq!("wˈi", ipa("oui"));
+        assert_eq!("øʁˈø", ipa("heureux"));
+        assert_eq!("flˈœʁ", ipa("fleur"));
+        assert_eq!("ʁˈoz", ipa("rose"));
+        assert_eq!("ɛɡzˈɑ̃pl", ipa("exemple"));
+        assert_eq!("vwajˈaʒ", ipa("voyage"));
+        assert_eq!("ɡˈɛʁ", ipa("guerre"));
+        assert_eq!("mɑ̃ʒˈɔ̃", ipa("mangeons"));
+        assert_eq!("avˈɛk", ipa("avec"));
+        assert_eq!("pjˈe", ipa("pied"));
+        assert_eq!("ʁəɡaʁdˈe", ipa("regardez"));
+        assert_eq!("sˈɛt", ipa("sept"));
+    }
+
+    #[test]
+    fn test_elision() {
+        let ipa = |word| compound_to_ipa(word, false);
+        assert_eq!("lamˈi", ipa("l'ami"));
+        assert_eq!("sɛ", ipa("c'est"));
+        assert_eq!("kil", ipa("qu'il"));
+        assert_eq!("ʒˈɛm", ipa("j'aime"));
+        assert_eq!("oʒuʁdɥˈi", ipa("aujourd'hui"));
+        assert_eq!("vˈɛ̃t dˈø", ipa("vingt-deux"));
+        assert_eq!("kˈatʁ vˈɛ̃ dˈis", ipa("quatre-vingt-dix"));
+        assert_eq!("dˈit ɛl", ipa("dit-elle"));
+        assert_eq!("a til", ipa("a-t-il"));
+    }
+
+    #[test]
+    fn test_liaison() -> Result<(), G2PError> {
+        let phonemes = |text| -> Result<Vec<String>, G2PError> {
+            Ok(words(text)?.into_iter().filter_map(|(_, i)| i).collect())
+        };
+        assert_eq!(vec!["lez", "amˈi"], phonemes("les amis")?);
+        assert_eq!(vec!["œ̃n", "ˈɔm"], phonemes("un homme")?);
+        assert_eq!(vec!["le", "aʁikˈo"], phonemes("les haricots")?);
+        assert_eq!(vec!["sˈiz", "ˈɑ̃"], phonemes("six ans")?);
+        // 形容词和数词只在名词或形容词前连诵
+        assert_eq!(vec!["ɡʁˈɑ̃t", "ˈaʁbʁ"], phonemes("grand arbre")?);
+        assert_eq!(vec!["ɡʁˈɑ̃", "e", "bˈo"], phonemes("grand et beau")?);
+        assert_eq!(vec!["le", "ɡʁˈɑ̃z", "ɑ̃fˈɑ̃"], phonemes("les grands enfants")?);
+        assert_eq!(vec!["pətˈitz", "amˈi"], phonemes("petites amies")?);
+        assert_eq!(vec!["dˈotʁz", "idˈe"], phonemes("d'autres idées")?);
+        assert_eq!(vec!["ɡʁˈɑ̃d", "e", "bˈɛl"], phonemes("grandes et belles")?);
+        assert_eq!(vec!["dˈø", "a", "tʁwˈa"], phonemes("deux à trois")?);
+        // 标点隔开的单词不连诵
+        assert_eq!(vec!["tʁwˈa", "ɑ̃"], phonemes("trois, en")?);
+        // ils后面的动词词尾-ent不发音
+        assert_eq!(vec!["il", "pˈaʁl"], phonemes("ils parlent")?);
+        assert_eq!(
+            vec!["lez", "wazˈo", "ʃˈɑ̃t"],
+            phonemes("les oiseaux chantent")?
+        );
+        assert_eq!(vec!["mɔmˈɑ̃"], phonemes("moment")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vocab() -> Result<(), G2PError> {
+        let text = "Œuvre, noël, naïf, chrétien : accident, dixième ; l’oignon d’automne, \
+            quinze garçons ! aujourd’hui, jusqu’où ? Kayak, wagon, yaourt, ennui.";
+        for (_, phonemes) in words(text)? {
+            if let Some(phonemes) = phonemes {
+                assert!(
+                    phonemes.chars().all(|c| is_in_vocab(c, false)),
+                    "{}",
+                    phonemes
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use super::{G2PError, arpabet_to_ipa};
+
+/// 表示动词用法的前一个词，例如情态动词、人称代词和不定式的“to”
+const VERB_CUES: [&str; 24] = [
+    "to", "will", "would", "can", "could", "should", "must", "might", "may", "shall", "i", "we",
+    "you", "they", "please", "don't", "didn't", "doesn't", "won't", "can't", "cannot", "let's",
+    "gonna", "wanna",
+];
+
+/// 多音词的发音规则
+struct Heteronym {
+    word: &'static str,
+    /// 默认的发音，ARPAbet
+    default: &'static str,
+    /// 语境匹配时的发音，ARPAbet
+    alternative: &'static str,
+    /// 根据前后的单词判断是否使用`alternative`，单词都已转换为小写
+    is_alternative: fn(Option<&str>, Option<&str>) -> bool,
+}
+
+fn is_in(word: Option<&str>, list: &[&str]) -> bool {
+    word.is_some_and(|i| list.contains(&i))
+}
+
+const HETERONYMS: [Heteronym; 6] = [
+    // 过去式和过去分词：had read、read yesterday
+    Heteronym {
+        word: "read",
+        default: "R IY1 D",
+        alternative: "R EH1 D",
+        is_alternative: |prev, next| {
+            is_in(
+                prev,
+                &[
+                    "had", "has", "have", "having", "was", "were", "been", "is", "are", "be",
+                    "being", "already", "just", "never", "well", "widely",
+                ],
+            ) || is_in(next, &["yesterday", "last", "ago", "earlier"])
+        },
+    },
+    // 金属“铅”：lead poisoning、made of lead
+    Heteronym {
+        word: "lead",
+        default: "L IY1 D",
+        alternative: "L EH1 D",
+        is_alternative: |prev, next| {
+            is_in(prev, &["of", "unleaded"])
+                || is_in(
+                    next,
+                    &[
+                        "poisoning",
+                        "pipe",
+                        "pipes",
+                        "paint",
+                        "pencil",
+                        "pencils",
+                        "bullet",
+                        "bullets",
+                        "acid",
+                        "levels",
+                        "exposure",
+                        "weight",
+                        "weights",
+                        "shot",
+                    ],
+                )
+        },
+    },
+    // 形容词“现场的”：live music、the show is live
+    Heteronym {
+        word: "live",
+        default: "L IH1 V",
+        alternative: "L AY1 V",
+        is_alternative: |prev, next| {
+            is_in(
+                prev,
+                &[
+                    "a", "the", "go", "goes", "went", "going", "gone", "is", "are", "was", "were",
+                    "been", "being",
+                ],
+            ) || is_in(
+                next,
+                &[
+                    "music",
+                    "show",
+                    "shows",
+                    "broadcast",
+                    "performance",
+                    "concert",
+                    "stream",
+                    "streaming",
+                    "wire",
+                    "bait",
+                    "audience",
+                    "event",
+                    "events",
+                    "coverage",
+                    "recording",
+                    "album",
+                    "version",
+                    "feed",
+                    "tv",
+                    "radio",
+                    "band",
+                    "demo",
+                    "update",
+                    "updates",
+                ],
+            )
+        },
+    },
+    // 动词“录制”：to record、we record
+    Heteronym {
+        word: "record",
+        default: "R EH1 K ER0 D",
+        alternative: "R IH0 K AO1 R D",
+        is_alternative: |prev, _| is_in(prev, &VERB_CUES),
+    },
+    // 动词“缠绕”：wind up、to wind
+    Heteronym {
+        word: "wind",
+        default: "W IH1 N D",
+        alternative: "W AY1 N D",
+        is_alternative: |prev, next| {
+            is_in(prev, &VERB_CUES) || is_in(next, &["up", "down", "around", "back"])
+        },
+    },
+    // 动词“撕”：tear apart、wear and tear
+    Heteronym {
+        word: "tear",
+        default: "T IH1 R",
+        alternative: "T EH1 R",
+        is_alternative: |prev, next| {
+            is_in(prev, &VERB_CUES)
+                || is_in(prev, &["and"])
+                || is_in(
+                    next,
+                    &[
+                        "up", "down", "apart", "off", "open", "out", "through", "into", "away",
+                        "it", "them",
+                    ],
+                )
+        },
+    },
+];
+
+/// 根据前后的单词选择多音词的发音
+///
+/// 只处理常见的多音词（read、lead、live、record、wind、tear），其他单词返回`None`。
+///
+/// # 参数
+///
+/// * `word` - 要转换的单词，不区分大小写。
+/// * `prev` - 同一句中的前一个单词。
+/// * `next` - 同一句中的后一个单词。
+///
+/// # 返回值
+///
+/// 单词的国际音标。
+pub(super) fn disambiguate(
+    word: &str,
+    prev: Option<&str>,
+    next: Option<&str>,
+) -> Result<Option<String>, G2PError> {
+    let word = word.to_lowercase();
+    let Some(heteronym) = HETERONYMS.iter().find(|i| i.word == word) else {
+        return Ok(None);
+    };
+    let prev = prev.map(str::to_lowercase);
+    let next = next.map(str::to_lowercase);
+    let arpa = if (heteronym.is_alternative)(prev.as_deref(), next.as_deref()) {
+        heteronym.alternative
+    } else {
+        heteronym.default
+    };
+
+    arpabet_to_ipa(arpa).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phonemes(word: &str, prev: Option<&str>, next: Option<&str>) -> String {
+        disambiguate(word, prev, next).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_disambiguate() -> Result<(), G2PError> {
+        assert_eq!(None, disambiguate("hello", None, None)?);
+
+        assert_ne!(
+            phonemes("read", Some("I"), None),
+            phonemes("read", Some("had"), None)
+        );
+        assert_eq!(
+            phonemes("read", Some("had"), None),
+            phonemes("Read", None, Some("yesterday"))
+        );
+        assert_ne!(
+            phonemes("lead", Some("will"), None),
+            phonemes("lead", None, Some("poisoning"))
+        );
+        assert_ne!(
+            phonemes("live", Some("we"), None),
+            phonemes("live", None, Some("music"))
+        );
+        assert_ne!(
+            phonemes("record", Some("a"), None),
+            phonemes("record", Some("to"), None)
+        );
+        assert_ne!(
+            phonemes("wind", Some("strong"), None),
+            phonemes("wind", None, Some("up"))
+        );
+        assert_ne!(
+            phonemes("tear", Some("a"), None),
+            phonemes("tear", Some("and"), None)
+        );
+        // 结果与语境之外的因素无关
+        assert_eq!(
+            phonemes("record", Some("the"), Some("player")),
+            phonemes("record", None, None)
+        );
+
+        Ok(())
+    }
+}
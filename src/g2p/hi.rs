@@ -0,0 +1,537 @@
+use {super::G2PError, regex::Match};
+
+/// 鼻化元音的附加符号
+const TILDE: char = '\u{0303}';
+/// 去掉辅音固有元音的半体符号（्）
+const VIRAMA: char = '\u{094D}';
+/// 表示外来辅音的下加点（़）
+const NUKTA: char = '\u{093C}';
+/// 鼻化符号（ं），在塞音前面读作同部位的鼻音
+const ANUSVARA: char = '\u{0902}';
+/// 半月点（ँ），把前面的元音鼻化
+const CHANDRABINDU: char = '\u{0901}';
+/// 送气符号（ः）
+const VISARGA: char = '\u{0903}';
+
+/// 不重读的后置词、助动词和连词，包括常见的拉丁字母写法
+const UNSTRESSED: [&str; 39] = [
+    "का",
+    "की",
+    "के",
+    "को",
+    "में",
+    "से",
+    "ने",
+    "पर",
+    "है",
+    "हैं",
+    "था",
+    "थी",
+    "थे",
+    "और",
+    "या",
+    "तो",
+    "भी",
+    "ही",
+    "न",
+    "कि",
+    "ka",
+    "ki",
+    "ke",
+    "ko",
+    "mein",
+    "se",
+    "ne",
+    "par",
+    "hai",
+    "hain",
+    "tha",
+    "thi",
+    "the",
+    "aur",
+    "ya",
+    "to",
+    "bhi",
+    "hi",
+    "na",
+];
+
+/// 拉丁字母写法有歧义的常用词，先换成天城文再转换，主要是词尾的n表示鼻化元音的词
+const ROMANIZED: [(&str, &str); 11] = [
+    ("main", "मैं"),
+    ("hain", "हैं"),
+    ("mein", "में"),
+    ("nahin", "नहीं"),
+    ("nahi", "नहीं"),
+    ("haan", "हाँ"),
+    ("han", "हाँ"),
+    ("kyun", "क्यों"),
+    ("kyon", "क्यों"),
+    ("hoon", "हूँ"),
+    ("hun", "हूँ"),
+];
+
+/// 一个音素，记录是否为元音、是否为可以删除的固有元音和是否鼻化
+#[derive(Clone, Debug)]
+struct Phone {
+    ipa: &'static str,
+    vowel: bool,
+    /// 辅音字母自带的a（ə），可能按规则不发音
+    inherent: bool,
+    nasal: bool,
+}
+
+impl Phone {
+    fn consonant(ipa: &'static str) -> Self {
+        Self {
+            ipa,
+            vowel: false,
+            inherent: false,
+            nasal: false,
+        }
+    }
+
+    fn vowel(ipa: &'static str) -> Self {
+        Self {
+            ipa,
+            vowel: true,
+            inherent: false,
+            nasal: false,
+        }
+    }
+
+    fn schwa() -> Self {
+        Self {
+            inherent: true,
+            ..Self::vowel("ə")
+        }
+    }
+
+    /// 长元音和二合元音
+    fn is_long(&self) -> bool {
+        self.vowel && self.ipa.ends_with('ː')
+    }
+}
+
+/// 独立元音字母的读音
+fn independent(c: char) -> Option<&'static str> {
+    Some(match c {
+        'अ' => "ə",
+        'आ' => "aː",
+        'इ' => "ɪ",
+        'ई' => "iː",
+        'उ' => "ʊ",
+        'ऊ' => "uː",
+        'ए' => "eː",
+        'ऐ' => "ɛː",
+        'ओ' => "oː",
+        'औ' => "ɔː",
+        'ऑ' => "ɔ",
+        'ऍ' => "ɛ",
+        _ => return None,
+    })
+}
+
+/// 元音符号的读音
+fn matra(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ा' => "aː",
+        'ि' => "ɪ",
+        'ी' => "iː",
+        'ु' => "ʊ",
+        'ू' => "uː",
+        'े' => "eː",
+        'ै' => "ɛː",
+        'ो' => "oː",
+        'ौ' => "ɔː",
+        'ॉ' => "ɔ",
+        'ॅ' => "ɛ",
+        _ => return None,
+    })
+}
+
+/// 辅音字母的读音，带下加点的外来辅音另外处理
+fn consonant(c: char) -> Option<&'static str> {
+    Some(match c {
+        'क' => "k",
+        'ख' => "kʰ",
+        'ग' => "ɡ",
+        'घ' => "ɡʰ",
+        'ङ' => "ŋ",
+        'च' => "ʧ",
+        'छ' => "ʧʰ",
+        'ज' => "ʤ",
+        'झ' => "ʤʰ",
+        'ञ' => "ɲ",
+        'ट' => "ʈ",
+        'ठ' => "ʈʰ",
+        'ड' => "ɖ",
+        'ढ' => "ɖʰ",
+        'ण' => "ɳ",
+        'त' => "t",
+        'थ' => "tʰ",
+        'द' => "d",
+        'ध' => "dʰ",
+        'न' => "n",
+        'प' => "p",
+        'फ' => "pʰ",
+        'ब' => "b",
+        'भ' => "bʰ",
+        'म' => "m",
+        'य' => "j",
+        'र' => "ɾ",
+        'ल' | 'ळ' => "l",
+        'व' => "ʋ",
+        'श' => "ʃ",
+        'ष' => "ʂ",
+        'स' => "s",
+        'ह' => "h",
+        _ => return nukta(c),
+    })
+}
+
+/// 带下加点的辅音，包括预组合的字符和辅音字母加下加点
+fn nukta(c: char) -> Option<&'static str> {
+    Some(match c {
+        'क' | '\u{0958}' => "q",
+        'ख' | '\u{0959}' => "x",
+        'ग' | '\u{095A}' => "ɣ",
+        'ज' | '\u{095B}' => "z",
+        'ड' | '\u{095C}' => "ɽ",
+        'ढ' | '\u{095D}' => "ɽʰ",
+        'फ' | '\u{095E}' => "f",
+        'य' | '\u{095F}' => "j",
+        _ => return None,
+    })
+}
+
+/// 把天城文单词解析为音素，辅音后面没有元音符号和半体符号时带有固有元音ə
+fn parse(word: &str) -> Vec<Phone> {
+    let chars = word.chars().collect::<Vec<_>>();
+    let mut phones = Vec::with_capacity(chars.len() * 2);
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(ipa) = independent(c) {
+            phones.push(Phone::vowel(ipa));
+        } else if let Some(mut ipa) = consonant(c) {
+            if chars.get(i + 1) == Some(&NUKTA) {
+                ipa = nukta(c).unwrap_or(ipa);
+                i += 1;
+            }
+            phones.push(Phone::consonant(ipa));
+            match chars.get(i + 1).copied() {
+                Some(VIRAMA) => i += 1,
+                // ृ读作ɾɪ
+                Some('ृ') => {
+                    phones.push(Phone::consonant("ɾ"));
+                    phones.push(Phone::vowel("ɪ"));
+                    i += 1;
+                }
+                Some(c) if let Some(ipa) = matra(c) => {
+                    phones.push(Phone::vowel(ipa));
+                    i += 1;
+                }
+                _ => phones.push(Phone::schwa()),
+            }
+        } else {
+            match c {
+                'ऋ' => {
+                    phones.push(Phone::consonant("ɾ"));
+                    phones.push(Phone::vowel("ɪ"));
+                }
+                'ॐ' => {
+                    phones.push(Phone::vowel("oː"));
+                    phones.push(Phone::consonant("m"));
+                }
+                VISARGA => phones.push(Phone::consonant("h")),
+                CHANDRABINDU => nasalize(&mut phones),
+                // 塞音前面读作同部位的鼻音，其他位置把前面的元音鼻化
+                ANUSVARA => {
+                    let nasal = chars
+                        .get(i + 1)
+                        .and_then(|c| consonant(*c))
+                        .and_then(homorganic);
+                    match nasal {
+                        Some(nasal) => phones.push(Phone::consonant(nasal)),
+                        None => nasalize(&mut phones),
+                    }
+                }
+                _ => (),
+            }
+        }
+        i += 1;
+    }
+    phones
+}
+
+/// 与塞音同部位的鼻音
+fn homorganic(stop: &str) -> Option<&'static str> {
+    Some(match stop.chars().next()? {
+        'k' | 'ɡ' | 'q' | 'x' | 'ɣ' => "ŋ",
+        'ʧ' | 'ʤ' => "ɲ",
+        'ʈ' | 'ɖ' => "ɳ",
+        't' | 'd' | 'n' => "n",
+        'p' | 'b' | 'm' => "m",
+        _ => return None,
+    })
+}
+
+/// 把最后一个元音鼻化，鼻化的固有元音总是发音
+fn nasalize(phones: &mut [Phone]) {
+    if let Some(last) = phones.last_mut()
+        && last.vowel
+    {
+        last.nasal = true;
+        last.inherent = false;
+    }
+}
+
+/// 删除不发音的固有元音
+///
+/// 词尾的固有元音不发音，但单个辅音组成的词和以y、r、l、v结尾的复辅音后面的保留，例如मित्र读作mɪtɾə。词中的固有元音前后都是单个辅音且再往外都是元音时不发音，从右向左处理，例如कमला读作kəmlaː；y前面的保留，例如रुपये读作ɾʊpəjeː。
+fn delete_schwas(phones: &mut Vec<Phone>) {
+    let n = phones.len();
+    if n > 2 && phones[n - 1].inherent {
+        let cluster = !phones[n - 3].vowel && matches!(phones[n - 2].ipa, "j" | "ɾ" | "l" | "ʋ");
+        if !cluster {
+            phones.pop();
+        }
+    }
+
+    let mut i = phones.len().saturating_sub(2);
+    while i >= 2 {
+        let deletable = phones[i].inherent
+            && !phones[i - 1].vowel
+            && phones[i - 2].vowel
+            && !phones[i + 1].vowel
+            && phones[i + 1].ipa != "j"
+            && phones.get(i + 2).is_some_and(|i| i.vowel);
+        if deletable {
+            phones.remove(i);
+        }
+        i -= 1;
+    }
+}
+
+/// 按拉丁字母转写解析单词，写出的a都发音，词尾的a、i、u读作长元音
+fn parse_romanized(word: &str) -> Vec<Phone> {
+    let chars = word.chars().collect::<Vec<_>>();
+    let mut phones = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let rest = &chars[i..];
+        let starts = |prefix: &str| rest.iter().copied().take(prefix.len()).eq(prefix.chars());
+        let last = |len: usize| i + len == chars.len();
+        let vowel = [
+            ("aa", "aː"),
+            ("ai", "ɛː"),
+            ("au", "ɔː"),
+            ("ee", "iː"),
+            ("ii", "iː"),
+            ("oo", "uː"),
+            ("uu", "uː"),
+            ("a", if last(1) { "aː" } else { "ə" }),
+            ("i", if last(1) { "iː" } else { "ɪ" }),
+            ("u", if last(1) { "uː" } else { "ʊ" }),
+            ("e", "eː"),
+            ("o", "oː"),
+        ]
+        .into_iter()
+        .find(|(prefix, _)| starts(prefix));
+        if let Some((prefix, ipa)) = vowel {
+            phones.push(Phone::vowel(ipa));
+            i += prefix.len();
+            continue;
+        }
+
+        let consonant = [
+            ("chh", "ʧʰ"),
+            ("kh", "kʰ"),
+            ("gh", "ɡʰ"),
+            ("ch", "ʧ"),
+            ("jh", "ʤʰ"),
+            ("th", "tʰ"),
+            ("dh", "dʰ"),
+            ("ph", "pʰ"),
+            ("bh", "bʰ"),
+            ("sh", "ʃ"),
+            ("b", "b"),
+            ("c", "k"),
+            ("d", "d"),
+            ("f", "f"),
+            ("g", "ɡ"),
+            ("h", "h"),
+            ("j", "ʤ"),
+            ("k", "k"),
+            ("l", "l"),
+            ("m", "m"),
+            ("n", "n"),
+            ("p", "p"),
+            ("q", "q"),
+            ("r", "ɾ"),
+            ("s", "s"),
+            ("t", "t"),
+            ("v", "ʋ"),
+            ("w", "ʋ"),
+            ("y", "j"),
+            ("z", "z"),
+        ]
+        .into_iter()
+        .find(|(prefix, _)| starts(prefix));
+        match consonant {
+            Some((prefix, ipa)) => {
+                phones.push(Phone::consonant(ipa));
+                i += prefix.len();
+            }
+            // x读作ks
+            None if rest[0] == 'x' => {
+                phones.push(Phone::consonant("k"));
+                phones.push(Phone::consonant("s"));
+                i += 1;
+            }
+            None => i += 1,
+        }
+    }
+    phones
+}
+
+/// 重读的元音：重读最重的音节，长元音和闭音节比短的开音节重，一样重时重读最后一个不在词尾的
+fn stressed(phones: &[Phone]) -> Option<usize> {
+    let weight = |i: usize| {
+        let closed = phones.get(i + 1).is_some_and(|i| !i.vowel)
+            && phones.get(i + 2).is_none_or(|i| !i.vowel);
+        1 + phones[i].is_long() as u8 + closed as u8
+    };
+    let vowels = phones
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.vowel)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let (&last, rest) = vowels.split_last()?;
+    match rest.iter().max_by_key(|i| weight(**i)) {
+        Some(&i) if weight(i) >= weight(last) => Some(i),
+        _ => Some(last),
+    }
+}
+
+/// 拼接音素，重音符号放在重读的元音前面，鼻化符号放在元音字母后面
+fn render(phones: &[Phone], stressed: Option<usize>) -> String {
+    let mut result = String::new();
+    for (i, phone) in phones.iter().enumerate() {
+        if stressed == Some(i) {
+            result.push('ˈ');
+        }
+        let mut chars = phone.ipa.chars();
+        result.extend(chars.next());
+        if phone.nasal {
+            result.push(TILDE);
+        }
+        result.extend(chars);
+    }
+    result
+}
+
+/// 转换一个天城文或拉丁字母写的单词
+fn word_to_ipa(word: &str) -> String {
+    let lowercase = word.to_lowercase();
+    let phones = if word.is_ascii() {
+        match ROMANIZED.iter().find(|(i, _)| *i == lowercase) {
+            Some((_, word)) => return word_to_ipa(word),
+            None => parse_romanized(&lowercase),
+        }
+    } else {
+        let mut phones = parse(word);
+        delete_schwas(&mut phones);
+        phones
+    };
+    let stressed = if UNSTRESSED.contains(&lowercase.as_str()) {
+        None
+    } else {
+        stressed(&phones)
+    };
+    render(&phones, stressed)
+}
+
+/// 把印地语文本切分为单词和其他字符，单词附带音标
+///
+/// 天城文按字母和符号解析，删除不发音的固有元音；拉丁字母按常见的印地语转写读出，例如`namaste`。
+pub(super) fn words(text: &str) -> Result<Vec<(Match<'_>, Option<String>)>, G2PError> {
+    let word_pattern = regex!(r"[\p{L}\p{M}]+|[^\p{L}\p{M}]+")?;
+    Ok(word_pattern
+        .find_iter(text)
+        .map(|i| {
+            let phonemes = i
+                .as_str()
+                .starts_with(char::is_alphabetic)
+                .then(|| word_to_ipa(i.as_str()));
+            (i, phonemes)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::tokenizer::is_in_vocab};
+
+    #[test]
+    fn test_word_to_ipa() {
+        assert_eq!("nəmˈəsteː", word_to_ipa("नमस्ते"));
+        assert_eq!("hˈɪndiː", word_to_ipa("हिंदी"));
+        assert_eq!("kˈəmlaː", word_to_ipa("कमला"));
+        assert_eq!("ɡʰˈəɾ", word_to_ipa("घर"));
+        assert_eq!("ˈəpnaː", word_to_ipa("अपना"));
+        assert_eq!("lˈəɽkaː", word_to_ipa("लड़का"));
+        assert_eq!("mˈɪtɾə", word_to_ipa("मित्र"));
+        assert_eq!("me\u{303}ː", word_to_ipa("में"));
+        assert_eq!("mˈa\u{303}ː", word_to_ipa("माँ"));
+        assert_eq!("ʃˈəŋkəɾ", word_to_ipa("शंकर"));
+        assert_eq!("kɾˈɪʂɳ", word_to_ipa("कृष्ण"));
+        assert_eq!("zˈɪndəɡiː", word_to_ipa("ज़िंदगी"));
+        assert_eq!("dˈʊhkʰ", word_to_ipa("दुःख"));
+        assert_eq!("ɾʊpəjˈeː", word_to_ipa("रुपये"));
+    }
+
+    #[test]
+    fn test_romanized() {
+        assert_eq!("nəmˈəsteː", word_to_ipa("namaste"));
+        assert_eq!("ˈaːp", word_to_ipa("aap"));
+        assert_eq!("kˈɛːseː", word_to_ipa("kaise"));
+        assert_eq!("hɛ\u{303}ː", word_to_ipa("hain"));
+        assert_eq!("dʰənjəʋˈaːd", word_to_ipa("Dhanyavaad"));
+    }
+
+    #[test]
+    fn test_words() -> Result<(), G2PError> {
+        let text = "भारत एक विशाल देश है। क्या आप ठीक हैं?";
+        let words = words(text)?;
+        assert_eq!(
+            vec![
+                "bʰˈaːɾət",
+                "ˈeːk",
+                "ʋɪʃˈaːl",
+                "dˈeːʃ",
+                "hɛː",
+                "kjˈaː",
+                "ˈaːp",
+                "ʈʰˈiːk",
+                "hɛ\u{303}ː"
+            ],
+            words
+                .iter()
+                .filter_map(|(_, i)| i.as_deref())
+                .collect::<Vec<_>>()
+        );
+        for (_, phonemes) in words {
+            if let Some(phonemes) = phonemes {
+                assert!(
+                    phonemes.chars().all(|c| is_in_vocab(c, false)),
+                    "{}",
+                    phonemes
+                );
+            }
+        }
+        Ok(())
+    }
+}
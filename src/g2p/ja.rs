@@ -0,0 +1,726 @@
+use {
+    super::lexicon::is_han,
+    std::{collections::HashMap, sync::LazyLock},
+};
+
+/// 可以与前一个假名组成一个音拍的小写假名
+const SMALL: &str = "ャュョァィゥェォヮ";
+
+/// 片假名音拍和对应的国际音标，拗音和外来语的写法按两个字符匹配
+const MORAS: [(&str, &str); 174] = [
+    ("ア", "a"),
+    ("イ", "i"),
+    ("ウ", "ɯ"),
+    ("エ", "e"),
+    ("オ", "o"),
+    ("ァ", "a"),
+    ("ィ", "i"),
+    ("ゥ", "ɯ"),
+    ("ェ", "e"),
+    ("ォ", "o"),
+    ("カ", "ka"),
+    ("キ", "kʲi"),
+    ("ク", "kɯ"),
+    ("ケ", "ke"),
+    ("コ", "ko"),
+    ("ヵ", "ka"),
+    ("ヶ", "ke"),
+    ("ガ", "ɡa"),
+    ("ギ", "ɡʲi"),
+    ("グ", "ɡɯ"),
+    ("ゲ", "ɡe"),
+    ("ゴ", "ɡo"),
+    ("サ", "sa"),
+    ("シ", "ɕi"),
+    ("ス", "sɯ"),
+    ("セ", "se"),
+    ("ソ", "so"),
+    ("ザ", "za"),
+    ("ジ", "ʥi"),
+    ("ズ", "zɯ"),
+    ("ゼ", "ze"),
+    ("ゾ", "zo"),
+    ("タ", "ta"),
+    ("チ", "ʨi"),
+    ("ツ", "ʦɯ"),
+    ("テ", "te"),
+    ("ト", "to"),
+    ("ダ", "da"),
+    ("ヂ", "ʥi"),
+    ("ヅ", "zɯ"),
+    ("デ", "de"),
+    ("ド", "do"),
+    ("ナ", "na"),
+    ("ニ", "ɲi"),
+    ("ヌ", "nɯ"),
+    ("ネ", "ne"),
+    ("ノ", "no"),
+    ("ハ", "ha"),
+    ("ヒ", "çi"),
+    ("フ", "ɸɯ"),
+    ("ヘ", "he"),
+    ("ホ", "ho"),
+    ("バ", "ba"),
+    ("ビ", "bʲi"),
+    ("ブ", "bɯ"),
+    ("ベ", "be"),
+    ("ボ", "bo"),
+    ("パ", "pa"),
+    ("ピ", "pʲi"),
+    ("プ", "pɯ"),
+    ("ペ", "pe"),
+    ("ポ", "po"),
+    ("マ", "ma"),
+    ("ミ", "mʲi"),
+    ("ム", "mɯ"),
+    ("メ", "me"),
+    ("モ", "mo"),
+    ("ヤ", "ja"),
+    ("ユ", "jɯ"),
+    ("ヨ", "jo"),
+    ("ャ", "ja"),
+    ("ュ", "jɯ"),
+    ("ョ", "jo"),
+    ("ラ", "ɾa"),
+    ("リ", "ɾʲi"),
+    ("ル", "ɾɯ"),
+    ("レ", "ɾe"),
+    ("ロ", "ɾo"),
+    ("ワ", "ɰa"),
+    ("ヮ", "ɰa"),
+    ("ヰ", "i"),
+    ("ヱ", "e"),
+    ("ヲ", "o"),
+    ("ヴ", "vɯ"),
+    // 拗音
+    ("キャ", "kʲa"),
+    ("キュ", "kʲɯ"),
+    ("キョ", "kʲo"),
+    ("ギャ", "ɡʲa"),
+    ("ギュ", "ɡʲɯ"),
+    ("ギョ", "ɡʲo"),
+    ("シャ", "ɕa"),
+    ("シュ", "ɕɯ"),
+    ("ショ", "ɕo"),
+    ("シェ", "ɕe"),
+    ("ジャ", "ʥa"),
+    ("ジュ", "ʥɯ"),
+    ("ジョ", "ʥo"),
+    ("ジェ", "ʥe"),
+    ("チャ", "ʨa"),
+    ("チュ", "ʨɯ"),
+    ("チョ", "ʨo"),
+    ("チェ", "ʨe"),
+    ("ヂャ", "ʥa"),
+    ("ヂュ", "ʥɯ"),
+    ("ヂョ", "ʥo"),
+    ("ニャ", "ɲa"),
+    ("ニュ", "ɲɯ"),
+    ("ニョ", "ɲo"),
+    ("ヒャ", "ça"),
+    ("ヒュ", "çɯ"),
+    ("ヒョ", "ço"),
+    ("ビャ", "bʲa"),
+    ("ビュ", "bʲɯ"),
+    ("ビョ", "bʲo"),
+    ("ピャ", "pʲa"),
+    ("ピュ", "pʲɯ"),
+    ("ピョ", "pʲo"),
+    ("ミャ", "mʲa"),
+    ("ミュ", "mʲɯ"),
+    ("ミョ", "mʲo"),
+    ("リャ", "ɾʲa"),
+    ("リュ", "ɾʲɯ"),
+    ("リョ", "ɾʲo"),
+    // 外来语
+    ("ファ", "ɸa"),
+    ("フィ", "ɸi"),
+    ("フェ", "ɸe"),
+    ("フォ", "ɸo"),
+    ("フュ", "ɸʲɯ"),
+    ("ティ", "ti"),
+    ("トゥ", "tɯ"),
+    ("テュ", "tʲɯ"),
+    ("ディ", "di"),
+    ("ドゥ", "dɯ"),
+    ("デュ", "dʲɯ"),
+    ("ウィ", "ɰi"),
+    ("ウェ", "ɰe"),
+    ("ウォ", "ɰo"),
+    ("ツァ", "ʦa"),
+    ("ツィ", "ʦi"),
+    ("ツェ", "ʦe"),
+    ("ツォ", "ʦo"),
+    ("ヴァ", "va"),
+    ("ヴィ", "vi"),
+    ("ヴェ", "ve"),
+    ("ヴォ", "vo"),
+    ("イェ", "je"),
+    ("スィ", "si"),
+    ("ズィ", "zi"),
+    ("クァ", "kɰa"),
+    ("グァ", "ɡɰa"),
+    ("キェ", "kʲe"),
+    ("ギェ", "ɡʲe"),
+    ("ニェ", "ɲe"),
+    ("ヒェ", "çe"),
+    ("ミェ", "mʲe"),
+    ("リェ", "ɾʲe"),
+    ("ビェ", "bʲe"),
+    ("ピェ", "pʲe"),
+    ("クィ", "kɰi"),
+    ("クェ", "kɰe"),
+    ("クォ", "kɰo"),
+    ("グィ", "ɡɰi"),
+    ("グェ", "ɡɰe"),
+    ("グォ", "ɡɰo"),
+    ("ツュ", "ʦʲɯ"),
+    ("ヴュ", "vʲɯ"),
+    ("ヴャ", "vʲa"),
+    ("ヴョ", "vʲo"),
+    ("テャ", "tʲa"),
+    ("テョ", "tʲo"),
+    ("デャ", "dʲa"),
+    ("デョ", "dʲo"),
+    ("フャ", "ɸʲa"),
+    ("フョ", "ɸʲo"),
+];
+
+/// 词典中的一个词条
+struct Entry {
+    /// 片假名读音
+    reading: String,
+    /// 声调核的位置，0表示平板型
+    accent: Option<usize>,
+}
+
+/// 五段动词词尾的あ、い、え、お段假名和音便形，音便形包括后面的て
+const GODAN: [(char, [char; 4], &str); 9] = [
+    ('く', ['か', 'き', 'け', 'こ'], "いて"),
+    ('ぐ', ['が', 'ぎ', 'げ', 'ご'], "いで"),
+    ('す', ['さ', 'し', 'せ', 'そ'], "して"),
+    ('つ', ['た', 'ち', 'て', 'と'], "って"),
+    ('ぬ', ['な', 'に', 'ね', 'の'], "んで"),
+    ('ぶ', ['ば', 'び', 'べ', 'ぼ'], "んで"),
+    ('む', ['ま', 'み', 'め', 'も'], "んで"),
+    ('る', ['ら', 'り', 'れ', 'ろ'], "って"),
+    ('う', ['わ', 'い', 'え', 'お'], "って"),
+];
+
+/// 按词典第四列的活用类型生成活用形，每项为写法和片假名读音
+///
+/// `五`为五段动词，生成未然形、连用形、假定形、意志形的词干和て形、た形；`一`为一段动词，生成词干加常见词尾；`形`为形容词，生成く、くて、かった、ければ、さ等形式。
+///
+/// # 参数
+///
+/// * `word` - 词典形的写法。
+/// * `reading` - 词典形的片假名读音。
+/// * `kind` - 活用类型。
+fn inflections(word: &str, reading: &str, kind: &str) -> Vec<(String, String)> {
+    let (Some(last), Some(_)) = (word.chars().last(), reading.chars().last()) else {
+        return Vec::new();
+    };
+    let stem = &word[..word.len() - last.len_utf8()];
+    let stem_reading = reading
+        .char_indices()
+        .last()
+        .map_or(reading, |(i, _)| &reading[..i]);
+    let endings: Vec<String> = match kind {
+        "五" => {
+            let Some((_, rows, onbin)) = GODAN.iter().find(|(i, ..)| *i == last) else {
+                return Vec::new();
+            };
+            // 行く的音便形是行って
+            let onbin = if word.ends_with("行く") {
+                "って"
+            } else {
+                onbin
+            };
+            let past = onbin.replace('て', "た").replace('で', "だ");
+            let mut endings = rows.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+            endings.extend([onbin.to_owned(), past]);
+            endings
+        }
+        "一" if last == 'る' => {
+            let mut endings = ["る", "れ", "ろ", "よ", "な", "ま", "て", "た", "ら", "さ"]
+                .map(str::to_owned)
+                .to_vec();
+            // 词干以送假名结尾时单独成词
+            if stem.chars().last().is_some_and(is_kana) {
+                endings.push(String::new());
+            }
+            endings
+        }
+        "形" if last == 'い' => ["い", "く", "くて", "かった", "かって", "けれ", "さ", "そう"]
+            .map(str::to_owned)
+            .to_vec(),
+        _ => return Vec::new(),
+    };
+    endings
+        .into_iter()
+        .map(|i| {
+            let kana = i.chars().map(to_katakana).collect::<String>();
+            (
+                format!("{}{}", stem, i),
+                format!("{}{}", stem_reading, kana),
+            )
+        })
+        .collect()
+}
+
+struct Dictionary {
+    entries: HashMap<String, Entry>,
+    /// 汉字的音读，用于词典中没有的复合词
+    on: HashMap<char, String>,
+    /// 最长的词条包含的字数
+    max_len: usize,
+}
+
+fn dictionary() -> &'static Dictionary {
+    static DICTIONARY: LazyLock<Dictionary> = LazyLock::new(|| {
+        let mut entries = HashMap::new();
+        let mut generated = HashMap::new();
+        let mut on = HashMap::new();
+        for line in include_str!("../../dict/ja.tsv").lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split('\t');
+            let (Some(word), Some(reading)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            let reading = reading.trim();
+            let accent = fields.next().and_then(|i| i.trim().parse().ok());
+            let kind = fields.next().map(str::trim);
+            if kind == Some("音") {
+                if let Some(c) = word.chars().next() {
+                    on.insert(c, reading.to_owned());
+                }
+                continue;
+            }
+            // 词典中直接列出的活用形优先于生成的活用形
+            if let Some(kind) = kind {
+                for (word, reading) in inflections(word, reading, kind) {
+                    generated.insert(word, reading);
+                }
+            }
+            entries.insert(
+                word.to_owned(),
+                Entry {
+                    reading: reading.to_owned(),
+                    accent,
+                },
+            );
+        }
+        for (word, reading) in generated {
+            entries.entry(word).or_insert(Entry {
+                reading,
+                accent: None,
+            });
+        }
+        let max_len = entries
+            .keys()
+            .map(|i| i.chars().count())
+            .max()
+            .unwrap_or_default();
+        Dictionary {
+            entries,
+            on,
+            max_len,
+        }
+    });
+    &DICTIONARY
+}
+
+/// 是否为平假名、片假名或长音符号
+pub(super) fn is_kana(c: char) -> bool {
+    ('\u{3041}'..='\u{3096}').contains(&c) || ('\u{30A1}'..='\u{30FA}').contains(&c) || c == 'ー'
+}
+
+/// 把平假名转换为片假名，其他字符不变
+fn to_katakana(c: char) -> char {
+    match c {
+        '\u{3041}'..='\u{3096}' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// 分词结果的类别
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum Kind {
+    /// 词典中的词
+    Word,
+    /// 读音与写法不同的助词は、へ
+    Particle,
+    /// 词典中没有的假名，按写法读出
+    Kana,
+    /// 词典中没有的汉字，无法读出
+    Unknown,
+}
+
+/// 分词得到的一个词
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct Token<'a> {
+    pub(super) text: &'a str,
+    /// 在输入文本中的字节偏移
+    pub(super) offset: usize,
+    /// 片假名读音
+    pub(super) reading: String,
+    /// 声调核的位置，0表示平板型
+    pub(super) accent: Option<usize>,
+    pub(super) kind: Kind,
+}
+
+impl Token<'_> {
+    /// 把读音转换为国际音标，`pitch_accent`为假时不标注声调
+    pub(super) fn to_ipa(&self, pitch_accent: bool) -> String {
+        let accent = if pitch_accent { self.accent } else { None };
+        kana_to_ipa(&self.reading, accent, self.kind == Kind::Kana)
+    }
+}
+
+/// 按内置词典的最长匹配分词
+///
+/// 连续的、不在词典中的假名合并为一个词；与其他汉字相连却没有组成词典词的单字按音读读出，音读也没有收录的汉字单独成词，类别为`Kind::Unknown`。紧跟在词典词或汉字后面的は、へ，只有在词典中没有从它开始的词，或者跳过它能匹配到更长的词时，才视为助词，读作ワ、エ。
+///
+/// # 参数
+///
+/// * `text` - 要分词的文本，应只包含汉字和假名。
+pub(super) fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let dictionary = dictionary();
+    let chars = text.char_indices().collect::<Vec<_>>();
+    let end = |i: usize| chars.get(i).map_or(text.len(), |(j, _)| *j);
+    let longest = |i: usize| {
+        let start = chars[i].0;
+        (1..=dictionary.max_len.min(chars.len() - i))
+            .rev()
+            .find_map(|n| {
+                let word = &text[start..end(i + n)];
+                dictionary.entries.get(word).map(|entry| (n, word, entry))
+            })
+    };
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let word = longest(i);
+        if matches!(c, 'は' | 'へ')
+            && tokens
+                .last()
+                .is_some_and(|i| matches!(i.kind, Kind::Word | Kind::Unknown))
+            && word.is_none_or(|(n, ..)| {
+                chars.len() > i + 1 && longest(i + 1).is_some_and(|(m, ..)| m >= n)
+            })
+        {
+            tokens.push(Token {
+                text: &text[start..end(i + 1)],
+                offset: start,
+                reading: if c == 'は' { "ワ" } else { "エ" }.to_owned(),
+                accent: None,
+                kind: Kind::Particle,
+            });
+            i += 1;
+            continue;
+        }
+
+        let compound =
+            (i > 0 && is_han(chars[i - 1].1)) || chars.get(i + 1).is_some_and(|(_, c)| is_han(*c));
+        if let Some(on) = dictionary.on.get(&c)
+            && (compound || word.is_none())
+            && word.is_none_or(|(n, ..)| n == 1)
+        {
+            tokens.push(Token {
+                text: &text[start..end(i + 1)],
+                offset: start,
+                reading: on.clone(),
+                accent: None,
+                kind: Kind::Word,
+            });
+            i += 1;
+            continue;
+        }
+        if let Some((n, word, entry)) = word {
+            tokens.push(Token {
+                text: word,
+                offset: start,
+                reading: entry.reading.clone(),
+                accent: entry.accent,
+                kind: Kind::Word,
+            });
+            i += n;
+            continue;
+        }
+
+        match tokens.last_mut() {
+            Some(last) if is_kana(c) && last.kind == Kind::Kana => {
+                last.text = &text[last.offset..end(i + 1)];
+                last.reading.push(to_katakana(c));
+            }
+            _ => tokens.push(Token {
+                text: &text[start..end(i + 1)],
+                offset: start,
+                reading: if is_kana(c) {
+                    to_katakana(c).to_string()
+                } else {
+                    String::new()
+                },
+                accent: None,
+                kind: if is_kana(c) {
+                    Kind::Kana
+                } else {
+                    Kind::Unknown
+                },
+            }),
+        }
+        i += 1;
+    }
+
+    tokens
+}
+
+/// 音拍
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Mora {
+    Syllable(&'static str),
+    /// 长音ー
+    Long,
+    /// 促音ッ
+    Geminate,
+    /// 拨音ン
+    Nasal,
+}
+
+fn lookup(kana: &str) -> Option<&'static str> {
+    MORAS.iter().find(|(i, _)| *i == kana).map(|(_, ipa)| *ipa)
+}
+
+/// 把假名切分为音拍
+fn moras(kana: &str) -> Vec<Mora> {
+    let kana = kana.chars().map(to_katakana).collect::<Vec<_>>();
+    let mut moras = Vec::with_capacity(kana.len());
+    let mut i = 0;
+    while i < kana.len() {
+        if let Some(next) = kana.get(i + 1)
+            && SMALL.contains(*next)
+            && let Some(ipa) = lookup(&format!("{}{}", kana[i], next))
+        {
+            moras.push(Mora::Syllable(ipa));
+            i += 2;
+            continue;
+        }
+        match kana[i] {
+            'ー' => moras.push(Mora::Long),
+            'ッ' => moras.push(Mora::Geminate),
+            'ン' => moras.push(Mora::Nasal),
+            c => {
+                if let Some(ipa) = lookup(&c.to_string()) {
+                    moras.push(Mora::Syllable(ipa));
+                }
+            }
+        }
+        i += 1;
+    }
+    moras
+}
+
+/// 把假名读音转换为国际音标
+///
+/// 促音重复后一个音拍的辅音，后面没有辅音时读作`ʔ`；拨音按后一个音拍的辅音读作`m`、`n`、`ŋ`、`ɲ`，词尾和元音前读作`ɴ`。
+///
+/// # 参数
+///
+/// * `kana` - 平假名或片假名。
+/// * `accent` - 声调核的位置，在该音拍后面加`↓`，0和`None`表示不标注。
+/// * `spelling` - 读音是否来自假名的写法，是则把おう、えい等读作长音。
+pub(super) fn kana_to_ipa(kana: &str, accent: Option<usize>, spelling: bool) -> String {
+    let mut moras = moras(kana);
+    if spelling {
+        for i in 1..moras.len() {
+            if let (Mora::Syllable(prev), Mora::Syllable(current)) = (moras[i - 1], moras[i])
+                && ((current == "ɯ" && (prev.ends_with('o') || prev.ends_with('ɯ')))
+                    || (current == "i" && prev.ends_with('e')))
+            {
+                moras[i] = Mora::Long;
+            }
+        }
+    }
+
+    let mut result = String::new();
+    for (i, mora) in moras.iter().enumerate() {
+        let next = match moras.get(i + 1) {
+            Some(Mora::Syllable(ipa)) => ipa.chars().next(),
+            _ => None,
+        };
+        match mora {
+            Mora::Syllable(ipa) => result.push_str(ipa),
+            Mora::Long => result.push('ː'),
+            Mora::Geminate => result.push(match next {
+                Some('ʨ' | 'ʦ') => 't',
+                Some('ʥ') => 'd',
+                Some(c) if !"aiɯeoj".contains(c) => c,
+                _ => 'ʔ',
+            }),
+            Mora::Nasal => result.push(match next {
+                Some('p' | 'b' | 'm') => 'm',
+                Some('t' | 'd' | 'n' | 'ɾ' | 'ʦ' | 'ʨ' | 'ʥ' | 'z') => 'n',
+                Some('k' | 'ɡ') => 'ŋ',
+                Some('ɲ') => 'ɲ',
+                _ => 'ɴ',
+            }),
+        }
+        if accent.is_some_and(|a| a == i + 1) {
+            result.push('↓');
+        }
+    }
+    result
+}
+
+/// 是否为日语使用的字符，包括汉字、々和假名
+pub(super) fn is_japanese(c: char) -> bool {
+    is_han(c) || c == '々' || is_kana(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::tokenizer::is_in_vocab};
+
+    #[test]
+    fn test_dictionary() {
+        let dictionary = dictionary();
+        assert!(dictionary.entries.len() > 1500);
+        for (word, entry) in &dictionary.entries {
+            let moras = moras(&entry.reading);
+            assert_eq!(
+                entry
+                    .reading
+                    .chars()
+                    .filter(|c| !SMALL.contains(*c))
+                    .count(),
+                moras.len(),
+                "{}",
+                word
+            );
+            assert!(entry.accent.is_none_or(|i| i <= moras.len()), "{}", word);
+        }
+        for (c, reading) in &dictionary.on {
+            assert_eq!(
+                reading.chars().filter(|c| !SMALL.contains(*c)).count(),
+                moras(reading).len(),
+                "{}",
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn test_kana_to_ipa() {
+        assert_eq!("koɲɲiʨiɰa", kana_to_ipa("コンニチワ", None, false));
+        assert_eq!("ɕimbɯɴ", kana_to_ipa("しんぶん", None, false));
+        assert_eq!("ɡeŋkʲi", kana_to_ipa("ゲンキ", None, false));
+        assert_eq!("ɡakkoː", kana_to_ipa("ガッコー", None, false));
+        assert_eq!("kʲitte", kana_to_ipa("きって", None, false));
+        assert_eq!("matʨa", kana_to_ipa("マッチャ", None, false));
+        assert_eq!("aʔ", kana_to_ipa("あっ", None, false));
+        assert_eq!("ɸaiɾɯ", kana_to_ipa("ファイル", None, false));
+        // 按写法读出时的长音
+        assert_eq!("toːkʲoː", kana_to_ipa("とうきょう", None, true));
+        assert_eq!("seɴseː", kana_to_ipa("せんせい", None, true));
+        assert_eq!("seɴsei", kana_to_ipa("せんせい", None, false));
+        // 声调核
+        assert_eq!("ha↓ɕi", kana_to_ipa("ハシ", Some(1), false));
+        assert_eq!("haɕi↓", kana_to_ipa("ハシ", Some(2), false));
+        assert_eq!("haɕi", kana_to_ipa("ハシ", Some(0), false));
+
+        let ipa = kana_to_ipa(
+            "アイウエオカキクケコガギグゲゴサシスセソザジズゼゾタチツテトダヂヅデドナニヌネノハヒフヘホバビブベボパピプペポマミムメモヤユヨラリルレロワヲンーッ",
+            Some(3),
+            true,
+        );
+        let all = MORAS.iter().map(|(_, i)| *i).collect::<String>() + &ipa + "ʔmnŋɲɴː↓";
+        assert!(all.chars().all(|c| is_in_vocab(c, false)));
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("私は東京へ行きます");
+        assert_eq!(
+            vec![
+                ("私", Kind::Word),
+                ("は", Kind::Particle),
+                ("東京", Kind::Word),
+                ("へ", Kind::Particle),
+                ("行きます", Kind::Word)
+            ],
+            tokens.iter().map(|i| (i.text, i.kind)).collect::<Vec<_>>()
+        );
+        assert_eq!("ɰa", tokens[1].to_ipa(true));
+        assert_eq!(6, tokens[2].offset);
+
+        // 词典中没有的假名合并为一个词，句首的は按写法读
+        let tokens = tokenize("はなはとてもきれい");
+        assert_eq!(
+            vec!["はなは", "とても", "きれい"],
+            tokens.iter().map(|i| i.text).collect::<Vec<_>>()
+        );
+        assert_eq!("hanaha", tokens[0].to_ipa(true));
+        assert_eq!("kʲiɾeː", tokens[2].to_ipa(true));
+
+        // 生成的活用形
+        for (text, ipa) in [
+            ("行きました", "ikʲima↓ɕita"),
+            ("買いました", "kaima↓ɕita"),
+            ("飲んで", "no↓nde"),
+            ("行って", "itte"),
+            ("高かった", "takakatta"),
+            ("食べない", "tabenai"),
+            ("橋と箸", "haɕitohaɕi"),
+        ] {
+            let tokens = tokenize(text);
+            assert!(tokens.iter().all(|i| i.kind != Kind::Unknown), "{}", text);
+            assert_eq!(
+                ipa,
+                tokens.iter().map(|i| i.to_ipa(true)).collect::<String>(),
+                "{}",
+                text
+            );
+        }
+
+        // 从は、へ开始的词优先于助词
+        for (text, ipa) in [
+            ("そのはなしはへんだ", "sonohanaɕi↓ɰahe↓ɴda"),
+            ("私はへやにいる", "ɰataɕiɰaheja↓ɲiiɾɯ"),
+            ("これはいい", "koɾeɰai↓i"),
+        ] {
+            assert_eq!(
+                ipa,
+                tokenize(text)
+                    .iter()
+                    .map(|i| i.to_ipa(true))
+                    .collect::<String>(),
+                "{}",
+                text
+            );
+        }
+
+        // 词典中没有的复合词按音读读出
+        let tokens = tokenize("日本政府は経済対策を発表した");
+        assert!(tokens.iter().all(|i| i.kind != Kind::Unknown));
+        assert_eq!(
+            "ɲihoɴseːɸɯɰakeːzaitaisakɯohappʲoːɕita",
+            tokens.iter().map(|i| i.to_ipa(false)).collect::<String>()
+        );
+        assert_eq!(
+            "taiɡai",
+            tokenize("対外")
+                .iter()
+                .map(|i| i.to_ipa(false))
+                .collect::<String>()
+        );
+        assert_eq!("omote", tokenize("表")[0].to_ipa(false));
+
+        let tokens = tokenize("鬱です");
+        assert_eq!(Kind::Unknown, tokens[0].kind);
+        assert_eq!("de↓sɯ", tokens[1].to_ipa(true));
+        assert_eq!("desɯ", tokens[1].to_ipa(false));
+    }
+}
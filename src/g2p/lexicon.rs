@@ -0,0 +1,443 @@
+use {
+    super::G2PError,
+    crate::KokoroError,
+    std::{collections::HashMap, path::Path},
+    tokio::fs::read_to_string,
+};
+
+/// 词条的发音
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Pronunciation {
+    /// 国际音标，英语单词会转换为当前口音的misaki音标，其他词条原样输出
+    Ipa(String),
+    /// 空格分隔的数字标调拼音，例如`chong2 qing4`，会按模型版本转换为对应的音标
+    Pinyin(String),
+    /// 替换文本，按普通文本重新转换，例如把缩写替换为全称
+    Alias(String),
+}
+
+/// 用户发音词典
+///
+/// 词典中的词条会在内置前端之前查找。英语词条不区分大小写，可以是用空格分隔的多个单词；汉语词条按最长匹配查找。
+#[derive(Clone, Debug, Default)]
+pub struct Lexicon {
+    entries: HashMap<String, Pronunciation>,
+    // 汉语词条的最大字数
+    max_chars: usize,
+    // 英语词条的最大单词数
+    max_words: usize,
+}
+
+pub(super) fn is_han(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' | '\u{20000}'..='\u{3134F}')
+}
+
+/// 是否为空格分隔的数字标调拼音
+pub(super) fn is_pinyin(text: &str) -> bool {
+    regex!(r"^(?i:[a-zü]+[1-5](\s+|$))+$").is_ok_and(|i| i.is_match(text))
+}
+
+fn unescape(text: &str) -> Result<String, G2PError> {
+    let entity = regex!(r"&(#x[0-9a-fA-F]+|#[0-9]+|amp|lt|gt|quot|apos);")?;
+    Ok(entity
+        .replace_all(text, |caps: &regex::Captures| match &caps[1] {
+            "amp" => "&".to_owned(),
+            "lt" => "<".to_owned(),
+            "gt" => ">".to_owned(),
+            "quot" => "\"".to_owned(),
+            "apos" => "'".to_owned(),
+            e => {
+                let code = match e.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => e[1..].parse().ok(),
+                };
+                code.and_then(char::from_u32)
+                    .map_or(caps[0].to_owned(), |c| c.to_string())
+            }
+        })
+        .trim()
+        .to_owned())
+}
+
+fn alphabet(text: &str) -> Option<String> {
+    let pattern = regex!(r#"\balphabet\s*=\s*["']([^"']*)["']"#).ok()?;
+    pattern.captures(text).map(|i| i[1].to_lowercase())
+}
+
+impl Lexicon {
+    /// 创建空的词典
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Lexicon, Pronunciation};
+    ///
+    /// let mut lexicon = Lexicon::new();
+    /// lexicon
+    ///     .insert("Kokoro", Pronunciation::Ipa("kəkˈɔːɹəʊ".into()))
+    ///     .insert("重庆", Pronunciation::Pinyin("chong2 qing4".into()));
+    /// assert!(lexicon.get("kokoro").is_some());
+    /// ```
+    ///
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(word: &str) -> String {
+        word.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    }
+
+    /// 添加或替换一个词条
+    ///
+    /// # 参数
+    ///
+    /// * `word` - 单词或短语。
+    /// * `pronunciation` - 发音。
+    pub fn insert<W>(&mut self, word: W, pronunciation: Pronunciation) -> &mut Self
+    where
+        W: AsRef<str>,
+    {
+        let key = Self::key(word.as_ref());
+        if key.is_empty() {
+            return self;
+        }
+        if key.chars().any(is_han) {
+            self.max_chars = self.max_chars.max(key.chars().count());
+        } else {
+            self.max_words = self.max_words.max(key.split(' ').count());
+        }
+        self.entries.insert(key, pronunciation);
+        self
+    }
+
+    /// 查找词条
+    pub fn get(&self, word: &str) -> Option<&Pronunciation> {
+        self.entries.get(&Self::key(word))
+    }
+
+    /// 词条数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 词典是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 合并另一个词典，相同的词条以`other`为准
+    pub fn extend(&mut self, other: Self) {
+        for (word, pronunciation) in other.entries {
+            self.insert(word, pronunciation);
+        }
+    }
+
+    /// 从制表符分隔的文本中读取词典
+    ///
+    /// 每行一个词条，格式为`词<TAB>发音[<TAB>类型]`，类型可以是`ipa`、`pinyin`或`alias`。省略类型时，汉语词条的发音如果是数字标调拼音则视为拼音，否则视为音标。空行和以`#`开头的行会被忽略。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Lexicon, Pronunciation};
+    ///
+    /// let lexicon = Lexicon::from_tsv("# 产品名\nKokoro\tkəkˈɔːɹəʊ\n重庆\tchong2 qing4\nTTS\ttext to speech\talias").unwrap();
+    /// assert_eq!(lexicon.get("重庆"), Some(&Pronunciation::Pinyin("chong2 qing4".into())));
+    /// ```
+    ///
+    pub fn from_tsv(text: &str) -> Result<Self, G2PError> {
+        let mut lexicon = Self::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let mut columns = line.split('\t').map(str::trim);
+            let (Some(word), Some(value)) = (columns.next(), columns.next()) else {
+                return Err(G2PError::Lexicon(format!("line {}: missing column", n + 1)));
+            };
+            let value = value.to_owned();
+            let pronunciation = match columns.next().map(str::to_lowercase).as_deref() {
+                Some("ipa") => Pronunciation::Ipa(value),
+                Some("pinyin") => Pronunciation::Pinyin(value),
+                Some("alias") => Pronunciation::Alias(value),
+                None | Some("") if word.chars().any(is_han) && is_pinyin(&value) => {
+                    Pronunciation::Pinyin(value)
+                }
+                None | Some("") => Pronunciation::Ipa(value),
+                Some(kind) => {
+                    return Err(G2PError::Lexicon(format!(
+                        "line {}: unknown type `{}`",
+                        n + 1,
+                        kind
+                    )));
+                }
+            };
+            lexicon.insert(word, pronunciation);
+        }
+
+        Ok(lexicon)
+    }
+
+    /// 从W3C发音词典规范（PLS）的XML中读取词典
+    ///
+    /// 支持`ipa`以及`x-pinyin`（或`pinyin`）字母表，每个`lexeme`取第一个`phoneme`，没有`phoneme`时使用`alias`。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Lexicon, Pronunciation};
+    ///
+    /// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <lexicon version="1.0" xmlns="http://www.w3.org/2005/01/pronunciation-lexicon" alphabet="ipa" xml:lang="en-US">
+    ///   <lexeme>
+    ///     <grapheme>Kokoro</grapheme>
+    ///     <phoneme>kəkˈɔːɹəʊ</phoneme>
+    ///   </lexeme>
+    /// </lexicon>"#;
+    /// let lexicon = Lexicon::from_pls(xml).unwrap();
+    /// assert_eq!(lexicon.get("kokoro"), Some(&Pronunciation::Ipa("kəkˈɔːɹəʊ".into())));
+    /// ```
+    ///
+    pub fn from_pls(xml: &str) -> Result<Self, G2PError> {
+        let comment = regex!(r"(?s)<!--.*?-->")?;
+        let xml = comment.replace_all(xml, "");
+        let root = regex!(r"<lexicon\b[^>]*>")?;
+        let Some(root) = root.find(&xml) else {
+            return Err(G2PError::Lexicon("missing <lexicon> element".to_owned()));
+        };
+        let default_alphabet = alphabet(root.as_str()).unwrap_or("ipa".to_owned());
+
+        let lexeme = regex!(r"(?s)<lexeme\b[^>]*>(.*?)</lexeme>")?;
+        let grapheme = regex!(r"(?s)<grapheme\b[^>]*>(.*?)</grapheme>")?;
+        let phoneme = regex!(r"(?s)<phoneme\b([^>]*)>(.*?)</phoneme>")?;
+        let alias = regex!(r"(?s)<alias\b[^>]*>(.*?)</alias>")?;
+        let mut lexicon = Self::new();
+        for i in lexeme.captures_iter(&xml) {
+            let body = &i[1];
+            let pronunciation = if let Some(p) = phoneme.captures(body) {
+                let value = unescape(&p[2])?;
+                match alphabet(&p[1]).unwrap_or(default_alphabet.clone()).as_str() {
+                    "ipa" => Pronunciation::Ipa(value),
+                    "x-pinyin" | "pinyin" => Pronunciation::Pinyin(value),
+                    a => {
+                        return Err(G2PError::Lexicon(format!("unsupported alphabet `{}`", a)));
+                    }
+                }
+            } else if let Some(a) = alias.captures(body) {
+                Pronunciation::Alias(unescape(&a[1])?)
+            } else {
+                continue;
+            };
+
+            for g in grapheme.captures_iter(body) {
+                lexicon.insert(unescape(&g[1])?, pronunciation.clone());
+            }
+        }
+
+        Ok(lexicon)
+    }
+
+    /// 从文件读取词典
+    ///
+    /// 扩展名为`.pls`或`.xml`的文件按PLS格式读取，其他文件按制表符分隔的文本读取。
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径。
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self, KokoroError> {
+        let is_pls = path
+            .as_ref()
+            .extension()
+            .and_then(|i| i.to_str())
+            .is_some_and(|i| i.eq_ignore_ascii_case("pls") || i.eq_ignore_ascii_case("xml"));
+        let text = read_to_string(path).await?;
+        let lexicon = if is_pls {
+            Self::from_pls(&text)?
+        } else {
+            Self::from_tsv(&text)?
+        };
+        Ok(lexicon)
+    }
+
+    /// 按最长匹配把文本切分为词典中的词条和其余部分
+    ///
+    /// 汉语词条逐字匹配，英语词条按单词匹配，短语中的单词之间只能是空白。单词可以包含拉丁扩展字母，以及连接字母的撇号、连字符和`&`（AT&T）。
+    pub(super) fn split<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> Result<Vec<(&'a str, Option<&'a Pronunciation>)>, G2PError> {
+        let word =
+            regex!(r"[0-9\p{Latin}\u0300-\u036F_]+(?:['’&-][0-9\p{Latin}\u0300-\u036F_]+)*")?;
+        let words = word.find_iter(text).collect::<Vec<_>>();
+        let chars = text.char_indices().collect::<Vec<_>>();
+        let char_end = |i: usize| chars.get(i).map_or(text.len(), |c| c.0);
+
+        let mut pieces = Vec::new();
+        let (mut start, mut w) = (0, 0);
+        let mut i = 0;
+        while i < chars.len() {
+            let (pos, c) = chars[i];
+            let mut matched = None;
+            if is_han(c) {
+                for n in (1..=self.max_chars.min(chars.len() - i)).rev() {
+                    let end = char_end(i + n);
+                    if let Some(p) = self.get(&text[pos..end]) {
+                        matched = Some((end, p));
+                        break;
+                    }
+                }
+            } else {
+                while w < words.len() && words[w].start() < pos {
+                    w += 1;
+                }
+                if w < words.len() && words[w].start() == pos {
+                    for n in (1..=self.max_words.min(words.len() - w)).rev() {
+                        let phrase = &words[w..w + n];
+                        if phrase
+                            .windows(2)
+                            .any(|i| !text[i[0].end()..i[1].start()].trim().is_empty())
+                        {
+                            continue;
+                        }
+                        let end = phrase[n - 1].end();
+                        if let Some(p) = self.get(&text[pos..end]) {
+                            matched = Some((end, p));
+                            break;
+                        }
+                    }
+                }
+            }
+
+            match matched {
+                Some((end, p)) => {
+                    if start < pos {
+                        pieces.push((&text[start..pos], None));
+                    }
+                    pieces.push((&text[pos..end], Some(p)));
+                    start = end;
+                    while i < chars.len() && chars[i].0 < end {
+                        i += 1;
+                    }
+                }
+                // 不在单词开头时跳过整个单词，避免匹配到单词的后半部分
+                None if !is_han(c) && w < words.len() && words[w].start() == pos => {
+                    let end = words[w].end();
+                    while i < chars.len() && chars[i].0 < end {
+                        i += 1;
+                    }
+                }
+                None => i += 1,
+            }
+        }
+        if start < text.len() {
+            pieces.push((&text[start..], None));
+        }
+
+        Ok(pieces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexicon_split() -> Result<(), G2PError> {
+        let lexicon = Lexicon::from_tsv("长城\tA\n长\tB\nnew york\tC\nkokoro\tD")?;
+        let pieces = lexicon.split("长城很长")?;
+        assert_eq!(
+            vec!["长城", "很", "长"],
+            pieces.iter().map(|i| i.0).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&Pronunciation::Ipa("B".into())), pieces[2].1);
+        assert!(pieces[1].1.is_none());
+
+        let pieces = lexicon.split("I love New  York, Kokoro!")?;
+        assert_eq!(
+            vec!["I love ", "New  York", ", ", "Kokoro", "!"],
+            pieces.iter().map(|i| i.0).collect::<Vec<_>>()
+        );
+        // 单词之间有标点时不算短语
+        assert_eq!(1, lexicon.split("new, york")?.len());
+        // 不匹配单词的一部分
+        assert_eq!(1, lexicon.split("supernew york")?.len());
+        let other = Lexicon::from_tsv("AT&T\tA\nŁódź\tB")?;
+        assert_eq!(
+            vec!["call ", "AT&T", " in ", "Łódź"],
+            other
+                .split("call AT&T in Łódź")?
+                .iter()
+                .map(|i| i.0)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["我在", "new york", "看", "长城"],
+            lexicon
+                .split("我在new york看长城")?
+                .iter()
+                .map(|i| i.0)
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexicon_import() -> Result<(), G2PError> {
+        let lexicon =
+            Lexicon::from_tsv("重庆\tchong2 qing4\nTTS\ttext to speech\talias\n银行\tjin\tipa")?;
+        assert_eq!(
+            Some(&Pronunciation::Pinyin("chong2 qing4".into())),
+            lexicon.get("重庆")
+        );
+        assert_eq!(
+            Some(&Pronunciation::Alias("text to speech".into())),
+            lexicon.get("tts")
+        );
+        assert_eq!(Some(&Pronunciation::Ipa("jin".into())), lexicon.get("银行"));
+        assert!(Lexicon::from_tsv("word").is_err());
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<lexicon version="1.0" xmlns="http://www.w3.org/2005/01/pronunciation-lexicon" alphabet="ipa" xml:lang="en-US">
+  <!-- <lexeme><grapheme>ignored</grapheme><phoneme>x</phoneme></lexeme> -->
+  <lexeme>
+    <grapheme>Kokoro</grapheme>
+    <grapheme>KOKORO</grapheme>
+    <phoneme>kəkˈɔːɹəʊ</phoneme>
+    <phoneme>kokoro</phoneme>
+  </lexeme>
+  <lexeme>
+    <grapheme>W3C</grapheme>
+    <alias>World Wide Web Consortium</alias>
+  </lexeme>
+  <lexeme>
+    <grapheme>AT&amp;T</grapheme>
+    <alias>A T and T</alias>
+  </lexeme>
+  <lexeme>
+    <grapheme>重庆</grapheme>
+    <phoneme alphabet="x-pinyin">chong2 qing4</phoneme>
+  </lexeme>
+</lexicon>"#;
+        let lexicon = Lexicon::from_pls(xml)?;
+        assert_eq!(4, lexicon.len());
+        assert!(lexicon.get("ignored").is_none());
+        assert_eq!(
+            Some(&Pronunciation::Ipa("kəkˈɔːɹəʊ".into())),
+            lexicon.get("kokoro")
+        );
+        assert!(lexicon.get("at&t").is_some());
+        assert_eq!(
+            Some(&Pronunciation::Pinyin("chong2 qing4".into())),
+            lexicon.get("重庆")
+        );
+        assert!(Lexicon::from_pls("<lexemes/>").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use super::{
+    G2PError,
+    lexicon::{is_han, is_pinyin},
+};
+
+/// 主重音
+const PRIMARY_STRESS: char = 'ˈ';
+/// 次重音
+const SECONDARY_STRESS: char = 'ˌ';
+/// 可以承载重音的元音
+const VOWELS: &str = "AIOQWYaeiouyæɐɑɒɔəɘɚɛɜɝɞɤɨɪɯɵøœɶʉʊʌʏᵻ";
+
+/// 文本中的发音标记
+///
+/// 与misaki相同，标记的格式为`[文本](内容)`，内容可以是：
+///
+/// * `/音标/` - 直接使用的音标；汉语文本中的数字标调拼音按拼音处理。
+/// * 汉字的数字标调拼音，例如`[重庆](chong2 qing4)`；其他文本的同形内容不是有效的标记。
+/// * 重音调整，例如`+1`、`-1`、`0.5`，只对英语单词有效。
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Markup<'a> {
+    Phonemes(&'a str),
+    Pinyin(&'a str),
+    Stress(f32),
+}
+
+/// 把文本切分为带标记的单词和普通文本
+///
+/// 无法识别的标记只保留文本部分，标记本身不会出现在结果中。
+pub(super) fn parse(text: &str) -> Result<Vec<(&str, Option<Markup<'_>>)>, G2PError> {
+    let pattern = regex!(r"\[([^\[\]]+)\]\(([^()]*)\)")?;
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    for i in pattern.captures_iter(text) {
+        let (Some(all), Some(word), Some(content)) = (i.get(0), i.get(1), i.get(2)) else {
+            continue;
+        };
+        if start < all.start() {
+            pieces.push((&text[start..all.start()], None));
+        }
+        let content = content.as_str().trim();
+        // 拼音只用于汉字，其他单词的同形内容按音标或无法识别的标记处理
+        let han = word.as_str().chars().any(is_han);
+        let markup = match content.strip_prefix('/').and_then(|i| i.strip_suffix('/')) {
+            Some(value) if han && is_pinyin(value) => Some(Markup::Pinyin(value.trim())),
+            Some(value) => Some(Markup::Phonemes(value.trim())),
+            None if han && is_pinyin(content) => Some(Markup::Pinyin(content)),
+            None => content
+                .parse()
+                .ok()
+                .filter(|i: &f32| i.is_finite())
+                .map(Markup::Stress),
+        };
+        pieces.push((word.as_str(), markup));
+        start = all.end();
+    }
+    if start < text.len() {
+        pieces.push((&text[start..], None));
+    }
+
+    Ok(pieces)
+}
+
+/// 按misaki的规则调整音标中的重音
+///
+/// 小于-1时去掉所有重音；-1时把主重音降为次重音；0到1之间时为没有重音的单词加上次重音；大于等于1时把次重音升为主重音；大于1时为没有重音的单词加上主重音。
+pub(super) fn apply_stress(phonemes: &str, stress: f32) -> String {
+    let has_primary = phonemes.contains(PRIMARY_STRESS);
+    let has_secondary = phonemes.contains(SECONDARY_STRESS);
+    let has_vowel = phonemes.chars().any(|c| VOWELS.contains(c));
+    // 在第一个元音前加上重音
+    let restress = |mark: char| {
+        let mut result = String::with_capacity(phonemes.len() + mark.len_utf8());
+        let mut marked = false;
+        for c in phonemes.chars() {
+            if !marked && VOWELS.contains(c) {
+                result.push(mark);
+                marked = true;
+            }
+            result.push(c);
+        }
+        result
+    };
+
+    if stress < -1. {
+        phonemes
+            .chars()
+            .filter(|c| *c != PRIMARY_STRESS && *c != SECONDARY_STRESS)
+            .collect()
+    } else if stress == -1. || ((stress == 0. || stress == -0.5) && has_primary) {
+        phonemes
+            .chars()
+            .filter(|c| *c != SECONDARY_STRESS)
+            .map(|c| {
+                if c == PRIMARY_STRESS {
+                    SECONDARY_STRESS
+                } else {
+                    c
+                }
+            })
+            .collect()
+    } else if [0., 0.5, 1.].contains(&stress) && !has_primary && !has_secondary {
+        if has_vowel {
+            restress(SECONDARY_STRESS)
+        } else {
+            phonemes.to_owned()
+        }
+    } else if stress >= 1. && !has_primary && has_secondary {
+        phonemes.replace(SECONDARY_STRESS, &PRIMARY_STRESS.to_string())
+    } else if stress > 1. && !has_primary && !has_secondary && has_vowel {
+        restress(PRIMARY_STRESS)
+    } else {
+        phonemes.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<(), G2PError> {
+        assert_eq!(
+            vec![
+                ("I love ", None),
+                ("Kokoro", Some(Markup::Phonemes("kˈOkəɹO"))),
+                (" and ", None),
+                ("it", Some(Markup::Stress(1.))),
+                ("!", None),
+            ],
+            parse("I love [Kokoro](/kˈOkəɹO/) and [it](+1)!")?
+        );
+        assert_eq!(
+            vec![
+                ("去", None),
+                ("重庆", Some(Markup::Pinyin("chong2 qing4"))),
+                ("和", None),
+                ("长沙", Some(Markup::Pinyin("chang2 sha1"))),
+            ],
+            parse("去[重庆](chong2 qing4)和[长沙](/chang2 sha1/)")?
+        );
+        // 无法识别的标记只保留文本
+        assert_eq!(vec![("word", None)], parse("[word](???)")?);
+        assert_eq!(vec![("word", None)], parse("[word](ma3)")?);
+        assert_eq!(
+            vec![("word", Some(Markup::Phonemes("ma3")))],
+            parse("[word](/ma3/)")?
+        );
+        assert_eq!(vec![("[a] (b)", None)], parse("[a] (b)")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_stress() {
+        assert_eq!("həlˌəʊ", apply_stress("həlˈəʊ", -1.));
+        assert_eq!("hələʊ", apply_stress("hˌəlˈəʊ", -2.));
+        assert_eq!("ðˌə", apply_stress("ðə", 0.5));
+        assert_eq!("ðˈə", apply_stress("ðə", 2.));
+        assert_eq!("ˈænd", apply_stress("ˌænd", 1.));
+        assert_eq!("həlˈəʊ", apply_stress("həlˈəʊ", 1.));
+        assert_eq!("ʃ", apply_stress("ʃ", 2.));
+    }
+}
@@ -0,0 +1,169 @@
+use super::EnglishDialect;
+
+/// misaki美式英语使用的音标，`A`、`I`、`O`、`W`、`Y`分别表示eɪ、aɪ、oʊ、aʊ、ɔɪ
+pub(super) const US_INVENTORY: &str = "AIOWYbdfhijklmnpstuvwzæðŋɑɔəɛɜɡɪɹɾʃʊʌʒʤʧˈˌθᵊᵻ";
+/// misaki英式英语使用的音标，`Q`表示əʊ，长元音带`ː`
+pub(super) const GB_INVENTORY: &str = "AIQWYabdfhijklmnpstuvwzðŋɑɒɔəɛɜɡɪɹʃʊʌʒʤʧˈˌːθᵊ";
+/// 元音，用于判断`ɹ`前后是否有元音
+pub(super) const VOWELS: &str = "AIOQWYaeiouæɑɒɔəɛɜɪʊʌᵻ";
+
+/// 两种口音共用的规则，按顺序匹配，较长的写在前面
+const COMMON_RULES: [(&str, &str); 10] = [
+    ("dʒ", "ʤ"),
+    ("tʃ", "ʧ"),
+    ("eɪ", "A"),
+    ("aɪ", "I"),
+    ("aʊ", "W"),
+    ("ɔɪ", "Y"),
+    ("r", "ɹ"),
+    ("g", "ɡ"),
+    ("ʍ", "w"),
+    ("x", "k"),
+];
+
+/// 美式英语：英式的长元音和非卷舌元音改为美式的写法
+const US_RULES: [(&str, &str); 23] = [
+    ("oʊ", "O"),
+    ("əʊ", "O"),
+    ("Q", "O"),
+    ("ɪə", "ɪɹ"),
+    ("eə", "ɛɹ"),
+    ("ɛə", "ɛɹ"),
+    ("ʊə", "ʊɹ"),
+    ("ɜː", "ɜɹ"),
+    ("ɛː", "ɛɹ"),
+    ("iː", "i"),
+    ("uː", "u"),
+    ("ɑː", "ɑ"),
+    ("ɔː", "ɔ"),
+    ("ː", ""),
+    ("ɝ", "ɜɹ"),
+    ("ɚ", "əɹ"),
+    ("ɒ", "ɑ"),
+    ("a", "æ"),
+    ("e", "ɛ"),
+    ("o", "O"),
+    ("ɐ", "ə"),
+    ("ɫ", "l"),
+    ("ɬ", "l"),
+];
+
+/// 英式英语：元音后面没有元音时`ɹ`不发音，美式的元音改为英式的写法
+const GB_RULES: [(&str, &str); 30] = [
+    ("oʊ", "Q"),
+    ("əʊ", "Q"),
+    ("O", "Q"),
+    ("eə", "ɛː"),
+    ("ɛə", "ɛː"),
+    ("iː", "iː"),
+    ("uː", "uː"),
+    ("ɑː", "ɑː"),
+    ("ɔː", "ɔː"),
+    ("ɜː", "ɜː"),
+    ("ɛː", "ɛː"),
+    ("ɑɹ", "ɑː"),
+    ("ɔɹ", "ɔː"),
+    ("ɪɹ", "ɪə"),
+    ("ɛɹ", "ɛː"),
+    ("ʊɹ", "ʊə"),
+    ("ɜɹ", "ɜː"),
+    ("əɹ", "ə"),
+    ("ɝ", "ɜː"),
+    ("ɚ", "ə"),
+    ("ɑ", "ɒ"),
+    ("ɔ", "ɔː"),
+    ("æ", "a"),
+    ("e", "ɛ"),
+    ("o", "Q"),
+    ("ɐ", "ə"),
+    ("ɾ", "t"),
+    ("ᵻ", "ɪ"),
+    ("ɫ", "l"),
+    ("ɬ", "l"),
+];
+
+/// 把英语前端输出的音标转换为misaki的音标
+///
+/// espeak输出英式的国际音标，cmudict输出美式的国际音标，Kokoro训练时使用的是misaki的音标，该函数把两者统一为`dialect`对应的misaki音标，无法转换的符号会被去掉。对misaki的音标重复调用结果不变。
+///
+/// # 参数
+///
+/// * `ipa` - 一个单词的音标。
+/// * `dialect` - 口音。
+pub(super) fn to_misaki(ipa: &str, dialect: EnglishDialect) -> String {
+    let (rules, inventory) = match dialect {
+        EnglishDialect::American => (&US_RULES[..], US_INVENTORY),
+        EnglishDialect::British => (&GB_RULES[..], GB_INVENTORY),
+    };
+    let mut result = String::with_capacity(ipa.len());
+    let mut rest = ipa;
+    'outer: while let Some(c) = rest.chars().next() {
+        for (from, to) in COMMON_RULES.iter().chain(rules) {
+            let Some(next) = rest.strip_prefix(from) else {
+                continue;
+            };
+            // 英式英语只去掉不在元音前面的`ɹ`，例如care，但不包括caring
+            if from.ends_with('ɹ')
+                && !to.ends_with('ɹ')
+                && next
+                    .trim_start_matches(['ˈ', 'ˌ'])
+                    .starts_with(|c| VOWELS.contains(c))
+            {
+                continue;
+            }
+            result.push_str(to);
+            rest = next;
+            continue 'outer;
+        }
+        if inventory.contains(c) {
+            result.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::tokenizer::is_in_vocab};
+
+    #[test]
+    fn test_inventory() {
+        for c in US_INVENTORY.chars().chain(GB_INVENTORY.chars()) {
+            assert!(is_in_vocab(c, false), "{} is not in VOCAB_V10", c);
+            assert!(is_in_vocab(c, true), "{} is not in VOCAB_V11", c);
+        }
+    }
+
+    #[test]
+    fn test_to_misaki() {
+        use EnglishDialect::*;
+
+        // espeak的英式音标
+        assert_eq!("həlˈO", to_misaki("həlˈəʊ", American));
+        assert_eq!("həlˈQ", to_misaki("həlˈəʊ", British));
+        assert_eq!("wˈɜɹld", to_misaki("wˈɜːld", American));
+        assert_eq!("ʧˈɪldɹɛn", to_misaki("tʃˈɪldɹɛn", American));
+        assert_eq!("dˈAz", to_misaki("dˈeɪz", American));
+        assert_eq!("ˈæpəl", to_misaki("ˈapəl", American));
+        // cmudict的美式音标
+        assert_eq!("hʌlˈO", to_misaki("hʌlˈoʊ", American));
+        assert_eq!("kˈɑː", to_misaki("kˈɑɹ", British));
+        assert_eq!("kˈɛɹɪŋ", to_misaki("kˈɛɹɪŋ", British));
+        assert_eq!("bˈʌtəɹ", to_misaki("bˈʌtɚ", American));
+        // 无法转换的符号被去掉
+        assert_eq!("ɑvðə", to_misaki("ɒ2vðə2||", American));
+
+        for dialect in [American, British] {
+            let inventory = match dialect {
+                American => US_INVENTORY,
+                British => GB_INVENTORY,
+            };
+            for ipa in ["həlˈəʊ", "tʃˈɜːtʃ", "hˈɛɹ", "ʒˈɔɪ", "ˈɪə", "ɾᵻ"] {
+                let misaki = to_misaki(ipa, dialect);
+                assert!(misaki.chars().all(|c| inventory.contains(c)));
+                assert_eq!(misaki, to_misaki(&misaki, dialect));
+            }
+        }
+    }
+}
@@ -0,0 +1,295 @@
+mod en;
+mod hi;
+mod ja;
+mod romance;
+mod symbol;
+mod zh;
+
+use {
+    super::{G2PError, Language, lexicon::is_han},
+    regex::{Captures, Regex},
+    std::iter::repeat_n,
+};
+
+/// 汉语规范化输出的字形
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ChineseVariant {
+    /// 简体，例如“两万”
+    #[default]
+    Simplified,
+    /// 繁体，例如“兩萬”
+    Traditional,
+}
+
+/// 替换规则中生成替换文本的函数，返回`None`时保留原文
+type Replacer = fn(&Captures) -> Option<String>;
+
+/// 编译替换规则中的正则表达式，规则按顺序应用，只需在第一次使用时编译一次
+fn compile(rules: Vec<(&'static str, Replacer)>) -> Result<Vec<(Regex, Replacer)>, regex::Error> {
+    rules
+        .into_iter()
+        .map(|(pattern, replacer)| Ok((Regex::new(pattern)?, replacer)))
+        .collect()
+}
+
+/// 表情符号的读法
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum EmojiReading {
+    /// 读出名称，例如👍读作“thumbs up”或“拇指向上”
+    #[default]
+    Speak,
+    /// 不读出，交给`Fallback`处理
+    Skip,
+    /// 换成停顿
+    Pause,
+}
+
+/// 规范化后的文本，记录每个字节在原文中的字节偏移
+#[derive(Clone, Debug)]
+pub(super) struct Normalized {
+    pub(super) text: String,
+    offsets: Vec<usize>,
+    len: usize,
+}
+
+impl Normalized {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            offsets: (0..text.len()).collect(),
+            len: text.len(),
+        }
+    }
+
+    /// 规范化后的字节位置在原文中的字节偏移，替换得到的文字都对应被替换内容的开头
+    pub(super) fn original(&self, position: usize) -> usize {
+        self.offsets.get(position).copied().unwrap_or(self.len)
+    }
+
+    /// 替换所有匹配，`replacer`返回`None`时保留原文
+    ///
+    /// `replacer`的第一个参数是替换前的整段文本，用于判断匹配所处的语境。
+    fn replace_all<F>(&self, regex: &Regex, mut replacer: F) -> Self
+    where
+        F: FnMut(&str, &Captures) -> Option<String>,
+    {
+        let mut text = String::with_capacity(self.text.len());
+        let mut offsets = Vec::with_capacity(self.offsets.len());
+        let mut start = 0;
+        for caps in regex.captures_iter(&self.text) {
+            let Some(m) = caps.get(0) else {
+                continue;
+            };
+            let Some(replaced) = replacer(&self.text, &caps) else {
+                continue;
+            };
+            text.push_str(&self.text[start..m.start()]);
+            offsets.extend_from_slice(&self.offsets[start..m.start()]);
+            text.push_str(&replaced);
+            offsets.extend(repeat_n(self.original(m.start()), replaced.len()));
+            start = m.end();
+        }
+        text.push_str(&self.text[start..]);
+        offsets.extend_from_slice(&self.offsets[start..]);
+
+        Self {
+            text,
+            offsets,
+            len: self.len,
+        }
+    }
+}
+
+/// 把全角的字母、数字和符号转换为半角，汉语常用的全角标点（，！？：；（））保留，由后续处理转换
+fn to_halfwidth(text: Normalized) -> Result<Normalized, G2PError> {
+    let fullwidth = regex!(r"[\x{FF01}-\x{FF5E}--[，！？：；（）]]+")?;
+    Ok(text.replace_all(fullwidth, |_, caps| {
+        caps[0]
+            .chars()
+            .map(|c| char::from_u32(c as u32 - 0xFEE0))
+            .collect()
+    }))
+}
+
+/// 十进制数字的值，Unicode的十进制数字都是从0到9连续排列的，往前数连续的数字个数即可得到
+fn digit_value(c: char) -> Option<u32> {
+    let is_digit =
+        |c: char| regex!(r"^\p{Nd}$").is_ok_and(|i| i.is_match(c.encode_utf8(&mut [0; 4])));
+    let mut count = 0;
+    while let Some(prev) = char::from_u32(c as u32 - count - 1)
+        && is_digit(prev)
+    {
+        count += 1;
+    }
+    is_digit(c).then_some(count % 10)
+}
+
+/// 把其他文字的十进制数字（例如阿拉伯-印度数字١٢٣、天城文数字१२३）转换为ASCII数字，后续的规则只需处理ASCII数字
+fn to_ascii_digits(text: Normalized) -> Result<Normalized, G2PError> {
+    let digits = regex!(r"[\p{Nd}--0-9]+")?;
+    Ok(text.replace_all(digits, |_, caps| {
+        caps[0]
+            .chars()
+            .map(|c| char::from_digit(digit_value(c)?, 10))
+            .collect()
+    }))
+}
+
+fn is_latin(c: char) -> bool {
+    c.is_alphabetic() && c < '\u{0250}'
+}
+
+/// 判断`text`中`start..end`处的内容是否处于英语语境
+///
+/// 紧跟汉字时视为汉语，例如`3个`、`2024年`；否则以距离最近的文字为准，距离相同时以后面的文字为准；两边都没有文字时，只要文本中有汉字就视为汉语。紧跟在数字后面的单位缩写和身份证号末尾的`X`不算文字，例如`体重60kg`、`身高1.75m`仍是汉语。
+pub(super) fn is_english(text: &str, start: usize, end: usize) -> bool {
+    let suffix = regex!(
+        r"^(\s?(?:km/h|kph|mph|km|kg|cm|mm|mg|ml|mL|lbs|lb|oz|ft|mi|TB|GB|MB|KB|kB|GHz|MHz|kHz|Hz|°C|°F|ms|min|m²|m³|m|g|s|h|L|l|t|X|x))(?:$|\P{L}|\p{Han})"
+    );
+    let end = match suffix.map(|i| i.captures(&text[end..])) {
+        Ok(Some(caps)) if text[..end].ends_with(|c: char| c.is_ascii_digit()) => {
+            end + caps[1].len()
+        }
+        _ => end,
+    };
+    if text[end..]
+        .chars()
+        .find(|c| !c.is_whitespace())
+        .is_some_and(is_han)
+    {
+        return false;
+    }
+
+    let before = text[..start]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_alphabetic())
+        .map(|(i, c)| (start - i, c));
+    let after = text[end..]
+        .char_indices()
+        .find(|(_, c)| c.is_alphabetic())
+        .map(|(i, c)| (i + 1, c));
+    match (before, after) {
+        (Some(before), Some(after)) if before.0 < after.0 => is_latin(before.1),
+        (_, Some((_, c))) | (Some((_, c)), None) => is_latin(c),
+        (None, None) => !text.chars().any(is_han),
+    }
+}
+
+/// 按语境规范化文本中的数字、符号和缩写
+///
+/// 全角字符和其他文字的十进制数字先转换为ASCII，键帽表情换成键帽上的字符。西班牙语、意大利语、葡萄牙语、法语和印地语的数字都读作该语言；其他语言中英语语境的数字读作英语，其余的按`language`读作日语或汉语，汉语使用`variant`指定的字形。表情符号按`emoji`处理，普通话的汉语语境读汉语名称，其他情况读英语名称。
+pub(super) fn normalize(
+    text: &str,
+    variant: ChineseVariant,
+    language: Language,
+    emoji: EmojiReading,
+) -> Result<Normalized, G2PError> {
+    let text = to_ascii_digits(to_halfwidth(Normalized::new(text))?)?;
+    // 键帽表情上的数字与其他数字一样按语言展开
+    let text = symbol::keycaps(text, emoji, language == Language::Mandarin)?;
+    let text = match language {
+        // 这些语言使用拉丁字母，不能按语境判断数字是否属于英语
+        Language::Spanish => romance::spanish(text),
+        Language::Italian => romance::italian(text),
+        Language::Portuguese => romance::portuguese(text),
+        Language::French => romance::french(text),
+        Language::Japanese => ja::normalize(en::normalize(text)?),
+        // 印地语中的拉丁字母按转写读出，数字都读作印地语
+        Language::Hindi => hi::normalize(text),
+        _ => zh::normalize(en::normalize(text)?, variant),
+    }?;
+    // 数字和单位先按语言展开，剩下的符号再读出名称
+    symbol::normalize(text, emoji, language == Language::Mandarin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_english() {
+        let text = "I have 3 apples";
+        assert!(is_english(text, 7, 8));
+        let text = "我有3个苹果";
+        assert!(!is_english(text, 6, 7));
+        let text = "Windows 10系统";
+        assert!(!is_english(text, 8, 10));
+        assert!(is_english("42", 0, 2));
+        assert!(!is_english("42。你好", 0, 2));
+        // 单位和身份证号末尾的X不算英语
+        assert!(!is_english("体重60kg", 6, 8));
+        assert!(!is_english("我60kg重", 3, 5));
+        assert!(!is_english("温度25°C", 6, 8));
+        assert!(is_english("I weigh 60kg", 8, 10));
+        assert!(!is_english("号11010119900307123X", 3, 20));
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), G2PError> {
+        let normalized = normalize(
+            "I have 3 apples and 12 pears",
+            ChineseVariant::Simplified,
+            Language::default(),
+            EmojiReading::default(),
+        )?;
+        assert_eq!("I have three apples and twelve pears", normalized.text);
+        // 替换得到的文字对应数字在原文中的位置
+        assert_eq!(7, normalized.original(7));
+        assert_eq!(7, normalized.original(11));
+        assert_eq!(9, normalized.original(13));
+        assert_eq!(20, normalized.original(24));
+
+        let normalized = normalize(
+            "我有3个苹果和12个梨",
+            ChineseVariant::Simplified,
+            Language::default(),
+            EmojiReading::default(),
+        )?;
+        assert_eq!("我有三个苹果和十二个梨", normalized.text);
+
+        assert_eq!(
+            "I paid five dollars for 三个苹果",
+            normalize(
+                "I paid $5 for 3个苹果",
+                ChineseVariant::Simplified,
+                Language::default(),
+                EmojiReading::default()
+            )?
+            .text
+        );
+
+        // 其他文字的数字按ASCII数字读出
+        let normalize = |text| {
+            normalize(
+                text,
+                ChineseVariant::Simplified,
+                Language::default(),
+                EmojiReading::default(),
+            )
+            .map(|i| i.text)
+        };
+        assert_eq!("one hundred twenty three", normalize("١٢٣")?);
+        assert_eq!("x one hundred twenty three y", normalize("x ١٢٣ y")?);
+        assert_eq!("room forty two", normalize("room ۴۲")?);
+        assert_eq!("我有三个苹果", normalize("我有३个苹果")?);
+        // 键帽表情上的数字按语言读出
+        assert_eq!("one", normalize("1️⃣")?);
+        assert_eq!("step two", normalize("step 2️⃣")?);
+        assert_eq!("第三名", normalize("第3️⃣名")?);
+        // 汉语中带单位的数字和身份证号按汉语读出
+        assert_eq!("体重六十千克", normalize("体重60kg")?);
+        assert_eq!("身高一点七五米", normalize("身高1.75m")?);
+        assert_eq!("温度二十五摄氏度", normalize("温度25°C")?);
+        assert_eq!("温度是负三摄氏度", normalize("温度是-3℃")?);
+        assert_eq!(
+            "身份证号幺幺零幺零幺幺九九零零三零七幺二三X",
+            normalize("身份证号11010119900307123X")?
+        );
+        assert_eq!("I weigh sixty kilograms", normalize("I weigh 60kg")?);
+        assert_eq!(Some(7), digit_value('\u{1D7D5}'));
+        assert_eq!(None, digit_value('x'));
+
+        Ok(())
+    }
+}
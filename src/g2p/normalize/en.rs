@@ -0,0 +1,608 @@
+use {
+    super::{super::G2PError, Normalized, Replacer, compile, is_english},
+    regex::{Captures, Regex},
+    std::sync::LazyLock,
+};
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const SCALES: [(u64, &str); 4] = [
+    (1_000_000_000_000, "trillion"),
+    (1_000_000_000, "billion"),
+    (1_000_000, "million"),
+    (1_000, "thousand"),
+];
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+/// 单位的缩写、单数和复数
+const UNITS: [(&str, &str, &str); 32] = [
+    ("km/h", "kilometer per hour", "kilometers per hour"),
+    ("kph", "kilometer per hour", "kilometers per hour"),
+    ("mph", "mile per hour", "miles per hour"),
+    ("km", "kilometer", "kilometers"),
+    ("kg", "kilogram", "kilograms"),
+    ("cm", "centimeter", "centimeters"),
+    ("mm", "millimeter", "millimeters"),
+    ("mg", "milligram", "milligrams"),
+    ("ml", "milliliter", "milliliters"),
+    ("mL", "milliliter", "milliliters"),
+    ("lbs", "pound", "pounds"),
+    ("lb", "pound", "pounds"),
+    ("oz", "ounce", "ounces"),
+    ("ft", "foot", "feet"),
+    ("mi", "mile", "miles"),
+    ("TB", "terabyte", "terabytes"),
+    ("GB", "gigabyte", "gigabytes"),
+    ("MB", "megabyte", "megabytes"),
+    ("KB", "kilobyte", "kilobytes"),
+    ("kB", "kilobyte", "kilobytes"),
+    ("GHz", "gigahertz", "gigahertz"),
+    ("MHz", "megahertz", "megahertz"),
+    ("kHz", "kilohertz", "kilohertz"),
+    ("Hz", "hertz", "hertz"),
+    ("°C", "degree Celsius", "degrees Celsius"),
+    ("°F", "degree Fahrenheit", "degrees Fahrenheit"),
+    ("ms", "millisecond", "milliseconds"),
+    ("min", "minute", "minutes"),
+    ("m", "meter", "meters"),
+    ("g", "gram", "grams"),
+    ("s", "second", "seconds"),
+    ("h", "hour", "hours"),
+];
+
+fn below_thousand(n: u64, words: &mut Vec<&'static str>) {
+    if n >= 100 {
+        words.push(ONES[(n / 100) as usize]);
+        words.push("hundred");
+    }
+    match n % 100 {
+        0 => (),
+        n @ 1..20 => words.push(ONES[n as usize]),
+        n => {
+            words.push(TENS[(n / 10) as usize]);
+            if n % 10 != 0 {
+                words.push(ONES[(n % 10) as usize]);
+            }
+        }
+    }
+}
+
+/// 基数词，例如`1234`读作`one thousand two hundred thirty four`
+pub(super) fn cardinal(n: u64) -> String {
+    if n == 0 {
+        return ONES[0].to_owned();
+    }
+    let mut words = Vec::new();
+    let Some((scale, name)) = SCALES.iter().find(|(scale, _)| n >= *scale) else {
+        below_thousand(n, &mut words);
+        return words.join(" ");
+    };
+    let mut result = format!("{} {}", cardinal(n / scale), name);
+    if !n.is_multiple_of(*scale) {
+        result.push(' ');
+        result.push_str(&cardinal(n % scale));
+    }
+    result
+}
+
+/// 序数词，例如`21`读作`twenty first`
+pub(super) fn ordinal(n: u64) -> String {
+    let cardinal = cardinal(n);
+    let (head, last) = cardinal
+        .rsplit_once(' ')
+        .map_or(("", cardinal.as_str()), |(h, l)| (h, l));
+    let last = match last {
+        "one" => "first".to_owned(),
+        "two" => "second".to_owned(),
+        "three" => "third".to_owned(),
+        "five" => "fifth".to_owned(),
+        "eight" => "eighth".to_owned(),
+        "nine" => "ninth".to_owned(),
+        "twelve" => "twelfth".to_owned(),
+        l if l.ends_with('y') => format!("{}ieth", &l[..l.len() - 1]),
+        l => format!("{}th", l),
+    };
+    if head.is_empty() {
+        last
+    } else {
+        format!("{} {}", head, last)
+    }
+}
+
+/// 逐位读出数字
+pub(super) fn digits(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| ONES[d as usize])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 读出数字，可以带千位分隔符和小数部分；以0开头或过长的整数逐位读出
+pub(super) fn number(text: &str) -> String {
+    let (int, frac) = text.split_once('.').unwrap_or((text, ""));
+    let int = int.replace(',', "");
+    let mut result = match int.parse::<u64>() {
+        Ok(n) if (int.len() == 1 || !int.starts_with('0')) && int.len() <= 15 => cardinal(n),
+        _ => digits(&int),
+    };
+    if !frac.is_empty() {
+        result.push_str(" point ");
+        result.push_str(&digits(frac));
+    }
+    result
+}
+
+/// 年份，例如`1999`读作`nineteen ninety nine`，`2005`读作`two thousand five`
+pub(super) fn year(n: u64) -> String {
+    match (n / 100, n % 100) {
+        (_, _) if !(1000..10000).contains(&n) => cardinal(n),
+        (20, y) if y < 10 => cardinal(n),
+        (h, 0) => format!("{} hundred", cardinal(h)),
+        (h, y) if y < 10 => format!("{} oh {}", cardinal(h), ONES[y as usize]),
+        (h, y) => format!("{} {}", cardinal(h), cardinal(y)),
+    }
+}
+
+/// 复数形式，用于年代，例如`ninety`变为`nineties`
+fn plural(words: &str) -> String {
+    match words.strip_suffix('y') {
+        Some(w) => format!("{}ies", w),
+        None if words.ends_with('x') => format!("{}es", words),
+        None => format!("{}s", words),
+    }
+}
+
+fn month(name: &str) -> Option<&'static str> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    if name.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .find(|m| m.to_lowercase().starts_with(&name))
+        .copied()
+}
+
+fn date(year_text: Option<&str>, month: usize, day: u64) -> Option<String> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut result = format!("{} {}", MONTHS[month - 1], ordinal(day));
+    if let Some(y) = year_text {
+        let mut y = y.parse::<u64>().ok()?;
+        if y < 100 {
+            y += if y < 50 { 2000 } else { 1900 };
+        }
+        result.push_str(", ");
+        result.push_str(&year(y));
+    }
+    Some(result)
+}
+
+/// 缩写前面是否是街道名，例如`Mulholland Dr.`；句首的大写单词不算
+fn after_street_name(before: &str) -> bool {
+    let before = before.trim_end();
+    let rest = before.trim_end_matches(char::is_alphanumeric);
+    before[rest.len()..].starts_with(char::is_uppercase)
+        && rest
+            .trim_end()
+            .chars()
+            .next_back()
+            .is_some_and(|c| !matches!(c, '.' | '!' | '?'))
+}
+
+fn abbreviation(caps: &Captures, street: bool) -> Option<String> {
+    let next_is_name = caps
+        .get(2)
+        .is_some_and(|i| i.as_str().starts_with(char::is_uppercase));
+    let next_is_number = caps
+        .get(2)
+        .is_some_and(|i| i.as_str().starts_with(|c: char| c.is_ascii_digit()));
+    let word = match &caps[1] {
+        "Mr" => "Mister",
+        "Mrs" => "Missus",
+        "Ms" => "Miz",
+        "Prof" => "Professor",
+        "Jr" => "Junior",
+        "Sr" => "Senior",
+        "Mt" => "Mount",
+        "vs" => "versus",
+        "etc" => "et cetera",
+        "approx" => "approximately",
+        "dept" | "Dept" => "department",
+        "Ave" => "Avenue",
+        "Rd" => "Road",
+        "Blvd" => "Boulevard",
+        "Dr" if street => "Drive",
+        "Dr" => "Doctor",
+        "St" if next_is_name => "Saint",
+        "St" => "Street",
+        "No" | "no" if next_is_number => "number",
+        _ => return None,
+    };
+    Some(format!("{}{}", word, caps.get(2).map_or("", |_| " ")))
+}
+
+fn currency(caps: &Captures) -> Option<String> {
+    let (unit, units, cent, cents) = match &caps[1] {
+        "$" => ("dollar", "dollars", "cent", "cents"),
+        "£" => ("pound", "pounds", "penny", "pence"),
+        "€" => ("euro", "euros", "cent", "cents"),
+        _ => return None,
+    };
+    let int = caps[2].replace(',', "");
+    let value = int.parse::<u64>().ok()?;
+    if let Some(scale) = caps.get(4) {
+        let amount = match caps.get(3) {
+            Some(frac) => format!("{} point {}", number(&int), digits(frac.as_str())),
+            None => number(&int),
+        };
+        return Some(format!("{} {} {}", amount, scale.as_str(), units));
+    }
+
+    let cents_value = caps.get(3).map_or(0, |i| {
+        let frac = i.as_str();
+        frac.parse::<u64>().unwrap_or_default() * if frac.len() == 1 { 10 } else { 1 }
+    });
+    let major = format!("{} {}", number(&int), if value == 1 { unit } else { units });
+    let minor = format!(
+        "{} {}",
+        cardinal(cents_value),
+        if cents_value == 1 { cent } else { cents }
+    );
+    Some(match (value, cents_value) {
+        (0, c) if c > 0 => minor,
+        (_, 0) => major,
+        _ => format!("{} and {}", major, minor),
+    })
+}
+
+fn time(caps: &Captures) -> Option<String> {
+    let hour = caps[1].parse::<u64>().ok()?;
+    let minute = caps[2].parse::<u64>().ok()?;
+    if hour > 24 || minute > 59 {
+        return None;
+    }
+    let mut result = match minute {
+        0 if caps.get(4).is_some() => cardinal(hour),
+        0 => format!("{} o clock", cardinal(hour)),
+        m if m < 10 => format!("{} oh {}", cardinal(hour), ONES[m as usize]),
+        m => format!("{} {}", cardinal(hour), cardinal(m)),
+    };
+    if let Some(second) = caps.get(3) {
+        let second = second.as_str().parse::<u64>().ok()?;
+        result.push_str(&format!(
+            " and {} {}",
+            cardinal(second),
+            if second == 1 { "second" } else { "seconds" }
+        ));
+    }
+    if let Some(period) = caps.get(4) {
+        let period = period.as_str().replace('.', "").to_uppercase();
+        result.push(' ');
+        result.push_str(&period);
+    }
+    Some(result)
+}
+
+fn phone(caps: &Captures) -> Option<String> {
+    let groups = (1..=4)
+        .filter_map(|i| caps.get(i))
+        .map(|i| digits(i.as_str()))
+        .collect::<Vec<_>>();
+    Some(groups.join(", "))
+}
+
+fn unit(caps: &Captures) -> Option<String> {
+    let unit = caps.get(2).or(caps.get(3))?.as_str();
+    let value = &caps[1];
+    let (_, singular, plural) = UNITS.iter().find(|(u, _, _)| *u == unit)?;
+    Some(format!(
+        "{} {}",
+        number(value),
+        if value == "1" { singular } else { plural }
+    ))
+}
+
+type Rule = (&'static str, fn(&Captures) -> Option<String>);
+
+/// 英语规则，按顺序应用
+fn rules() -> Vec<Rule> {
+    vec![
+        // 电话号码：555-123-4567、(555) 123-4567、+1 555 123 4567
+        (
+            r"(?:\+(1)[-. ]?)?\(?\b(\d{3})\)?[-. ](\d{3})[-. ](\d{4})\b",
+            phone,
+        ),
+        // 日期：2024-01-05
+        (r"\b(\d{4})-(\d{1,2})-(\d{1,2})\b", |caps| {
+            date(Some(&caps[1]), caps[2].parse().ok()?, caps[3].parse().ok()?)
+        }),
+        // 日期：1/5/2024，按月/日/年读
+        (r"\b(\d{1,2})/(\d{1,2})/(\d{4}|\d{2})\b", |caps| {
+            date(Some(&caps[3]), caps[1].parse().ok()?, caps[2].parse().ok()?)
+        }),
+        // 日期：January 5th, 2024、Jan. 5
+        (
+            r"(?i)\b(January|February|March|April|May|June|July|August|September|October|November|December|Jan|Feb|Mar|Apr|Jun|Jul|Aug|Sept|Sep|Oct|Nov|Dec)\.?\s+(\d{1,2})(?:st|nd|rd|th)?\b(?:,?\s+(\d{4})\b)?",
+            |caps| {
+                let month = month(&caps[1])?;
+                let month = 1 + MONTHS.iter().position(|m| *m == month)?;
+                date(
+                    caps.get(3).map(|i| i.as_str()),
+                    month,
+                    caps[2].parse().ok()?,
+                )
+            },
+        ),
+        // 时间：3:30、3:30 pm、15:04:05
+        (
+            r"\b(\d{1,2}):(\d{2})(?::(\d{2}))?(?:\s?([AaPp]\.?[Mm]\.?))?",
+            time,
+        ),
+        // 货币：$3.50、£2、€1,000、$2.5 million
+        (
+            r"([$£€])\s?(\d{1,3}(?:,\d{3})+|\d+)(?:\.(\d+))?(?:\s(thousand|million|billion|trillion)\b)?",
+            currency,
+        ),
+        // 百分比
+        (r"(\d+(?:\.\d+)?)\s?%", |caps| {
+            Some(format!("{} percent", number(&caps[1])))
+        }),
+        // 序数词：1st、22nd
+        (r"\b(\d+)(?:st|nd|rd|th)\b", |caps| {
+            Some(ordinal(caps[1].parse().ok()?))
+        }),
+        // 年代：1990s
+        (r"\b(1\d|20)(\d0)s\b", |caps| {
+            Some(plural(&year(
+                format!("{}{}", &caps[1], &caps[2]).parse().ok()?,
+            )))
+        }),
+        // 年代的简写：the 80s、'90s，不是秒
+        (r"'?\b([1-9]0)s\b", |caps| {
+            Some(plural(&cardinal(caps[1].parse().ok()?)))
+        }),
+        // 单位：5kg、2.5 km、100°C
+        (
+            r"(\d+(?:\.\d+)?)(?:\s?(km/h|kph|mph|km|kg|cm|mm|mg|ml|mL|lbs|lb|oz|ft|mi|TB|GB|MB|KB|kB|GHz|MHz|kHz|Hz|°C|°F|ms|min)|(m|g|s|h))\b",
+            unit,
+        ),
+        // 范围：3-5
+        (r"\b(\d+)\s?[-–]\s?(\d+)\b", |caps| {
+            Some(format!("{} to {}", number(&caps[1]), number(&caps[2])))
+        }),
+        // 年份：in 1999、since 2024、May 2024，没有上下文的四位数按基数词读
+        (
+            r"(?i)\b((?:in|since|by|of|from|until|till|before|after|during|around|circa|year|January|February|March|April|May|June|July|August|September|October|November|December|Jan|Feb|Mar|Apr|Jun|Jul|Aug|Sept|Sep|Oct|Nov|Dec)\.?\s+)(1[1-9]\d{2}|20\d{2})\b",
+            |caps| Some(format!("{}{}", &caps[1], year(caps[2].parse().ok()?))),
+        ),
+        // 年份：1066 AD、44 BC、AD 800
+        (
+            r"\b(?:(\d{1,4})\s?(AD|BC|BCE|CE)|(AD)\s+(\d{1,4}))\b",
+            |caps| {
+                Some(match (caps.get(1), caps.get(4)) {
+                    (Some(y), _) => format!("{} {}", year(y.as_str().parse().ok()?), &caps[2]),
+                    (_, Some(y)) => format!("{} {}", &caps[3], year(y.as_str().parse().ok()?)),
+                    _ => return None,
+                })
+            },
+        ),
+        // 年份：2024's
+        (r"\b(1[1-9]\d{2}|20\d{2})'s\b", |caps| {
+            Some(format!("{}'s", year(caps[1].parse().ok()?)))
+        }),
+        // 负数
+        (r"(^|[\s(])[-−](\d[\d,]*(?:\.\d+)?)", |caps| {
+            Some(format!("{}minus {}", &caps[1], number(&caps[2])))
+        }),
+        // 其余的数字
+        (r"\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d+(?:\.\d+)?", |caps| {
+            Some(number(&caps[0]))
+        }),
+    ]
+}
+
+/// 把英语语境中的数字、符号和常见缩写展开为单词
+pub(super) fn normalize(mut text: Normalized) -> Result<Normalized, G2PError> {
+    let abbreviations = regex!(
+        r"\b(Mrs|Mr|Ms|Prof|Jr|Sr|Mt|vs|etc|approx|[Dd]ept|Ave|Rd|Blvd|Dr|St|No|no)\.(?:\s+(\w))?",
+    )?;
+    text = text.replace_all(abbreviations, |text, caps| {
+        let m = caps.get(0)?;
+        let expanded = abbreviation(caps, after_street_name(&text[..m.start()]))?;
+        Some(match caps.get(2) {
+            Some(next) => format!("{}{}", expanded, next.as_str()),
+            // 后面没有单词时缩写点同时是句号，紧跟其他标点时才去掉
+            None if text[m.end()..].starts_with([',', ';', ':', '!', '?', '.']) => expanded,
+            None => format!("{}.", expanded),
+        })
+    });
+    let latin = regex!(r"\b(?:e\.g\.|i\.e\.)")?;
+    text = text.replace_all(latin, |_, caps| {
+        Some(
+            if caps[0].starts_with('e') {
+                "for example"
+            } else {
+                "that is"
+            }
+            .to_owned(),
+        )
+    });
+
+    static RULES: LazyLock<Result<Vec<(Regex, Replacer)>, regex::Error>> =
+        LazyLock::new(|| compile(rules()));
+    for (regex, rule) in RULES.as_ref().map_err(Clone::clone)? {
+        text = text.replace_all(regex, |text, caps| {
+            let m = caps.get(0)?;
+            if !is_english(text, m.start(), m.end()) {
+                return None;
+            }
+            // 与字母相连的数字（MP3、GPT-4o）单独读出，不与字母拼成一个单词
+            let digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+            let letter = |c: Option<char>| c.is_some_and(char::is_alphabetic);
+            let space = |inner, outer| {
+                if digit(inner) && letter(outer) {
+                    " "
+                } else {
+                    ""
+                }
+            };
+            Some(format!(
+                "{}{}{}",
+                space(
+                    m.as_str().chars().next(),
+                    text[..m.start()].chars().next_back()
+                ),
+                rule(caps)?,
+                space(
+                    m.as_str().chars().next_back(),
+                    text[m.end()..].chars().next()
+                )
+            ))
+        });
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(text: &str) -> Result<String, G2PError> {
+        Ok(super::normalize(Normalized::new(text))?.text)
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!("zero", cardinal(0));
+        assert_eq!("one thousand two hundred thirty four", cardinal(1234));
+        assert_eq!("two million five", cardinal(2_000_005));
+        assert_eq!("twenty first", ordinal(21));
+        assert_eq!("twelfth", ordinal(12));
+        assert_eq!("one hundredth", ordinal(100));
+        assert_eq!("ninetieth", ordinal(90));
+        assert_eq!("nineteen ninety nine", year(1999));
+        assert_eq!("two thousand five", year(2005));
+        assert_eq!("twenty twenty four", year(2024));
+        assert_eq!("nineteen hundred", year(1900));
+        assert_eq!("nineteen oh five", year(1905));
+        assert_eq!("three point one four", number("3.14"));
+        assert_eq!("zero zero seven", number("007"));
+        assert_eq!("one million", number("1,000,000"));
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), G2PError> {
+        assert_eq!("I have three apples", normalize("I have 3 apples")?);
+        assert_eq!(
+            "It costs three dollars and fifty cents",
+            normalize("It costs $3.50")?
+        );
+        assert_eq!("one pound", normalize("£1")?);
+        assert_eq!("two point five million euros", normalize("€2.5 million")?);
+        assert_eq!("fifty percent off", normalize("50% off")?);
+        assert_eq!(
+            "on January fifth, twenty twenty four",
+            normalize("on 2024-01-05")?
+        );
+        assert_eq!(
+            "on March third, nineteen ninety nine",
+            normalize("on 3/3/1999")?
+        );
+        assert_eq!(
+            "on July fourth, seventeen seventy six",
+            normalize("on July 4th, 1776")?
+        );
+        assert_eq!("at three thirty PM", normalize("at 3:30 pm")?);
+        assert_eq!("at seven o clock", normalize("at 7:00")?);
+        assert_eq!("at nine oh five", normalize("at 9:05")?);
+        assert_eq!("in nineteen ninety nine", normalize("in 1999")?);
+        assert_eq!("since eighteen fifty", normalize("since 1850")?);
+        assert_eq!("in May twenty twenty four", normalize("in May 2024")?);
+        assert_eq!("forty four BC", normalize("44 BC")?);
+        assert_eq!("AD ten sixty six", normalize("AD 1066")?);
+        assert_eq!("twenty twenty four's best", normalize("2024's best")?);
+        assert_eq!(
+            "I have two thousand twenty four apples",
+            normalize("I have 2024 apples")?
+        );
+        assert_eq!(
+            "one thousand five hundred people",
+            normalize("1500 people")?
+        );
+        assert_eq!("the nineteen nineties", normalize("the 1990s")?);
+        assert_eq!("the eighties music", normalize("the 80s music")?);
+        assert_eq!("the nineties", normalize("the '90s")?);
+        assert_eq!("five seconds", normalize("5s")?);
+        assert_eq!(
+            "call five five five, one two three, four five six seven",
+            normalize("call 555-123-4567")?
+        );
+        assert_eq!("the twenty second floor", normalize("the 22nd floor")?);
+        assert_eq!("five kilograms and one meter", normalize("5kg and 1m")?);
+        assert_eq!("thirty seven degrees Celsius", normalize("37°C")?);
+        assert_eq!("pages three to five", normalize("pages 3-5")?);
+        assert_eq!("it is minus five", normalize("it is -5")?);
+        assert_eq!(
+            "Doctor Smith lives on Main Street, next to Saint Paul",
+            normalize("Dr. Smith lives on Main St., next to St. Paul")?
+        );
+        assert_eq!("number seven", normalize("No. 7")?);
+        assert_eq!(
+            "apples, pears, et cetera.",
+            normalize("apples, pears, etc.")?
+        );
+        assert_eq!("Go to the Doctor.", normalize("Go to the Dr.")?);
+        assert_eq!(
+            "She lives on Mulholland Drive.",
+            normalize("She lives on Mulholland Dr.")?
+        );
+        assert_eq!("Ask Doctor Lee", normalize("Ask Dr. Lee")?);
+        assert_eq!("for example this", normalize("e.g. this")?);
+        assert_eq!("MP three and GPT-four o", normalize("MP3 and GPT-4o")?);
+        assert_eq!("COVID-nineteen", normalize("COVID-19")?);
+
+        // 汉语语境中的数字不读作英语
+        assert_eq!("我有3个苹果", normalize("我有3个苹果")?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use super::{super::G2PError, Normalized};
+
+/// 0到99，印地语的两位数都有各自的读法
+const BELOW_HUNDRED: [&str; 100] = [
+    "शून्य",
+    "एक",
+    "दो",
+    "तीन",
+    "चार",
+    "पाँच",
+    "छह",
+    "सात",
+    "आठ",
+    "नौ",
+    "दस",
+    "ग्यारह",
+    "बारह",
+    "तेरह",
+    "चौदह",
+    "पंद्रह",
+    "सोलह",
+    "सत्रह",
+    "अठारह",
+    "उन्नीस",
+    "बीस",
+    "इक्कीस",
+    "बाईस",
+    "तेईस",
+    "चौबीस",
+    "पच्चीस",
+    "छब्बीस",
+    "सत्ताईस",
+    "अट्ठाईस",
+    "उनतीस",
+    "तीस",
+    "इकतीस",
+    "बत्तीस",
+    "तैंतीस",
+    "चौंतीस",
+    "पैंतीस",
+    "छत्तीस",
+    "सैंतीस",
+    "अड़तीस",
+    "उनतालीस",
+    "चालीस",
+    "इकतालीस",
+    "बयालीस",
+    "तैंतालीस",
+    "चवालीस",
+    "पैंतालीस",
+    "छियालीस",
+    "सैंतालीस",
+    "अड़तालीस",
+    "उनचास",
+    "पचास",
+    "इक्यावन",
+    "बावन",
+    "तिरपन",
+    "चौवन",
+    "पचपन",
+    "छप्पन",
+    "सत्तावन",
+    "अट्ठावन",
+    "उनसठ",
+    "साठ",
+    "इकसठ",
+    "बासठ",
+    "तिरसठ",
+    "चौंसठ",
+    "पैंसठ",
+    "छियासठ",
+    "सड़सठ",
+    "अड़सठ",
+    "उनहत्तर",
+    "सत्तर",
+    "इकहत्तर",
+    "बहत्तर",
+    "तिहत्तर",
+    "चौहत्तर",
+    "पचहत्तर",
+    "छिहत्तर",
+    "सतहत्तर",
+    "अठहत्तर",
+    "उन्यासी",
+    "अस्सी",
+    "इक्यासी",
+    "बयासी",
+    "तिरासी",
+    "चौरासी",
+    "पचासी",
+    "छियासी",
+    "सत्तासी",
+    "अट्ठासी",
+    "नवासी",
+    "नब्बे",
+    "इक्यानबे",
+    "बानबे",
+    "तिरानबे",
+    "चौरानबे",
+    "पंचानबे",
+    "छियानबे",
+    "सत्तानबे",
+    "अट्ठानबे",
+    "निन्यानबे",
+];
+/// 印度计数法的单位：千、十万、千万、十亿、千亿
+const SCALES: [(u64, &str); 5] = [
+    (100_000_000_000, "खरब"),
+    (1_000_000_000, "अरब"),
+    (10_000_000, "करोड़"),
+    (100_000, "लाख"),
+    (1000, "हज़ार"),
+];
+
+/// 逐位读出数字
+fn digits(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| BELOW_HUNDRED[d as usize])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 读出1到999
+fn below_thousand(n: u64) -> String {
+    let mut words = Vec::new();
+    if n >= 100 {
+        words.push(format!("{} सौ", BELOW_HUNDRED[(n / 100) as usize]));
+    }
+    if !n.is_multiple_of(100) {
+        words.push(BELOW_HUNDRED[(n % 100) as usize].to_owned());
+    }
+    words.join(" ")
+}
+
+/// 按印度计数法读出小于一万亿的整数，例如१२,३४,५६७读作बारह लाख चौंतीस हज़ार पाँच सौ सड़सठ
+fn cardinal(n: u64) -> String {
+    if n == 0 {
+        return BELOW_HUNDRED[0].to_owned();
+    }
+    let mut words = Vec::new();
+    let mut rest = n;
+    for (scale, name) in SCALES {
+        if rest >= scale {
+            words.push(format!("{} {}", below_thousand(rest / scale), name));
+            rest %= scale;
+        }
+    }
+    if rest > 0 {
+        words.push(below_thousand(rest));
+    }
+    words.join(" ")
+}
+
+/// 读出整数或小数，`,`是千位分隔符，小数部分逐位读出；以0开头或超过一万亿的数字逐位读出
+fn number(text: &str) -> String {
+    // 天城文数字换成阿拉伯数字
+    let text = text
+        .chars()
+        .map(|c| match c {
+            '०'..='९' => char::from_digit(c as u32 - '०' as u32, 10).unwrap_or(c),
+            _ => c,
+        })
+        .collect::<String>();
+    let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+    let int = int.replace(',', "");
+    let mut result = match int.parse::<u64>() {
+        Ok(n) if (int == "0" || !int.starts_with('0')) && n < 1_000_000_000_000 => cardinal(n),
+        _ => digits(&int),
+    };
+    if !frac.is_empty() {
+        result.push_str(" दशमलव ");
+        result.push_str(&digits(frac));
+    }
+    result
+}
+
+/// 把印地语文本中的数字、负号和百分号展开为单词，包括天城文数字
+pub(super) fn normalize(text: Normalized) -> Result<Normalized, G2PError> {
+    let number_pattern =
+        regex!(r"(^|[^\w-])(-?)([0-9०-९]+(?:,[0-9०-९]+)*(?:\.[0-9०-९]+)?)(\s?%)?")?;
+    Ok(text.replace_all(number_pattern, |_, caps| {
+        let mut result = caps[1].to_owned();
+        if !caps[2].is_empty() {
+            result.push_str("ऋण ");
+        }
+        result.push_str(&number(&caps[3]));
+        if caps.get(4).is_some() {
+            result.push_str(" प्रतिशत");
+        }
+        Some(result)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!("शून्य", cardinal(0));
+        assert_eq!("सड़सठ", cardinal(67));
+        assert_eq!("एक सौ", cardinal(100));
+        assert_eq!("दो सौ पाँच", cardinal(205));
+        assert_eq!("दो हज़ार चौबीस", cardinal(2024));
+        assert_eq!("बारह लाख चौंतीस हज़ार पाँच सौ सड़सठ", cardinal(1_234_567));
+        assert_eq!("तीन करोड़", cardinal(30_000_000));
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), G2PError> {
+        let normalize = |text| normalize(Normalized::new(text)).map(|i| i.text);
+        assert_eq!("मेरे पास पच्चीस रुपये हैं", normalize("मेरे पास 25 रुपये हैं")?);
+        assert_eq!("बारह लाख", normalize("१२,००,०००")?);
+        assert_eq!("ऋण तीन दशमलव पाँच प्रतिशत", normalize("-3.5%")?);
+        assert_eq!("शून्य शून्य सात", normalize("007")?);
+        Ok(())
+    }
+}
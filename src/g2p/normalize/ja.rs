@@ -0,0 +1,349 @@
+use {
+    super::{super::G2PError, Normalized, Replacer, compile, is_english},
+    regex::{Captures, Regex},
+    std::sync::LazyLock,
+};
+
+const DIGITS: [&str; 10] = [
+    "ぜろ",
+    "いち",
+    "に",
+    "さん",
+    "よん",
+    "ご",
+    "ろく",
+    "なな",
+    "はち",
+    "きゅう",
+];
+/// 万进的单位
+const UNITS: [&str; 4] = ["", "まん", "おく", "ちょう"];
+/// 用和语数词读的“～つ”
+const TSU: [&str; 10] = [
+    "ひとつ",
+    "ふたつ",
+    "みっつ",
+    "よっつ",
+    "いつつ",
+    "むっつ",
+    "ななつ",
+    "やっつ",
+    "ここのつ",
+    "とお",
+];
+
+/// 逐位读出数字
+fn digits(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| DIGITS[d as usize])
+        .collect()
+}
+
+/// 读出1到9999，百和千按习惯变音，例如さんびゃく、はっせん
+fn group(n: u64) -> String {
+    let mut result = String::new();
+    match n / 1000 {
+        0 => (),
+        1 => result.push_str("せん"),
+        3 => result.push_str("さんぜん"),
+        8 => result.push_str("はっせん"),
+        d => result.push_str(&format!("{}せん", DIGITS[d as usize])),
+    }
+    match n / 100 % 10 {
+        0 => (),
+        1 => result.push_str("ひゃく"),
+        3 => result.push_str("さんびゃく"),
+        6 => result.push_str("ろっぴゃく"),
+        8 => result.push_str("はっぴゃく"),
+        d => result.push_str(&format!("{}ひゃく", DIGITS[d as usize])),
+    }
+    match n / 10 % 10 {
+        0 => (),
+        1 => result.push_str("じゅう"),
+        d => result.push_str(&format!("{}じゅう", DIGITS[d as usize])),
+    }
+    match n % 10 {
+        0 => (),
+        d => result.push_str(DIGITS[d as usize]),
+    }
+    result
+}
+
+/// 读出整数或小数，例如`12.5`读作“じゅうにてんご”
+///
+/// 以0开头或超过一万兆的数字逐位读出。
+fn cardinal(text: &str) -> String {
+    let (int, frac) = text.split_once('.').unwrap_or((text, ""));
+    let int = int.replace(',', "");
+    let mut result = match int.parse::<u64>() {
+        Ok(0) => DIGITS[0].to_owned(),
+        Ok(n) if !int.starts_with('0') && n < 10_000_u64.pow(4) => {
+            let mut result = String::new();
+            for (i, unit) in UNITS.iter().enumerate().rev() {
+                let g = n / 10_000_u64.pow(i as u32) % 10_000;
+                if g == 0 {
+                    continue;
+                }
+                let mut reading = group(g);
+                // いっちょう、はっちょう、じゅっちょう
+                if *unit == "ちょう" {
+                    for (from, to) in [("いち", "いっ"), ("はち", "はっ"), ("じゅう", "じゅっ")]
+                    {
+                        if let Some(stem) = reading.strip_suffix(from) {
+                            reading = format!("{}{}", stem, to);
+                            break;
+                        }
+                    }
+                }
+                result.push_str(&reading);
+                result.push_str(unit);
+            }
+            result
+        }
+        _ => digits(&int),
+    };
+    if !frac.is_empty() {
+        result.push_str("てん");
+        result.push_str(&digits(frac));
+    }
+    result
+}
+
+/// 把数字读音的词尾替换为变音后的形式，只替换第一个匹配的词尾
+fn change_suffix(number: &str, table: &[(&str, &str)]) -> String {
+    table
+        .iter()
+        .find_map(|(from, to)| {
+            number
+                .strip_suffix(from)
+                .map(|stem| format!("{}{}", stem, to))
+        })
+        .unwrap_or_else(|| number.to_owned())
+}
+
+/// 促音化的数字词尾，例如いっこ、ろっかい、じゅっぷん
+const GEMINATE: [(&str, &str); 5] = [
+    ("いち", "いっ"),
+    ("ろく", "ろっ"),
+    ("はち", "はっ"),
+    ("じゅう", "じゅっ"),
+    ("ひゃく", "ひゃっ"),
+];
+
+/// 读出日期或天数，1日作为日期读作ついたち，作为天数读作いちにち
+fn day(n: u64, number: &str, date: bool) -> String {
+    const DAYS: [&str; 10] = [
+        "ついたち",
+        "ふつか",
+        "みっか",
+        "よっか",
+        "いつか",
+        "むいか",
+        "なのか",
+        "ようか",
+        "ここのか",
+        "とおか",
+    ];
+    match n {
+        1 if !date => "いちにち".to_owned(),
+        1..=10 => DAYS[n as usize - 1].to_owned(),
+        20 => "はつか".to_owned(),
+        _ if number.ends_with("よん") => change_suffix(number, &[("よん", "よっか")]),
+        _ => {
+            let number = change_suffix(number, &[("なな", "しち"), ("きゅう", "く")]);
+            format!("{}にち", number)
+        }
+    }
+}
+
+/// 带量词的数字，数字和量词按习惯变音，例如さんぼん、いっぱい、よっか
+///
+/// 以は、ひ、ふ、ほ开头的量词在促音后读作半浊音，在ん后读作浊音；其他量词在促音化的数字后面不变音。
+///
+/// # 参数
+///
+/// * `n` - 数值。
+/// * `number` - 数字的读音。
+/// * `unit` - 量词。
+fn counted(n: u64, number: &str, unit: &str) -> Option<String> {
+    // 促音后、ん后的读音，None表示与原读音相同
+    let (kana, plosive, voiced) = match unit {
+        "人" => match n {
+            1 => return Some("ひとり".to_owned()),
+            2 => return Some("ふたり".to_owned()),
+            _ => return Some(format!("{}にん", change_suffix(number, &[("よん", "よ")]))),
+        },
+        "つ" if (1..=10).contains(&n) => return Some(TSU[n as usize - 1].to_owned()),
+        "月" => {
+            return match n {
+                4 => Some("しがつ".to_owned()),
+                7 => Some("しちがつ".to_owned()),
+                9 => Some("くがつ".to_owned()),
+                1..=12 => Some(format!("{}がつ", number)),
+                _ => None,
+            };
+        }
+        "日" => return Some(day(n, number, false)),
+        "時" | "時間" | "年" | "円" => {
+            let mut table = vec![("よん", "よ")];
+            if unit.starts_with('時') {
+                table.extend([("なな", "しち"), ("きゅう", "く")]);
+            }
+            let kana = match unit {
+                "時" => "じ",
+                "時間" => "じかん",
+                "年" => "ねん",
+                _ => "えん",
+            };
+            return Some(format!("{}{}", change_suffix(number, &table), kana));
+        }
+        "杯" => ("はい", Some("ぱい"), Some("ばい")),
+        "本" => ("ほん", Some("ぽん"), Some("ぼん")),
+        "匹" => ("ひき", Some("ぴき"), Some("びき")),
+        "分" => ("ふん", Some("ぷん"), Some("ぷん")),
+        "個" => ("こ", None, None),
+        "回" => ("かい", None, None),
+        "階" => ("かい", None, Some("がい")),
+        "件" => ("けん", None, None),
+        "軒" => ("けん", None, Some("げん")),
+        "点" => ("てん", None, None),
+        "か月" | "ヶ月" | "ケ月" | "カ月" => ("かげつ", None, None),
+        "冊" => ("さつ", None, None),
+        "歳" | "才" if n == 20 => return Some("はたち".to_owned()),
+        "歳" | "才" => ("さい", None, None),
+        "週間" => ("しゅうかん", None, None),
+        "台" => ("だい", None, None),
+        "枚" => ("まい", None, None),
+        "度" => ("ど", None, None),
+        "番" => ("ばん", None, None),
+        "秒" => ("びょう", None, None),
+        _ => return None,
+    };
+    // さ行的量词不把ろく促音化，例如ろくさつ
+    let geminate = GEMINATE
+        .iter()
+        .filter(|(from, _)| *from != "ろく" || !kana.starts_with(['さ', 'し']))
+        .find_map(|(from, to)| number.strip_suffix(from).map(|stem| (stem, to)));
+    Some(match geminate {
+        Some((stem, to)) => format!("{}{}{}", stem, to, plosive.unwrap_or(kana)),
+        None if number.ends_with('ん') && (unit == "分" || !number.ends_with("よん")) => {
+            format!("{}{}", number, voiced.unwrap_or(kana))
+        }
+        None => format!("{}{}", number, kana),
+    })
+}
+
+/// 带量词的数字
+fn counter(caps: &Captures) -> Option<String> {
+    let n = caps[1].parse::<u64>().ok()?;
+    counted(n, &cardinal(&caps[1]), &caps[2])
+}
+
+type Rule = (&'static str, fn(&Captures) -> Option<String>);
+
+/// 日语规则，按顺序应用
+fn rules() -> Vec<Rule> {
+    vec![
+        // 百分比：50%、-3.5%
+        (r"(-?)(\d+(?:\.\d+)?)\s?[%％]", |caps| {
+            let sign = if caps[1].is_empty() {
+                ""
+            } else {
+                "マイナス"
+            };
+            Some(format!("{}{}パーセント", sign, cardinal(&caps[2])))
+        }),
+        // 负数
+        (r"(^|[^\d])-(\d+(?:\.\d+)?)", |caps| {
+            Some(format!("{}マイナス{}", &caps[1], cardinal(&caps[2])))
+        }),
+        // 日期：5月1日
+        (r"(\d+)月(\d+)日", |caps| {
+            let month = counted(caps[1].parse().ok()?, &cardinal(&caps[1]), "月")?;
+            let n = caps[2].parse().ok()?;
+            Some(format!("{}{}", month, day(n, &cardinal(&caps[2]), true)))
+        }),
+        // 量词：1人、3つ、4月、2杯、5日
+        (
+            r"(\d+)(か月|ヶ月|ケ月|カ月|時間|週間|[人つ月日時年円杯本匹分個回階件軒点冊歳才台枚度番秒])",
+            counter,
+        ),
+    ]
+}
+
+/// 把日语语境中的数字和符号展开为假名，英语规范化之后剩下的数字都读作日语
+pub(super) fn normalize(mut text: Normalized) -> Result<Normalized, G2PError> {
+    static RULES: LazyLock<Result<Vec<(Regex, Replacer)>, regex::Error>> =
+        LazyLock::new(|| compile(rules()));
+    for (regex, rule) in RULES.as_ref().map_err(Clone::clone)? {
+        text = text.replace_all(regex, |text, caps| {
+            // 量词本身是日语，因此只看数字前后的语境
+            let number = caps
+                .iter()
+                .skip(1)
+                .flatten()
+                .find(|i| i.as_str().starts_with(|c: char| c.is_ascii_digit()))?;
+            if is_english(text, number.start(), number.end()) {
+                return None;
+            }
+            rule(caps)
+        });
+    }
+
+    let number = regex!(r"\d[\d,]*(?:\.\d+)?")?;
+    Ok(text.replace_all(number, |_, caps| Some(cardinal(&caps[0]))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(text: &str) -> Result<String, G2PError> {
+        Ok(super::normalize(Normalized::new(text))?.text)
+    }
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!("じゅうに", cardinal("12"));
+        assert_eq!("さんびゃく", cardinal("300"));
+        assert_eq!("ろっぴゃく", cardinal("600"));
+        assert_eq!("はっぴゃくはち", cardinal("808"));
+        assert_eq!("さんぜんよんひゃくごじゅうろく", cardinal("3456"));
+        assert_eq!("はっせん", cardinal("8000"));
+        assert_eq!("いちまんにせん", cardinal("12000"));
+        assert_eq!("いちおくごまん", cardinal("100050000"));
+        assert_eq!("いっちょう", cardinal("1000000000000"));
+        assert_eq!("さんてんいちよん", cardinal("3.14"));
+        assert_eq!("ぜろぜろなな", cardinal("007"));
+        assert_eq!("ぜろ", cardinal("0"));
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), G2PError> {
+        assert_eq!("ひとりとふたり", normalize("1人と2人")?);
+        assert_eq!("さんにん", normalize("3人")?);
+        assert_eq!("みっつ", normalize("3つ")?);
+        assert_eq!("しがつとじゅうにがつ", normalize("4月と12月")?);
+        assert_eq!("ごじゅうパーセントの人", normalize("50%の人")?);
+        assert_eq!("気温はマイナスご度", normalize("気温は-5度")?);
+        assert_eq!("にせんにじゅうよねん", normalize("2024年")?);
+        assert_eq!("コーヒーをにはい", normalize("コーヒーを2杯")?);
+        assert_eq!("いっぱいとさんばい", normalize("1杯と3杯")?);
+        assert_eq!("さんぼんとよんほんとろっぽん", normalize("3本と4本と6本")?);
+        assert_eq!("さんぷんとじゅっぷん", normalize("3分と10分")?);
+        assert_eq!("じゅういっことさんがい", normalize("11個と3階")?);
+        assert_eq!("ろくさつとはっさいとはたち", normalize("6冊と8歳と20歳")?);
+        assert_eq!("よじとくじかん", normalize("4時と9時間")?);
+        assert_eq!("ひゃくえんとさんかげつ", normalize("100円と3ヶ月")?);
+        // 日期和天数
+        assert_eq!(
+            "いつかとじゅうよっかとじゅうしちにち",
+            normalize("5日と14日と17日")?
+        );
+        assert_eq!("ごがつついたち", normalize("5月1日")?);
+        assert_eq!("いちにちにかい", normalize("1日2回")?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+mod es;
+mod fr;
+mod it;
+mod pt;
+
+use super::{super::G2PError, Normalized};
+
+/// 一种语言读数字用到的词
+pub(super) struct Words {
+    /// 读出小于一万亿的非负整数
+    cardinal: fn(u64) -> String,
+    /// 小数点
+    point: &'static str,
+    /// 百分号
+    percent: &'static str,
+    /// 负号
+    minus: &'static str,
+    /// 单位符号，以及数量为1时的读法（包括数词）和复数形式
+    units: &'static [(&'static str, &'static str, &'static str)],
+    /// 时间`14h30`中的小时，数量为1时的读法和复数形式
+    hour: (&'static str, &'static str),
+    /// 货币符号，以及数量为1时的读法和复数形式
+    currencies: &'static [(char, &'static str, &'static str)],
+    /// 连接小时和分钟、金额的整数和小数部分的词，可以为空
+    and: &'static str,
+}
+
+/// 逐位读出数字
+fn digits(text: &str, words: &Words) -> String {
+    text.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| (words.cardinal)(d as u64))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 读出整数部分，以0开头或超过一万亿的数字逐位读出
+fn integer(text: &str, words: &Words) -> String {
+    match text.parse::<u64>() {
+        Ok(n) if (text == "0" || !text.starts_with('0')) && n < 1_000_000_000_000 => {
+            (words.cardinal)(n)
+        }
+        _ => digits(text, words),
+    }
+}
+
+/// 按欧洲大陆的习惯读出数字，`.`和空格是千位分隔符，`,`是小数点，例如`1.234,5`、`1 234,5`
+///
+/// 没有`,`且只有一个`.`时，`.`后面不是三位数字则把`.`当作小数点，例如`3.5`。
+fn number(text: &str, words: &Words) -> String {
+    let text = text.replace([' ', '\u{A0}', '\u{202F}'], "");
+    let text = text.as_str();
+    let (int, frac) = match text.rsplit_once(',') {
+        Some(parts) => parts,
+        None => match text.split_once('.') {
+            Some((int, frac)) if !frac.contains('.') && frac.len() != 3 => (int, frac),
+            _ => (text, ""),
+        },
+    };
+    let mut result = integer(&int.replace('.', ""), words);
+    if !frac.is_empty() {
+        result.push(' ');
+        result.push_str(words.point);
+        result.push(' ');
+        // 小数部分较短时按整数读出，例如tres coma catorce
+        if frac.len() > 3 {
+            result.push_str(&digits(frac, words));
+        } else {
+            result.push_str(&integer(frac, words));
+        }
+    }
+    result
+}
+
+/// 数量为1时读出单数形式，否则读出数字和复数形式
+fn count(text: &str, (singular, plural): (&str, &str), words: &Words) -> String {
+    if text == "1" {
+        singular.to_owned()
+    } else {
+        format!("{} {}", number(text, words), plural)
+    }
+}
+
+/// 把时间、数字、负号、百分号和单位展开为对应语言的单词
+///
+/// 与字母相连的数字单独读出，例如`MP3`、`A4`读作字母和数字，`5kg`、`14h30`按单位和时间读出。
+fn normalize(text: Normalized, words: &Words) -> Result<Normalized, G2PError> {
+    // 数字前面的字母后面加空格
+    let separate = |prefix: &str| {
+        if prefix.ends_with(char::is_alphabetic) {
+            format!("{} ", prefix)
+        } else {
+            prefix.to_owned()
+        }
+    };
+
+    let time_pattern = regex!(r"(^|\D)(\d{1,2})\s?h\s?(\d{2})?\b")?;
+    let text = text.replace_all(time_pattern, |_, caps| {
+        let hour = caps[2].parse::<u8>().ok().filter(|i| *i <= 24)?;
+        let minute = match caps.get(3) {
+            Some(m) => m.as_str().parse::<u8>().ok().filter(|i| *i < 60)?,
+            None => 0,
+        };
+        let mut result = separate(&caps[1]);
+        result.push_str(&count(&hour.to_string(), words.hour, words));
+        if minute > 0 {
+            result.push(' ');
+            if !words.and.is_empty() {
+                result.push_str(words.and);
+                result.push(' ');
+            }
+            result.push_str(&(words.cardinal)(minute as u64));
+        }
+        Some(result)
+    });
+
+    // 金额：100 €、2,50 €、$5
+    let currency_pattern = regex!(
+        r"(^|[^\w€$£])(?:([€$£])\s?(\d{1,3}(?:[.\s\x{A0}\x{202F}]\d{3})+|\d+)(?:[.,](\d{2}))?|(\d{1,3}(?:[.\s\x{A0}\x{202F}]\d{3})+|\d+)(?:,(\d{2}))?\s?([€$£]))",
+    )?;
+    let text = text.replace_all(currency_pattern, |_, caps| {
+        let symbol = caps.get(2).or(caps.get(7))?.as_str().chars().next()?;
+        let (_, singular, plural) = words.currencies.iter().find(|(i, ..)| *i == symbol)?;
+        let int = caps.get(3).or(caps.get(5))?.as_str();
+        let mut result = caps[1].to_owned();
+        result.push_str(&count(int, (singular, plural), words));
+        if let Some(cents) = caps.get(4).or(caps.get(6))
+            && cents.as_str() != "00"
+        {
+            result.push(' ');
+            if !words.and.is_empty() {
+                result.push_str(words.and);
+                result.push(' ');
+            }
+            result.push_str(&number(cents.as_str(), words));
+        }
+        Some(result)
+    });
+
+    let number_pattern = regex!(
+        r"(^|\D)(-?)(\d{1,3}(?:[ \x{A0}\x{202F}]\d{3})+\b(?:,\d+)?|\d+(?:[.,]\d+)*)(\s?%)?(\s?(?:km/h|°C|km|kg|cm|mm|ml|mL|min|g|m|l|L|s)\b)?",
+    )?;
+    Ok(text.replace_all(number_pattern, |text, caps| {
+        let mut result = if caps[2].is_empty() {
+            separate(&caps[1])
+        } else {
+            caps[1].to_owned()
+        };
+        // 字母后面的连字符不是负号，例如GPT-4
+        if !caps[2].is_empty() {
+            if caps[1].ends_with(char::is_alphanumeric) {
+                result.push('-');
+            } else {
+                result.push_str(words.minus);
+                result.push(' ');
+            }
+        }
+        let end = caps.get(0)?.end();
+        let next = text[end..].chars().next();
+        // 后面是撇号的单个字母不是单位，例如l'
+        let unit = caps
+            .get(5)
+            .filter(|_| !next.is_some_and(|c| "'’".contains(c)))
+            .and_then(|unit| {
+                let symbol = unit.as_str().trim_start();
+                words
+                    .units
+                    .iter()
+                    .find(|(i, ..)| i.eq_ignore_ascii_case(symbol))
+            });
+        match unit {
+            Some((_, singular, plural)) if caps.get(4).is_none() => {
+                result.push_str(&count(&caps[3], (singular, plural), words))
+            }
+            _ => result.push_str(&number(&caps[3], words)),
+        }
+        if caps.get(4).is_some() {
+            result.push(' ');
+            result.push_str(words.percent);
+        }
+        match caps.get(5) {
+            Some(symbol) if unit.is_none() || caps.get(4).is_some() => {
+                result.push_str(symbol.as_str())
+            }
+            // 数字后面的字母前面加空格
+            None if caps.get(4).is_none() && next.is_some_and(char::is_alphabetic) => {
+                result.push(' ')
+            }
+            _ => (),
+        }
+        Some(result)
+    }))
+}
+
+/// 把西班牙语文本中的数字展开为单词
+pub(super) fn spanish(text: Normalized) -> Result<Normalized, G2PError> {
+    normalize(text, &es::WORDS)
+}
+
+/// 把意大利语文本中的数字展开为单词
+pub(super) fn italian(text: Normalized) -> Result<Normalized, G2PError> {
+    normalize(text, &it::WORDS)
+}
+
+/// 把葡萄牙语文本中的数字展开为单词
+pub(super) fn portuguese(text: Normalized) -> Result<Normalized, G2PError> {
+    normalize(text, &pt::WORDS)
+}
+
+/// 把法语文本中的序数词和数字展开为单词，例如`1er`读作premier，`2e`读作deuxième
+pub(super) fn french(text: Normalized) -> Result<Normalized, G2PError> {
+    let ordinal_pattern = regex!(r"\b(\d+)(er|re|ère|e|ème|eme|es|èmes|ers|res)\b")?;
+    let text = text.replace_all(ordinal_pattern, |_, caps| {
+        let n = caps[1]
+            .parse::<u64>()
+            .ok()
+            .filter(|i| *i < 1_000_000_000_000)?;
+        let suffix = &caps[2];
+        // 1e、1es不是序数词，2er、2re也不是
+        if (n == 1) != matches!(suffix, "er" | "re" | "ère" | "ers" | "res") {
+            return None;
+        }
+        let plural = suffix.ends_with('s');
+        let mut result = fr::ordinal(n, suffix.starts_with('r') || suffix == "ère");
+        if plural {
+            result.push('s');
+        }
+        Some(result)
+    });
+    normalize(text, &fr::WORDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() -> Result<(), G2PError> {
+        let normalize = |text, convert: fn(Normalized) -> Result<Normalized, G2PError>| {
+            convert(Normalized::new(text)).map(|i| i.text)
+        };
+        assert_eq!(
+            "Tengo veinticinco años y mil quinientos euros",
+            normalize("Tengo 25 años y 1.500 euros", spanish)?
+        );
+        assert_eq!(
+            "menos tres coma cinco por ciento",
+            normalize("-3,5%", spanish)?
+        );
+        assert_eq!("tres coma catorce", normalize("3.14", spanish)?);
+        assert_eq!(
+            "Sono le tre-quattro, costa ventitré euro",
+            normalize("Sono le 3-4, costa 23 euro", italian)?
+        );
+        assert_eq!("cinquanta per cento", normalize("50 %", italian)?);
+        assert_eq!(
+            "Em dois mil e vinte e quatro, cerca de um milhão de pessoas",
+            normalize("Em 2024, cerca de 1.000.000 de pessoas", portuguese)?
+        );
+        assert_eq!("zero zero sete", normalize("007", portuguese)?);
+        assert_eq!(
+            "J'ai vingt et un ans et deux mille cinq cents euros",
+            normalize("J'ai 21 ans et 2 500 euros", french)?
+        );
+        assert_eq!(
+            "moins douze virgule cinq pour cent",
+            normalize("-12,5 %", french)?
+        );
+        assert_eq!("un million", normalize("1\u{202F}000\u{A0}000", french)?);
+
+        // 与字母相连的数字、单位和时间
+        assert_eq!("MP trois", normalize("MP3", french)?);
+        assert_eq!("A quatre et G sept", normalize("A4 et G7", french)?);
+        assert_eq!("quatorze heures trente", normalize("14h30", french)?);
+        assert_eq!("à une heure", normalize("à 1 h", french)?);
+        assert_eq!("cinq kilogrammes", normalize("5kg", french)?);
+        assert_eq!("un kilomètre", normalize("1 km", french)?);
+        assert_eq!("cinco kilogramos", normalize("5kg", spanish)?);
+        assert_eq!(
+            "as catorze horas e trinta",
+            normalize("as 14h30", portuguese)?
+        );
+        assert_eq!("dieci metri", normalize("10 m", italian)?);
+        assert_eq!("GPT-quatre", normalize("GPT-4", french)?);
+        assert_eq!("trois D", normalize("3D", french)?);
+        // 后面是撇号的字母不是单位
+        assert_eq!("deux l'été", normalize("2 l'été", french)?);
+
+        // 货币和序数词
+        assert_eq!("cent euros", normalize("100 €", french)?);
+        assert_eq!("un euro", normalize("1€", french)?);
+        assert_eq!("deux euros cinquante", normalize("2,50 €", french)?);
+        assert_eq!("cinco dólares", normalize("$5", spanish)?);
+        assert_eq!("dieci euro e venti", normalize("10,20 €", italian)?);
+        assert_eq!(
+            "le premier mai et la première fois",
+            normalize("le 1er mai et la 1re fois", french)?
+        );
+        assert_eq!(
+            "deuxième, vingt et unième, quatre-vingtième, centième",
+            normalize("2e, 21e, 80e, 100e", french)?
+        );
+        assert_eq!("les cinquièmes", normalize("les 5es", french)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use super::Words;
+
+/// 西班牙语读数字用到的词
+pub(super) const WORDS: Words = Words {
+    cardinal,
+    point: "coma",
+    percent: "por ciento",
+    minus: "menos",
+    units: &[
+        ("km/h", "un kilómetro por hora", "kilómetros por hora"),
+        ("km", "un kilómetro", "kilómetros"),
+        ("kg", "un kilogramo", "kilogramos"),
+        ("cm", "un centímetro", "centímetros"),
+        ("mm", "un milímetro", "milímetros"),
+        ("ml", "un mililitro", "mililitros"),
+        ("min", "un minuto", "minutos"),
+        ("°C", "un grado Celsius", "grados Celsius"),
+        ("g", "un gramo", "gramos"),
+        ("m", "un metro", "metros"),
+        ("l", "un litro", "litros"),
+        ("s", "un segundo", "segundos"),
+    ],
+    hour: ("una hora", "horas"),
+    currencies: &[
+        ('€', "un euro", "euros"),
+        ('$', "un dólar", "dólares"),
+        ('£', "una libra", "libras"),
+    ],
+    and: "y",
+};
+
+const UNITS: [&str; 30] = [
+    "cero",
+    "uno",
+    "dos",
+    "tres",
+    "cuatro",
+    "cinco",
+    "seis",
+    "siete",
+    "ocho",
+    "nueve",
+    "diez",
+    "once",
+    "doce",
+    "trece",
+    "catorce",
+    "quince",
+    "dieciséis",
+    "diecisiete",
+    "dieciocho",
+    "diecinueve",
+    "veinte",
+    "veintiuno",
+    "veintidós",
+    "veintitrés",
+    "veinticuatro",
+    "veinticinco",
+    "veintiséis",
+    "veintisiete",
+    "veintiocho",
+    "veintinueve",
+];
+const TENS: [&str; 10] = [
+    "",
+    "",
+    "",
+    "treinta",
+    "cuarenta",
+    "cincuenta",
+    "sesenta",
+    "setenta",
+    "ochenta",
+    "noventa",
+];
+const HUNDREDS: [&str; 10] = [
+    "",
+    "ciento",
+    "doscientos",
+    "trescientos",
+    "cuatrocientos",
+    "quinientos",
+    "seiscientos",
+    "setecientos",
+    "ochocientos",
+    "novecientos",
+];
+
+/// 读出1到999，整百的100读作cien
+fn below_thousand(n: u64) -> String {
+    if n == 100 {
+        return "cien".to_owned();
+    }
+    let mut words = Vec::new();
+    if n >= 100 {
+        words.push(HUNDREDS[(n / 100) as usize].to_owned());
+    }
+    match n % 100 {
+        0 => (),
+        r @ 1..=29 => words.push(UNITS[r as usize].to_owned()),
+        r if r.is_multiple_of(10) => words.push(TENS[(r / 10) as usize].to_owned()),
+        r => words.push(format!(
+            "{} y {}",
+            TENS[(r / 10) as usize],
+            UNITS[(r % 10) as usize]
+        )),
+    }
+    words.join(" ")
+}
+
+/// 在mil、millones前面把uno改为un，例如veintiún mil
+fn apocope(words: String) -> String {
+    match words.strip_suffix("uno") {
+        Some(stem) if stem.ends_with("veinti") => format!("{}ún", stem),
+        Some(stem) => format!("{}un", stem),
+        None => words,
+    }
+}
+
+/// 读出1到999999
+fn below_million(n: u64) -> String {
+    let mut words = Vec::new();
+    match n / 1000 {
+        0 => (),
+        1 => words.push("mil".to_owned()),
+        t => words.push(format!("{} mil", apocope(below_thousand(t)))),
+    }
+    if !n.is_multiple_of(1000) {
+        words.push(below_thousand(n % 1000));
+    }
+    words.join(" ")
+}
+
+/// 读出非负整数，十亿读作mil millones
+fn cardinal(n: u64) -> String {
+    if n == 0 {
+        return UNITS[0].to_owned();
+    }
+    let mut words = Vec::new();
+    match n / 1_000_000 {
+        0 => (),
+        1 => words.push("un millón".to_owned()),
+        m => words.push(format!("{} millones", apocope(below_million(m)))),
+    }
+    if !n.is_multiple_of(1_000_000) {
+        words.push(below_million(n % 1_000_000));
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!("cero", cardinal(0));
+        assert_eq!("dieciséis", cardinal(16));
+        assert_eq!("cuarenta y dos", cardinal(42));
+        assert_eq!("cien", cardinal(100));
+        assert_eq!("ciento uno", cardinal(101));
+        assert_eq!("quinientos cincuenta", cardinal(550));
+        assert_eq!("mil novecientos ochenta y cuatro", cardinal(1984));
+        assert_eq!("veintiún mil", cardinal(21_000));
+        assert_eq!("treinta y un mil uno", cardinal(31_001));
+        assert_eq!("dos millones trescientos mil", cardinal(2_300_000));
+        assert_eq!("mil millones", cardinal(1_000_000_000));
+    }
+}
@@ -0,0 +1,177 @@
+use super::Words;
+
+/// 法语读数字用到的词
+pub(super) const WORDS: Words = Words {
+    cardinal,
+    point: "virgule",
+    percent: "pour cent",
+    minus: "moins",
+    units: &[
+        ("km/h", "un kilomètre heure", "kilomètres heure"),
+        ("km", "un kilomètre", "kilomètres"),
+        ("kg", "un kilogramme", "kilogrammes"),
+        ("cm", "un centimètre", "centimètres"),
+        ("mm", "un millimètre", "millimètres"),
+        ("ml", "un millilitre", "millilitres"),
+        ("min", "une minute", "minutes"),
+        ("°C", "un degré Celsius", "degrés Celsius"),
+        ("g", "un gramme", "grammes"),
+        ("m", "un mètre", "mètres"),
+        ("l", "un litre", "litres"),
+        ("s", "une seconde", "secondes"),
+    ],
+    hour: ("une heure", "heures"),
+    currencies: &[
+        ('€', "un euro", "euros"),
+        ('$', "un dollar", "dollars"),
+        ('£', "une livre", "livres"),
+    ],
+    and: "",
+};
+
+const UNITS: [&str; 20] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf", "dix", "onze",
+    "douze", "treize", "quatorze", "quinze", "seize", "dix-sept", "dix-huit", "dix-neuf",
+];
+const TENS: [&str; 7] = [
+    "",
+    "",
+    "vingt",
+    "trente",
+    "quarante",
+    "cinquante",
+    "soixante",
+];
+
+/// 读出1到99，七十、九十在六十、八十的基础上加十到十九，例如soixante et onze、quatre-vingt-dix
+///
+/// `plural`为真时整八十读作quatre-vingts，后面还有数词时不加s。
+fn below_hundred(n: u64, plural: bool) -> String {
+    let (tens, rest) = match n {
+        0..=19 => return UNITS[n as usize].to_owned(),
+        20..=69 => (TENS[(n / 10) as usize], n % 10),
+        70..=79 => (TENS[6], n - 60),
+        _ => ("quatre-vingt", n - 80),
+    };
+    match rest {
+        0 if n == 80 && plural => "quatre-vingts".to_owned(),
+        0 => tens.to_owned(),
+        // 八十一、九十一不加et
+        1 | 11 if n < 80 => format!("{} et {}", tens, UNITS[rest as usize]),
+        _ => format!("{}-{}", tens, UNITS[rest as usize]),
+    }
+}
+
+/// 读出1到999，`plural`为真时整百读作deux cents等
+fn below_thousand(n: u64, plural: bool) -> String {
+    let mut words = Vec::new();
+    match n / 100 {
+        0 => (),
+        1 => words.push("cent".to_owned()),
+        h if n.is_multiple_of(100) && plural => words.push(format!("{} cents", UNITS[h as usize])),
+        h => words.push(format!("{} cent", UNITS[h as usize])),
+    }
+    if !n.is_multiple_of(100) {
+        words.push(below_hundred(n % 100, plural));
+    }
+    words.join(" ")
+}
+
+/// 读出1到999999，mille不变，前面的数词也不加s
+fn below_million(n: u64) -> String {
+    let mut words = Vec::new();
+    match n / 1000 {
+        0 => (),
+        1 => words.push("mille".to_owned()),
+        t => words.push(format!("{} mille", below_thousand(t, false))),
+    }
+    if !n.is_multiple_of(1000) {
+        words.push(below_thousand(n % 1000, true));
+    }
+    words.join(" ")
+}
+
+/// 读出非负整数，million、milliard是名词，复数要加s
+fn cardinal(n: u64) -> String {
+    if n == 0 {
+        return UNITS[0].to_owned();
+    }
+    let mut words = Vec::new();
+    for (scale, singular) in [(1_000_000_000, "milliard"), (1_000_000, "million")] {
+        match n / scale % 1000 {
+            0 => (),
+            1 => words.push(format!("un {}", singular)),
+            m => words.push(format!("{} {}s", below_thousand(m, true), singular)),
+        }
+    }
+    if !n.is_multiple_of(1_000_000) {
+        words.push(below_million(n % 1_000_000));
+    }
+    words.join(" ")
+}
+
+/// 读出序数词，premier、première以外的序数词在基数词后面加-ième
+///
+/// # 参数
+///
+/// * `n` - 大于0的整数。
+/// * `feminine` - 1是否读作阴性的première。
+pub(super) fn ordinal(n: u64, feminine: bool) -> String {
+    match n {
+        1 if feminine => return "première".to_owned(),
+        1 => return "premier".to_owned(),
+        _ => (),
+    }
+    let cardinal = cardinal(n);
+    let stem = cardinal.strip_suffix('s').unwrap_or(&cardinal);
+    // 去掉复数的s和词尾的e，cinq、neuf改为cinqu、neuv
+    let stem = match stem.strip_suffix("cinq") {
+        Some(stem) => format!("{}cinqu", stem),
+        None => match stem.strip_suffix("neuf") {
+            Some(stem) => format!("{}neuv", stem),
+            None => stem.strip_suffix('e').unwrap_or(stem).to_owned(),
+        },
+    };
+    format!("{}ième", stem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!("zéro", cardinal(0));
+        assert_eq!("dix-sept", cardinal(17));
+        assert_eq!("vingt et un", cardinal(21));
+        assert_eq!("soixante-dix", cardinal(70));
+        assert_eq!("soixante et onze", cardinal(71));
+        assert_eq!("quatre-vingts", cardinal(80));
+        assert_eq!("quatre-vingt-un", cardinal(81));
+        assert_eq!("quatre-vingt-dix-neuf", cardinal(99));
+        assert_eq!("cent", cardinal(100));
+        assert_eq!("deux cents", cardinal(200));
+        assert_eq!("deux cent trois", cardinal(203));
+        assert_eq!("mille neuf cent quatre-vingt-quatre", cardinal(1984));
+        assert_eq!("deux cent mille", cardinal(200_000));
+        assert_eq!("quatre-vingt mille", cardinal(80_000));
+        assert_eq!("un million deux cent mille", cardinal(1_200_000));
+        assert_eq!("trois milliards", cardinal(3_000_000_000));
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!("premier", ordinal(1, false));
+        assert_eq!("première", ordinal(1, true));
+        assert_eq!("deuxième", ordinal(2, false));
+        assert_eq!("quatrième", ordinal(4, false));
+        assert_eq!("cinquième", ordinal(5, false));
+        assert_eq!("neuvième", ordinal(9, false));
+        assert_eq!("onzième", ordinal(11, false));
+        assert_eq!("vingt et unième", ordinal(21, false));
+        assert_eq!("quatre-vingtième", ordinal(80, false));
+        assert_eq!("deux centième", ordinal(200, false));
+        assert_eq!("millième", ordinal(1000, false));
+        assert_eq!("deux millionième", ordinal(2_000_000, false));
+    }
+}
@@ -0,0 +1,154 @@
+use super::Words;
+
+/// 意大利语读数字用到的词
+pub(super) const WORDS: Words = Words {
+    cardinal,
+    point: "virgola",
+    percent: "per cento",
+    minus: "meno",
+    units: &[
+        ("km/h", "un chilometro orario", "chilometri orari"),
+        ("km", "un chilometro", "chilometri"),
+        ("kg", "un chilogrammo", "chilogrammi"),
+        ("cm", "un centimetro", "centimetri"),
+        ("mm", "un millimetro", "millimetri"),
+        ("ml", "un millilitro", "millilitri"),
+        ("min", "un minuto", "minuti"),
+        ("°C", "un grado Celsius", "gradi Celsius"),
+        ("g", "un grammo", "grammi"),
+        ("m", "un metro", "metri"),
+        ("l", "un litro", "litri"),
+        ("s", "un secondo", "secondi"),
+    ],
+    hour: ("un'ora", "ore"),
+    currencies: &[
+        ('€', "un euro", "euro"),
+        ('$', "un dollaro", "dollari"),
+        ('£', "una sterlina", "sterline"),
+    ],
+    and: "e",
+};
+
+const UNITS: [&str; 20] = [
+    "zero",
+    "uno",
+    "due",
+    "tre",
+    "quattro",
+    "cinque",
+    "sei",
+    "sette",
+    "otto",
+    "nove",
+    "dieci",
+    "undici",
+    "dodici",
+    "tredici",
+    "quattordici",
+    "quindici",
+    "sedici",
+    "diciassette",
+    "diciotto",
+    "diciannove",
+];
+const TENS: [&str; 10] = [
+    "",
+    "",
+    "venti",
+    "trenta",
+    "quaranta",
+    "cinquanta",
+    "sessanta",
+    "settanta",
+    "ottanta",
+    "novanta",
+];
+
+/// 读出0到99，uno、otto前面的十位省略词尾元音，例如ventuno、trentotto
+fn below_hundred(n: u64) -> String {
+    if n < 20 {
+        return UNITS[n as usize].to_owned();
+    }
+    let tens = TENS[(n / 10) as usize];
+    match n % 10 {
+        0 => tens.to_owned(),
+        1 | 8 => format!("{}{}", &tens[..tens.len() - 1], UNITS[(n % 10) as usize]),
+        3 => format!("{}tré", tens),
+        u => format!("{}{}", tens, UNITS[u as usize]),
+    }
+}
+
+/// 读出1到999，例如centottanta
+fn below_thousand(n: u64) -> String {
+    let mut result = match n / 100 {
+        0 => String::new(),
+        1 => "cento".to_owned(),
+        h => format!("{}cento", UNITS[h as usize]),
+    };
+    if !n.is_multiple_of(100) {
+        let rest = below_hundred(n % 100);
+        if rest.starts_with('o') {
+            result.pop();
+        }
+        result.push_str(&rest);
+    }
+    result
+}
+
+/// 后面还有其他部分时，-tré不再重读，例如ventitremila
+fn unaccent(words: String) -> String {
+    match words.strip_suffix("tré") {
+        Some(stem) => format!("{}tre", stem),
+        None => words,
+    }
+}
+
+/// 读出非负整数，一百万以下写作一个词
+fn cardinal(n: u64) -> String {
+    if n == 0 {
+        return UNITS[0].to_owned();
+    }
+    let mut words = Vec::new();
+    for (scale, one, many) in [
+        (1_000_000_000, "un miliardo", "miliardi"),
+        (1_000_000, "un milione", "milioni"),
+    ] {
+        match n / scale % 1000 {
+            0 => (),
+            1 => words.push(one.to_owned()),
+            g => words.push(format!("{} {}", unaccent(below_thousand(g)), many)),
+        }
+    }
+    let mut rest = match n / 1000 % 1000 {
+        0 => String::new(),
+        1 => "mille".to_owned(),
+        t => format!("{}mila", unaccent(below_thousand(t))),
+    };
+    if !n.is_multiple_of(1000) {
+        rest.push_str(&below_thousand(n % 1000));
+    }
+    if !rest.is_empty() {
+        words.push(rest);
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!("zero", cardinal(0));
+        assert_eq!("ventuno", cardinal(21));
+        assert_eq!("trentotto", cardinal(38));
+        assert_eq!("ventitré", cardinal(23));
+        assert_eq!("cento", cardinal(100));
+        assert_eq!("centottanta", cardinal(180));
+        assert_eq!("duecentoquaranta", cardinal(240));
+        assert_eq!("millenovecentottantaquattro", cardinal(1984));
+        assert_eq!("ventitremila", cardinal(23_000));
+        assert_eq!("un milione duecentomila", cardinal(1_200_000));
+        assert_eq!("tre miliardi", cardinal(3_000_000_000));
+    }
+}
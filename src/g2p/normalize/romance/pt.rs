@@ -0,0 +1,146 @@
+use super::Words;
+
+/// 葡萄牙语读数字用到的词，按巴西的习惯
+pub(super) const WORDS: Words = Words {
+    cardinal,
+    point: "vírgula",
+    percent: "por cento",
+    minus: "menos",
+    units: &[
+        ("km/h", "um quilômetro por hora", "quilômetros por hora"),
+        ("km", "um quilômetro", "quilômetros"),
+        ("kg", "um quilograma", "quilogramas"),
+        ("cm", "um centímetro", "centímetros"),
+        ("mm", "um milímetro", "milímetros"),
+        ("ml", "um mililitro", "mililitros"),
+        ("min", "um minuto", "minutos"),
+        ("°C", "um grau Celsius", "graus Celsius"),
+        ("g", "um grama", "gramas"),
+        ("m", "um metro", "metros"),
+        ("l", "um litro", "litros"),
+        ("s", "um segundo", "segundos"),
+    ],
+    hour: ("uma hora", "horas"),
+    currencies: &[
+        ('€', "um euro", "euros"),
+        ('$', "um dólar", "dólares"),
+        ('£', "uma libra", "libras"),
+    ],
+    and: "e",
+};
+
+const UNITS: [&str; 20] = [
+    "zero",
+    "um",
+    "dois",
+    "três",
+    "quatro",
+    "cinco",
+    "seis",
+    "sete",
+    "oito",
+    "nove",
+    "dez",
+    "onze",
+    "doze",
+    "treze",
+    "catorze",
+    "quinze",
+    "dezesseis",
+    "dezessete",
+    "dezoito",
+    "dezenove",
+];
+const TENS: [&str; 10] = [
+    "",
+    "",
+    "vinte",
+    "trinta",
+    "quarenta",
+    "cinquenta",
+    "sessenta",
+    "setenta",
+    "oitenta",
+    "noventa",
+];
+const HUNDREDS: [&str; 10] = [
+    "",
+    "cento",
+    "duzentos",
+    "trezentos",
+    "quatrocentos",
+    "quinhentos",
+    "seiscentos",
+    "setecentos",
+    "oitocentos",
+    "novecentos",
+];
+
+/// 读出1到999，各部分之间加e，整百的100读作cem
+fn below_thousand(n: u64) -> String {
+    if n == 100 {
+        return "cem".to_owned();
+    }
+    let mut words = Vec::new();
+    if n >= 100 {
+        words.push(HUNDREDS[(n / 100) as usize]);
+    }
+    match n % 100 {
+        0 => (),
+        r @ 1..=19 => words.push(UNITS[r as usize]),
+        r => {
+            words.push(TENS[(r / 10) as usize]);
+            if r % 10 > 0 {
+                words.push(UNITS[(r % 10) as usize]);
+            }
+        }
+    }
+    words.join(" e ")
+}
+
+/// 读出非负整数，最后一部分小于一百或是整百时前面加e，例如dois mil e vinte
+fn cardinal(n: u64) -> String {
+    if n == 0 {
+        return UNITS[0].to_owned();
+    }
+    let mut words = Vec::new();
+    for (scale, one, many) in [
+        (1_000_000_000, "um bilhão", "bilhões"),
+        (1_000_000, "um milhão", "milhões"),
+        (1000, "mil", "mil"),
+    ] {
+        match n / scale % 1000 {
+            0 => (),
+            1 => words.push(one.to_owned()),
+            g => words.push(format!("{} {}", below_thousand(g), many)),
+        }
+    }
+    let rest = n % 1000;
+    if rest > 0 {
+        if !words.is_empty() && (rest < 100 || rest.is_multiple_of(100)) {
+            words.push("e".to_owned());
+        }
+        words.push(below_thousand(rest));
+    }
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!("zero", cardinal(0));
+        assert_eq!("dezesseis", cardinal(16));
+        assert_eq!("vinte e um", cardinal(21));
+        assert_eq!("cem", cardinal(100));
+        assert_eq!("cento e um", cardinal(101));
+        assert_eq!("quinhentos e cinquenta", cardinal(550));
+        assert_eq!("mil e quinhentos", cardinal(1500));
+        assert_eq!("mil novecentos e oitenta e quatro", cardinal(1984));
+        assert_eq!("dois mil e vinte e quatro", cardinal(2024));
+        assert_eq!("dois milhões e trezentos", cardinal(2_000_300));
+        assert_eq!("um bilhão", cardinal(1_000_000_000));
+    }
+}
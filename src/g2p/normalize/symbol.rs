@@ -0,0 +1,153 @@
+use {
+    super::{super::G2PError, EmojiReading, Normalized, is_english},
+    std::{collections::HashMap, sync::LazyLock},
+};
+
+/// 零宽连接符，用于组合多个表情符号
+const ZWJ: char = '\u{200D}';
+
+/// 表情符号和常用符号的英语、汉语名称
+static NAMES: LazyLock<HashMap<&'static str, (&'static str, &'static str)>> = LazyLock::new(|| {
+    include_str!("../../../dict/emoji.tsv")
+        .lines()
+        .filter(|line| !line.starts_with("# "))
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some((fields.next()?, (fields.next()?, fields.next()?)))
+        })
+        .collect()
+});
+
+/// 是否为肤色修饰符
+fn is_skin_tone(c: char) -> bool {
+    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+/// 查找一个表情符号或符号的名称
+///
+/// 变体选择符和肤色修饰符不影响读法；词表中没有的组合表情按各个部分的名称读出，例如👨‍🦰读作man red hair。
+fn name(symbol: &str, chinese: bool) -> Option<String> {
+    let key = symbol
+        .chars()
+        .filter(|c| *c != '\u{FE0F}' && !is_skin_tone(*c))
+        .collect::<String>();
+    let lookup = |key: &str| {
+        NAMES
+            .get(key)
+            .map(|(en, zh)| if chinese { *zh } else { *en })
+    };
+    if let Some(name) = lookup(&key) {
+        return Some(name.to_owned());
+    }
+    // 所有部分都有名称时才读出，避免只读出一部分
+    let names = key.split(ZWJ).map(lookup).collect::<Option<Vec<_>>>()?;
+    Some(names.join(if chinese { "" } else { " " }))
+}
+
+/// 把键帽表情（1️⃣、#️⃣）换成键帽上的字符，需要在展开数字之前调用，使键帽上的数字按语言读出
+///
+/// `reading`为`Pause`时与其他表情符号一样换成停顿，为`Skip`时直接去掉，避免数字被展开后只剩下键帽符号。
+pub(super) fn keycaps(
+    text: Normalized,
+    reading: EmojiReading,
+    chinese: bool,
+) -> Result<Normalized, G2PError> {
+    let keycap = regex!(r"([0-9#*])\x{FE0F}?\x{20E3}")?;
+    Ok(text.replace_all(keycap, |text, caps| {
+        let m = caps.get(0)?;
+        Some(match reading {
+            EmojiReading::Skip => String::new(),
+            EmojiReading::Pause if chinese && !is_english(text, m.start(), m.end()) => {
+                "，".to_owned()
+            }
+            EmojiReading::Pause => ",".to_owned(),
+            _ => caps[1].to_owned(),
+        })
+    }))
+}
+
+/// 把表情符号和常用符号（&、@、#、→、©、°C、±等）换成名称
+///
+/// 汉语语境中读汉语名称，其他语境读英语名称。表情符号按`reading`读出、换成停顿或保留；保留和无法读出的表情符号由`Fallback`处理。
+pub(super) fn normalize(
+    text: Normalized,
+    reading: EmojiReading,
+    chinese: bool,
+) -> Result<Normalized, G2PError> {
+    let symbols = regex!(
+        r"(\p{Regional_Indicator}{2}|\p{Extended_Pictographic}[\x{FE0F}\p{Emoji_Modifier}]*(?:\x{200D}\p{Extended_Pictographic}[\x{FE0F}\p{Emoji_Modifier}]*)*)|°[CF]|[&@#℃℉°±×÷≈≠≤≥→←↑↓§]",
+    )?;
+    Ok(text.replace_all(symbols, |text, caps| {
+        let m = caps.get(0)?;
+        let chinese = chinese && !is_english(text, m.start(), m.end());
+        let emoji = caps.get(1).is_some() && !matches!(m.as_str(), "©" | "®" | "™");
+        let replaced = match reading {
+            EmojiReading::Skip if emoji => return None,
+            EmojiReading::Pause if emoji => {
+                return Some(if chinese { "，" } else { "," }.to_owned());
+            }
+            _ => name(m.as_str(), chinese)?,
+        };
+        if chinese {
+            return Some(replaced);
+        }
+        // 英语名称与前后的单词用空格隔开
+        let space = |c: Option<char>| match c {
+            Some(c) if c.is_alphanumeric() => " ",
+            _ => "",
+        };
+        Some(format!(
+            "{}{}{}",
+            space(text[..m.start()].chars().next_back()),
+            replaced,
+            space(text[m.end()..].chars().next())
+        ))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(text: &str, reading: EmojiReading) -> Result<String, G2PError> {
+        Ok(super::normalize(Normalized::new(text), reading, true)?.text)
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(Some("thumbs up".to_owned()), name("👍🏽", false));
+        assert_eq!(Some("红心".to_owned()), name("❤️", true));
+        assert_eq!(Some("man technologist".to_owned()), name("👨🏻‍💻", false));
+        assert_eq!(Some("woman rocket".to_owned()), name("👩‍🚀", false));
+        assert_eq!(Some("flag Japan".to_owned()), name("🇯🇵", false));
+        assert_eq!(None, name("🦩", false));
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), G2PError> {
+        assert_eq!("好的拇指向上", normalize("好的👍", EmojiReading::Speak)?);
+        assert_eq!(
+            "Nice thumbs up!",
+            normalize("Nice 👍!", EmojiReading::Speak)?
+        );
+        assert_eq!("R and D", normalize("R&D", EmojiReading::Skip)?);
+        assert_eq!(
+            "气温二十摄氏度",
+            normalize("气温二十°C", EmojiReading::Speak)?
+        );
+        assert_eq!(
+            "好的，明天见",
+            normalize("好的😀明天见", EmojiReading::Pause)?
+        );
+        assert_eq!("好的😀", normalize("好的😀", EmojiReading::Skip)?);
+
+        let keycaps = |text, reading| -> Result<String, G2PError> {
+            Ok(keycaps(Normalized::new(text), reading, true)?.text)
+        };
+        assert_eq!("1 and #", keycaps("1️⃣ and #️⃣", EmojiReading::Speak)?);
+        assert_eq!("第2名", keycaps("第2\u{20E3}名", EmojiReading::Speak)?);
+        assert_eq!("好的，", keycaps("好的1️⃣", EmojiReading::Pause)?);
+        assert_eq!("step ", keycaps("step 1️⃣", EmojiReading::Skip)?);
+        Ok(())
+    }
+}
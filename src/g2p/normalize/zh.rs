@@ -0,0 +1,314 @@
+use {
+    super::{super::G2PError, ChineseVariant, Normalized, Replacer, compile, is_english},
+    chinese_number::{
+        ChineseCase, ChineseCountMethod, ChineseVariant as NumberVariant, NumberToChinese,
+    },
+    regex::{Captures, Regex},
+    std::sync::LazyLock,
+};
+
+const DIGITS: [char; 10] = ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+/// 前面的“2”读作“两”的量词
+const MEASURES: &str = "个位本只条张件次天年岁人斤种份名台辆部把块双对杯碗瓶点场声句棵匹头架间";
+/// 单位的缩写和读法
+const UNITS: [(&str, &str); 20] = [
+    ("km/h", "千米每小时"),
+    ("km", "千米"),
+    ("kg", "千克"),
+    ("cm", "厘米"),
+    ("mm", "毫米"),
+    ("mg", "毫克"),
+    ("ml", "毫升"),
+    ("mL", "毫升"),
+    ("m²", "平方米"),
+    ("m³", "立方米"),
+    ("m", "米"),
+    ("g", "克"),
+    ("L", "升"),
+    ("l", "升"),
+    ("t", "吨"),
+    ("°C", "摄氏度"),
+    ("℃", "摄氏度"),
+    ("°F", "华氏度"),
+    ("℉", "华氏度"),
+    ("°", "度"),
+];
+/// 简体字和对应的繁体字，只包括规范化时会输出的字
+const TRADITIONAL: [(char, char); 14] = [
+    ('万', '萬'),
+    ('亿', '億'),
+    ('两', '兩'),
+    ('点', '點'),
+    ('负', '負'),
+    ('吨', '噸'),
+    ('摄', '攝'),
+    ('华', '華'),
+    ('欧', '歐'),
+    ('镑', '鎊'),
+    ('币', '幣'),
+    ('时', '時'),
+    ('钟', '鐘'),
+    ('号', '號'),
+];
+
+/// 逐位读出数字，`alt_one`为真时“1”读作“幺”，用于电话号码
+fn digits(text: &str, alt_one: bool) -> String {
+    text.chars()
+        .map(|c| match c.to_digit(10) {
+            Some(1) if alt_one => '幺',
+            Some(d) => DIGITS[d as usize],
+            None => c,
+        })
+        .collect()
+}
+
+/// 读出整数或小数，例如`12.05`读作“十二点零五”
+fn cardinal(text: &str) -> String {
+    let (int, frac) = text.split_once('.').unwrap_or((text, ""));
+    let int = int.replace(',', "");
+    let mut result = match int.parse::<u128>() {
+        Ok(n) if int.len() == 1 || !int.starts_with('0') => n
+            .to_chinese(
+                NumberVariant::Simple,
+                ChineseCase::Lower,
+                ChineseCountMethod::TenThousand,
+            )
+            .unwrap_or_else(|_| digits(&int, false)),
+        _ => digits(&int, false),
+    };
+    // “二千”“二万”“二亿”习惯读作“两”
+    if let Some(rest) = result.strip_prefix('二')
+        && rest.starts_with(['千', '万', '亿'])
+    {
+        result = format!("两{}", rest);
+    }
+    if !frac.is_empty() {
+        result.push('点');
+        result.push_str(&digits(frac, false));
+    }
+    result
+}
+
+fn date(caps: &Captures) -> Option<String> {
+    let month = caps[2].parse::<u32>().ok()?;
+    let day = caps[3].parse::<u32>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(format!(
+        "{}年{}月{}日",
+        digits(&caps[1], false),
+        cardinal(&month.to_string()),
+        cardinal(&day.to_string())
+    ))
+}
+
+fn time(caps: &Captures) -> Option<String> {
+    let hour = caps[1].parse::<u32>().ok()?;
+    let minute = caps[2].parse::<u32>().ok()?;
+    if hour > 24 || minute > 59 {
+        return None;
+    }
+    let mut result = format!("{}点", cardinal(&hour.to_string()));
+    if hour == 2 {
+        result = "两点".to_owned();
+    }
+    let second = caps.get(3).and_then(|i| i.as_str().parse::<u32>().ok());
+    if minute > 0 || second.is_some() {
+        if minute < 10 {
+            result.push('零');
+        }
+        result.push_str(&cardinal(&minute.to_string()));
+        result.push('分');
+    }
+    if let Some(second) = second {
+        if second < 10 {
+            result.push('零');
+        }
+        result.push_str(&cardinal(&second.to_string()));
+        result.push('秒');
+    }
+    Some(result)
+}
+
+fn currency(caps: &Captures) -> Option<String> {
+    let unit = match &caps[1] {
+        "¥" | "￥" => "元",
+        "$" => "美元",
+        "€" => "欧元",
+        "£" => "英镑",
+        _ => return None,
+    };
+    Some(format!("{}{}", cardinal(&caps[2]), unit))
+}
+
+fn unit(caps: &Captures) -> Option<String> {
+    let unit = caps.get(2).or(caps.get(3))?.as_str();
+    let (_, name) = UNITS.iter().find(|(u, _)| *u == unit)?;
+    Some(format!("{}{}", cardinal(&caps[1]), name))
+}
+
+type Rule = (&'static str, fn(&Captures) -> Option<String>);
+
+/// 汉语规则，按顺序应用
+fn rules() -> Vec<Rule> {
+    vec![
+        // 身份证号
+        (r"\d{17}[\dXx]", |caps| Some(digits(&caps[0], true))),
+        // 手机号和带区号的固定电话，可以带国家代码
+        (r"(?:\+(86)[-\s]?)?(1[3-9]\d{9}|0\d{2,3}-\d{7,8})", |caps| {
+            let number = digits(&caps[2], true).replace('-', "，");
+            Some(match caps.get(1) {
+                Some(code) => format!("加{}，{}", digits(code.as_str(), true), number),
+                None => number,
+            })
+        }),
+        // 日期：2024-01-05、2024/1/5、2024.1.5
+        (r"(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})", date),
+        // 年份逐位读出：2024年
+        (r"(1[1-9]\d{2}|20\d{2})年", |caps| {
+            Some(format!("{}年", digits(&caps[1], false)))
+        }),
+        // 时间：3:30、15:04:05
+        (r"(\d{1,2}):(\d{2})(?::(\d{2}))?", time),
+        // 货币：¥12.5、$5
+        (r"([¥￥$€£])\s?(\d[\d,]*(?:\.\d+)?)", currency),
+        // 百分比：50%、-3.5%
+        (r"(-?)(\d+(?:\.\d+)?)\s?[%％]", |caps| {
+            let sign = if caps[1].is_empty() { "" } else { "负" };
+            Some(format!("{}百分之{}", sign, cardinal(&caps[2])))
+        }),
+        // 分数：1/3
+        (r"(\d+)/(\d+)", |caps| {
+            Some(format!("{}分之{}", cardinal(&caps[2]), cardinal(&caps[1])))
+        }),
+        // 范围：3-5、3~5
+        (
+            r"(\d+(?:\.\d+)?)\s?[-~～–—]\s?(\d+(?:\.\d+)?)",
+            |caps| Some(format!("{}到{}", cardinal(&caps[1]), cardinal(&caps[2]))),
+        ),
+        // 负号：-5、-3℃，数字留给后面的规则读出；“零下”已经表示负数，不再读“负”
+        (r"(^|零下\s?|[^\d])-(\d)", |caps| {
+            let sign = if caps[1].starts_with("零下") {
+                ""
+            } else {
+                "负"
+            };
+            Some(format!("{}{}{}", &caps[1], sign, &caps[2]))
+        }),
+        // 单位：5kg、30°C
+        (
+            r"(\d+(?:\.\d+)?)\s?(?:(m²|m³|°C|℃|°F|℉|°)|(km/h|km|kg|cm|mm|mg|ml|mL|m|g|L|l|t)(?-u:\b))",
+            unit,
+        ),
+    ]
+}
+
+fn to_variant(text: String, variant: ChineseVariant) -> String {
+    match variant {
+        ChineseVariant::Simplified => text,
+        ChineseVariant::Traditional => text
+            .chars()
+            .map(|c| {
+                TRADITIONAL
+                    .iter()
+                    .find(|(s, _)| *s == c)
+                    .map_or(c, |(_, t)| *t)
+            })
+            .collect(),
+    }
+}
+
+/// 把汉语语境中的数字和符号展开为汉字，英语规范化之后剩下的数字都读作汉语
+pub(super) fn normalize(
+    mut text: Normalized,
+    variant: ChineseVariant,
+) -> Result<Normalized, G2PError> {
+    static RULES: LazyLock<Result<Vec<(Regex, Replacer)>, regex::Error>> =
+        LazyLock::new(|| compile(rules()));
+    for (regex, rule) in RULES.as_ref().map_err(Clone::clone)? {
+        text = text.replace_all(regex, |text, caps| {
+            let m = caps.get(0)?;
+            if is_english(text, m.start(), m.end()) {
+                return None;
+            }
+            rule(caps).map(|i| to_variant(i, variant))
+        });
+    }
+
+    let number = regex!(r"\d[\d,]*(?:\.\d+)?")?;
+    Ok(text.replace_all(number, |text, caps| {
+        let m = caps.get(0)?;
+        let next = text[m.end()..].chars().next();
+        // 序数“第2名”读作“第二名”
+        let ordinal = text[..m.start()].ends_with('第');
+        let result = if &caps[0] == "2" && !ordinal && next.is_some_and(|c| MEASURES.contains(c)) {
+            "两".to_owned()
+        } else {
+            cardinal(&caps[0])
+        };
+        Some(to_variant(result, variant))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(text: &str) -> Result<String, G2PError> {
+        Ok(super::normalize(Normalized::new(text), ChineseVariant::Simplified)?.text)
+    }
+
+    #[test]
+    fn test_cardinal() {
+        assert_eq!("十二", cardinal("12"));
+        assert_eq!("一百零五", cardinal("105"));
+        assert_eq!("十二万三千四百五十六", cardinal("123456"));
+        assert_eq!("两千", cardinal("2000"));
+        assert_eq!("三点一四", cardinal("3.14"));
+        assert_eq!("零零七", cardinal("007"));
+    }
+
+    #[test]
+    fn test_normalize() -> Result<(), G2PError> {
+        assert_eq!("二零二四年是龙年", normalize("2024年是龙年")?);
+        assert_eq!("今天是二零二四年一月五日", normalize("今天是2024-01-05")?);
+        assert_eq!("现在是三点三十分", normalize("现在是3:30")?);
+        assert_eq!("在十五点零四分零五秒", normalize("在15:04:05")?);
+        assert_eq!("等两点", normalize("等2:00")?);
+        assert_eq!("三到五个", normalize("3-5个")?);
+        assert_eq!("百分之五十的人", normalize("50%的人")?);
+        assert_eq!("增长百分之十二点五", normalize("增长12.5%")?);
+        assert_eq!("三分之一的人", normalize("1/3的人")?);
+        assert_eq!("花了十二点五元", normalize("花了¥12.5")?);
+        assert_eq!("花了五美元", normalize("花了$5")?);
+        assert_eq!("拨打幺三八零零幺三八零零零", normalize("拨打13800138000")?);
+        assert_eq!(
+            "电话零幺零，幺二三四五六七八",
+            normalize("电话010-12345678")?
+        );
+        assert_eq!("五千克重", normalize("5kg重")?);
+        assert_eq!("一百平方米的房子", normalize("100m²的房子")?);
+        assert_eq!("气温三十摄氏度", normalize("气温30℃")?);
+        assert_eq!("两个人", normalize("2个人")?);
+        assert_eq!("十二个人", normalize("12个人")?);
+        assert_eq!("二月", normalize("2月")?);
+        assert_eq!("零下五度", normalize("零下-5度")?);
+        assert_eq!("温度是负三摄氏度", normalize("温度是-3℃")?);
+        assert_eq!("气温负五", normalize("气温-5")?);
+        assert_eq!("下降负百分之三", normalize("下降-3%")?);
+        assert_eq!("第二名", normalize("第2名")?);
+        assert_eq!("第二个和两个", normalize("第2个和2个")?);
+
+        assert_eq!(
+            "有一萬二千人，其中兩千人",
+            super::normalize(
+                Normalized::new("有12000人，其中2000人"),
+                ChineseVariant::Traditional
+            )?
+            .text
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1888 @@
+use {
+    super::{
+        Acronyms, Analysis, DropReason, Dropped, G2PError, Lexicon, PhonemeSource, Pronunciation,
+        Segment, Word, contraction, dialect, fr, heteronym, hi, is_word_en, ja,
+        lexicon::is_han,
+        markup::{self, Markup},
+        misaki,
+        normalize::{ChineseVariant, EmojiReading, normalize},
+        romance, text2zhuyin_v11, to_half_shape, translit, v10, v11, word2ipa_en, word2ipa_zh,
+    },
+    crate::{get_token_ids, tokenizer::is_in_vocab},
+    jieba_rs::Jieba,
+    log::warn,
+    regex::{Captures, Match, Regex},
+    std::{
+        collections::HashMap,
+        sync::{Arc, LazyLock},
+    },
+};
+
+/// 文字类别，用于把文本切分后交给对应的音素化前端
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Script {
+    /// 汉字
+    Han,
+    /// 假名，即平假名和片假名，与假名相连的汉字也属于这一类
+    Kana,
+    /// 拉丁字母，包括Latin-1范围内的数字、空格和西文标点，以及扩展拉丁字母（ł、ő、ş）
+    Latin,
+    /// 天城文，用于印地语
+    Devanagari,
+    /// 希腊字母
+    Greek,
+    /// 西里尔字母
+    Cyrillic,
+    /// 阿拉伯字母
+    Arabic,
+    /// 希伯来字母
+    Hebrew,
+    /// 韩文
+    Hangul,
+    /// 泰文
+    Thai,
+    /// 其他文字，以及不属于任何文字的符号，例如表情符号
+    Other,
+}
+
+/// 没有注册前端的文本的处理方式
+///
+/// 没有注册前端的文字和不属于任何文字的符号按该方式处理，跳过的文本会记录警告日志，并出现在`Analysis::dropped`中。
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Fallback {
+    /// 跳过
+    #[default]
+    Skip,
+    /// 把希腊字母、西里尔字母和韩文转写为拉丁字母后按拉丁字母读出，其他文字跳过
+    Spell,
+    /// 替换为指定的文本，例如`Fallback::Replace(",".into())`读作停顿
+    Replace(String),
+}
+
+/// 音素化前端
+///
+/// 该trait把一段属于同一种文字的文本转换为Kokoro使用的音标。实现该trait并通过`PhonemizerChain::register`或`KokoroTts::with_phonemizer`注册，就可以替换内置的前端，或者为其他语言、领域提供发音规则。
+pub trait Phonemizer: Send + Sync {
+    /// 把文本转换为音标
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 要转换的文本，只包含注册时对应文字类别的字符。
+    fn phonemize(&self, text: &str) -> Result<String, G2PError>;
+
+    /// 把文本切分为单词并分别转换为音标，用于`PhonemizerChain::analyze`
+    ///
+    /// 默认把整段文本当作一个单词。
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 要转换的文本，单词的偏移相对于该文本。
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        Ok(vec![Word {
+            text: text.to_owned(),
+            offset: 0,
+            tag: None,
+            phonemes: self.phonemize(text)?,
+            source: PhonemeSource::Phonemizer,
+        }])
+    }
+}
+
+impl<P> Phonemizer for Arc<P>
+where
+    P: Phonemizer + ?Sized,
+{
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        (**self).phonemize(text)
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        (**self).analyze(text)
+    }
+}
+
+/// cmudict中一个单词有多个发音时的选择方式
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum PronunciationSelection {
+    /// 总是使用第一个发音，同一段文本每次都得到相同的结果
+    #[default]
+    First,
+    /// 由种子和单词决定，种子相同时结果相同，不同的种子可以得到不同的发音
+    Seeded(u64),
+}
+
+/// 英语的口音，决定输出misaki美式还是英式的音标
+///
+/// 合成时根据语音名称的前缀自动选择。
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum EnglishDialect {
+    /// 美式英语，用于`af_`、`am_`等语音
+    #[default]
+    American,
+    /// 英式英语，用于`bf_`、`bm_`开头的语音，元音后面没有元音时不读`ɹ`，BATH词读作`ɑː`
+    British,
+}
+
+impl EnglishDialect {
+    /// 该口音的内置词典，包括该口音特有的读法，例如英式的schedule、美式的tomato
+    ///
+    /// 可以在内置词典的基础上修改，再通过`EnglishPhonemizer::with_lexicon`或`KokoroTts::with_english_lexicon`使用。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{EnglishDialect, Pronunciation};
+    ///
+    /// let lexicon = EnglishDialect::British.lexicon();
+    /// assert_eq!(lexicon.get("schedule"), Some(&Pronunciation::Ipa("ʃˈɛdjuːl".into())));
+    /// ```
+    ///
+    pub fn lexicon(self) -> Lexicon {
+        dialect::builtin_lexicon(self).clone()
+    }
+}
+
+/// 语音的语言，决定拉丁字母、汉字和数字的读法以及英语的口音
+///
+/// 合成时根据语音名称的前缀自动选择。
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Language {
+    /// 普通话，用于`zf_`、`zm_`开头的语音
+    #[default]
+    Mandarin,
+    /// 英语，用于`af_`、`am_`、`bf_`、`bm_`开头的语音
+    English(EnglishDialect),
+    /// 日语，用于`jf_`、`jm_`开头的语音，汉字和数字读作日语
+    Japanese,
+    /// 西班牙语，用于`ef_`、`em_`开头的语音
+    Spanish,
+    /// 意大利语，用于`if_`、`im_`开头的语音
+    Italian,
+    /// 巴西葡萄牙语，用于`pf_`、`pm_`开头的语音
+    Portuguese,
+    /// 法语，用于`ff_`开头的语音
+    French,
+    /// 印地语，用于`hf_`、`hm_`开头的语音，拉丁字母按印地语的转写读出
+    Hindi,
+}
+
+/// 内置的英语前端，使用espeak或cmudict
+///
+/// 两种后端的输出都会转换为misaki的音标，即Kokoro训练时使用的音标。
+///
+/// 常见的多音词（read、lead、live、record、wind、tear）根据前后的单词选择发音，其他单词的结果只取决于单词本身和`PronunciationSelection`。单词先在口音词典中查找，找不到时才使用后端。缩略词和大小写混合的单词按`Acronyms`的设置读出。
+#[derive(Clone, Debug, Default)]
+pub struct EnglishPhonemizer {
+    selection: PronunciationSelection,
+    dialect: EnglishDialect,
+    // 口音词典，`None`时使用内置词典
+    lexicon: Option<Arc<Lexicon>>,
+    acronyms: Arc<Acronyms>,
+}
+
+impl EnglishPhonemizer {
+    /// 创建英语前端，输出美式英语的音标，总是使用词典中的第一个发音
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置词典中有多个发音时的选择方式，只对`use-cmudict`特性有效
+    ///
+    /// # 参数
+    ///
+    /// * `selection` - 选择方式。
+    pub fn with_selection(mut self, selection: PronunciationSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// 设置输出音标的口音
+    ///
+    /// # 参数
+    ///
+    /// * `dialect` - 口音。
+    pub fn with_dialect(mut self, dialect: EnglishDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// 替换口音词典，默认使用`EnglishDialect::lexicon`
+    ///
+    /// # 参数
+    ///
+    /// * `lexicon` - 口音词典，音标应使用对应口音的misaki音标。
+    pub fn with_lexicon(mut self, lexicon: Lexicon) -> Self {
+        self.lexicon = Some(Arc::new(lexicon));
+        self
+    }
+
+    /// 设置缩略词的读法，默认使用内置列表
+    ///
+    /// # 参数
+    ///
+    /// * `acronyms` - 缩略词的读法。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{AcronymReading, Acronyms, EnglishPhonemizer, Phonemizer};
+    ///
+    /// let mut acronyms = Acronyms::new();
+    /// acronyms.insert("NATO", AcronymReading::Spell);
+    /// let en = EnglishPhonemizer::new().with_acronyms(acronyms);
+    /// assert_ne!(en.phonemize("NATO").unwrap(), EnglishPhonemizer::new().phonemize("NATO").unwrap());
+    /// ```
+    ///
+    pub fn with_acronyms(mut self, acronyms: Acronyms) -> Self {
+        self.acronyms = Arc::new(acronyms);
+        self
+    }
+
+    /// 转换一个单词，`prev`和`next`是多音词的语境，`shouting`表示整段文本都是大写
+    fn pronounce(
+        &self,
+        word: &str,
+        prev: Option<&str>,
+        next: Option<&str>,
+        shouting: bool,
+    ) -> Result<String, G2PError> {
+        let lexicon = self
+            .lexicon
+            .as_deref()
+            .unwrap_or_else(|| dialect::builtin_lexicon(self.dialect));
+        if let Some(Pronunciation::Ipa(phonemes)) = lexicon.get(word) {
+            return Ok(misaki::to_misaki(phonemes, self.dialect));
+        }
+
+        let word = &translit::latin_to_ascii(word);
+        let convert = |word: &str| match contraction::lookup(word)? {
+            Some(phonemes) => Ok(phonemes),
+            None => word2ipa_en(word, self.selection),
+        };
+        let phonemes = match self
+            .acronyms
+            .pronounce(word, shouting, convert, is_word_en)?
+        {
+            Some(phonemes) => misaki::to_misaki(&phonemes, self.dialect),
+            None => {
+                let phonemes = match heteronym::disambiguate(word, prev, next)? {
+                    Some(phonemes) => phonemes,
+                    None => convert(word)?,
+                };
+                let phonemes = misaki::to_misaki(&phonemes, self.dialect);
+                dialect::apply_rhotic(word, &phonemes, self.dialect)
+            }
+        };
+        Ok(dialect::apply_bath(word, &phonemes, self.dialect))
+    }
+
+    /// 把文本切分为单词和其他字符，单词附带音标
+    ///
+    /// 缩约形式和所有格（don't、John's）作为一个单词查询，弯撇号`’`与`'`相同；连字符连接的复合词（state-of-the-art）逐个部分转换，各部分的音标以空格分隔。
+    fn words<'a>(&self, text: &'a str) -> Result<Vec<(Match<'a>, Option<String>)>, G2PError> {
+        let en_word_pattern = regex!(r"\w+(?:['’]\w+)*(?:-\w+(?:['’]\w+)*)*|\W+")?;
+        let pieces = en_word_pattern.find_iter(text).collect::<Vec<_>>();
+        // 带附加符号的拉丁字母转写为ASCII字母后读出，例如Łódź、straße
+        let is_word = |i: &Match| {
+            i.as_str()
+                .starts_with(|c: char| c == '_' || translit::is_latin(c))
+        };
+        // 同一句中前后的单词，作为多音词的语境，复合词取相邻的部分
+        let context = |range: &mut dyn Iterator<Item = &Match<'a>>, last: bool| {
+            range
+                .take_while(|i| !i.as_str().contains(['.', '!', '?', ';', ':']))
+                .find(|i| i.as_str().starts_with(translit::is_latin))
+                .and_then(|i| {
+                    let mut parts = i.as_str().split('-');
+                    if last {
+                        parts.next_back()
+                    } else {
+                        parts.next()
+                    }
+                })
+                .map(|i| i.replace('’', "'"))
+        };
+        // 整段文本都是大写时视为强调，其中的大写单词不作为缩略词
+        let shouting = !text.chars().any(char::is_lowercase)
+            && pieces
+                .iter()
+                .filter(|i| {
+                    is_word(i) && i.as_str().chars().filter(|c| c.is_alphabetic()).count() > 1
+                })
+                .count()
+                > 1;
+
+        let mut words = Vec::with_capacity(pieces.len());
+        for (i, piece) in pieces.iter().enumerate() {
+            if !is_word(piece) {
+                words.push((*piece, None));
+                continue;
+            }
+            let word = piece.as_str().replace('’', "'");
+            let parts = word.split('-').collect::<Vec<_>>();
+            let prev = context(&mut pieces[..i].iter().rev(), true);
+            let next = context(&mut pieces[i + 1..].iter(), false);
+            let mut phonemes = Vec::with_capacity(parts.len());
+            for (j, part) in parts.iter().enumerate() {
+                let prev = match j {
+                    0 => prev.as_deref(),
+                    _ => Some(parts[j - 1]),
+                };
+                let next = parts.get(j + 1).copied().or(next.as_deref());
+                phonemes.push(self.pronounce(part, prev, next, shouting)?);
+            }
+            words.push((*piece, Some(phonemes.join(" "))));
+        }
+
+        Ok(words)
+    }
+}
+
+impl Phonemizer for EnglishPhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        let mut result = String::new();
+        for (i, phonemes) in self.words(text)? {
+            if let Some(phonemes) = phonemes {
+                if result.trim_end().ends_with(['.', ',', '!', '?']) && !result.ends_with(' ') {
+                    result.push(' ');
+                }
+                result.push_str(&phonemes);
+            } else if i.as_str().starts_with(' ') && result.ends_with(' ') {
+                result.push_str(i.as_str().trim_start());
+            } else {
+                result.push_str(i.as_str());
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        let mut words = Vec::new();
+        for (i, phonemes) in self.words(text)? {
+            let word = i.as_str();
+            let phonemes = match phonemes {
+                Some(phonemes) => phonemes,
+                None if word.trim().is_empty() => continue,
+                None => word.trim().to_owned(),
+            };
+            words.push(Word {
+                text: word.to_owned(),
+                offset: i.start(),
+                tag: None,
+                phonemes,
+                source: PhonemeSource::Phonemizer,
+            });
+        }
+
+        Ok(words)
+    }
+}
+
+/// v1.0模型使用的分词器，加载词典较慢，因此所有前端共用一个
+static JIEBA: LazyLock<Jieba> = LazyLock::new(Jieba::new);
+
+/// 内置的普通话前端
+#[derive(Copy, Clone, Debug)]
+pub struct MandarinPhonemizer {
+    v11: bool,
+}
+
+impl MandarinPhonemizer {
+    /// 创建普通话前端
+    ///
+    /// # 参数
+    ///
+    /// * `v11` - 是否输出v1.1模型使用的注音符号，否则输出v1.0模型使用的国际音标。
+    pub fn new(v11: bool) -> Self {
+        Self { v11 }
+    }
+}
+
+impl Phonemizer for MandarinPhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        if self.v11 {
+            return text2zhuyin_v11(text);
+        }
+
+        let mut result = String::new();
+        for i in JIEBA.cut(text, true) {
+            if !result.is_empty() {
+                result.push(' ');
+            }
+            result.push_str(&word2ipa_zh(i)?);
+        }
+        Ok(result)
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        if self.v11 {
+            return Ok(v11::g2p_words(text, true)
+                .into_iter()
+                .map(|tk| Word {
+                    phonemes: if tk.phonemes.is_empty() {
+                        v11::UNK.to_owned()
+                    } else {
+                        tk.phonemes
+                    },
+                    text: tk.word,
+                    offset: tk.offset,
+                    tag: Some(tk.tag),
+                    source: PhonemeSource::Phonemizer,
+                })
+                .collect());
+        }
+
+        let mut words = Vec::new();
+        let mut offset = 0;
+        for i in JIEBA.tag(text, true) {
+            words.push(Word {
+                text: i.word.to_owned(),
+                offset,
+                tag: Some(i.tag.to_owned()),
+                phonemes: word2ipa_zh(i.word)?,
+                source: PhonemeSource::Phonemizer,
+            });
+            offset += i.word.len();
+        }
+        Ok(words)
+    }
+}
+
+/// 内置的日语前端
+///
+/// 使用内置词典按最长匹配分词，动词和形容词的活用形由词典形生成，把假名转换为国际音标，并按词典中的声调核在音高下降前的音拍后面加`↓`。词典中没有的假名按写法读出，词典中没有的汉字无法转换。`PhonemizerChain`在v1.1模型下不标注声调，因为v1.1的词表没有`↓`。
+#[derive(Copy, Clone, Debug)]
+pub struct JapanesePhonemizer {
+    pitch_accent: bool,
+}
+
+impl Default for JapanesePhonemizer {
+    fn default() -> Self {
+        Self { pitch_accent: true }
+    }
+}
+
+impl JapanesePhonemizer {
+    /// 创建日语前端，默认标注声调
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置是否标注声调
+    ///
+    /// # 参数
+    ///
+    /// * `pitch_accent` - 是否在音高下降处加`↓`。
+    pub fn with_pitch_accent(mut self, pitch_accent: bool) -> Self {
+        self.pitch_accent = pitch_accent;
+        self
+    }
+
+    /// 一个词的音标，无法转换的汉字为`v11::UNK`
+    fn pronounce(&self, token: &ja::Token) -> String {
+        match token.kind {
+            ja::Kind::Unknown => v11::UNK.to_owned(),
+            _ => token.to_ipa(self.pitch_accent),
+        }
+    }
+}
+
+impl Phonemizer for JapanesePhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Ok(ja::tokenize(text)
+            .iter()
+            .map(|i| self.pronounce(i))
+            .collect())
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        Ok(ja::tokenize(text)
+            .iter()
+            .map(|i| Word {
+                text: i.text.to_owned(),
+                offset: i.offset,
+                tag: None,
+                phonemes: self.pronounce(i),
+                source: PhonemeSource::Phonemizer,
+            })
+            .collect())
+    }
+}
+
+/// 把文本切分为单词和其他字符，单词按拼写规则转换为音标
+fn romance_words(
+    text: &str,
+    convert: fn(&str) -> String,
+) -> Result<Vec<(Match<'_>, Option<String>)>, G2PError> {
+    let word_pattern = regex!(r"\w+(?:['’]\w+)*|\W+")?;
+    Ok(word_pattern
+        .find_iter(text)
+        .map(|i| {
+            let phonemes = i
+                .as_str()
+                .starts_with(char::is_alphabetic)
+                .then(|| convert(i.as_str()));
+            (i, phonemes)
+        })
+        .collect())
+}
+
+/// 单词之外的字符，倒置的问号和叹号（¿、¡）不发音，书名号（«、»）换成词表中的引号，天城文的句号（।、॥）换成`.`
+fn punctuation(text: &str) -> String {
+    text.replace(['¿', '¡'], "")
+        .replace('«', "“")
+        .replace('»', "”")
+        .replace(['।', '॥'], ".")
+}
+
+/// 拼接单词的音标和其他字符
+fn phonemize_words(words: Vec<(Match<'_>, Option<String>)>) -> String {
+    words
+        .into_iter()
+        .map(|(i, phonemes)| phonemes.unwrap_or_else(|| punctuation(i.as_str())))
+        .collect()
+}
+
+fn analyze_words(words: Vec<(Match<'_>, Option<String>)>) -> Vec<Word> {
+    words
+        .into_iter()
+        .filter_map(|(i, phonemes)| {
+            let phonemes = match phonemes {
+                Some(phonemes) => phonemes,
+                None => punctuation(i.as_str()).trim().to_owned(),
+            };
+            (!phonemes.is_empty()).then(|| Word {
+                text: i.as_str().to_owned(),
+                offset: i.start(),
+                tag: None,
+                phonemes,
+                source: PhonemeSource::Phonemizer,
+            })
+        })
+        .collect()
+}
+
+/// 内置的西班牙语前端
+///
+/// 按拼写规则转换为国际音标，c、z读作`θ`，b、d、g在元音之间读作擦音。没有重音符号的单词按以元音、n、s结尾重读倒数第二个音节、其他重读最后一个音节的规则标注重音，冠词、介词等虚词不重读。
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SpanishPhonemizer;
+
+impl Phonemizer for SpanishPhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Ok(phonemize_words(romance_words(text, romance::spanish)?))
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        Ok(analyze_words(romance_words(text, romance::spanish)?))
+    }
+}
+
+/// 内置的意大利语前端
+///
+/// 按拼写规则转换为国际音标，双写的辅音读作长辅音。没有重音符号的单词重读倒数第二个音节，冠词、介词等虚词不重读。
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ItalianPhonemizer;
+
+impl Phonemizer for ItalianPhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Ok(phonemize_words(romance_words(text, romance::italian)?))
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        Ok(analyze_words(romance_words(text, romance::italian)?))
+    }
+}
+
+/// 内置的巴西葡萄牙语前端
+///
+/// 按拼写规则转换为国际音标，包括鼻化元音、词尾不重读元音的弱化以及t、d在i前面的腭化。没有重音符号的单词按以a、e、o、am、em结尾重读倒数第二个音节、其他重读最后一个音节的规则标注重音。
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PortuguesePhonemizer;
+
+impl Phonemizer for PortuguesePhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Ok(phonemize_words(romance_words(text, romance::portuguese)?))
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        Ok(analyze_words(romance_words(text, romance::portuguese)?))
+    }
+}
+
+/// 内置的法语前端
+///
+/// 先查找内置的例外词典，再按拼写规则转换为国际音标，包括鼻化元音、词尾不发音的辅音和省略（l'、d'、qu'）。冠词、代词、数词等必须连诵的词后面紧跟以元音开头的单词时读出词尾的辅音，例如les amis读作`lez amˈi`。
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FrenchPhonemizer;
+
+impl Phonemizer for FrenchPhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Ok(phonemize_words(fr::words(text)?))
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        Ok(analyze_words(fr::words(text)?))
+    }
+}
+
+/// 内置的印地语前端
+///
+/// 按天城文的辅音、元音符号和半体符号解析单词，删除不发音的固有元音（例如कमला读作`kˈəmlaː`），鼻化符号（ं、ँ）读作鼻音或鼻化元音。拉丁字母写的印地语（例如`namaste`）按常见的转写规则读出。
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HindiPhonemizer;
+
+impl Phonemizer for HindiPhonemizer {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        Ok(phonemize_words(hi::words(text)?))
+    }
+
+    fn analyze(&self, text: &str) -> Result<Vec<Word>, G2PError> {
+        Ok(analyze_words(hi::words(text)?))
+    }
+}
+
+/// 音素化前端链
+///
+/// 该结构体先对文本进行规范化，再按文字类别切分，把每一段交给注册的前端处理，最后拼接各段的结果。没有注册前端的文字按`Fallback`处理，默认跳过。如果设置了用户词典，词典中的词条会在前端之前查找。
+///
+/// 文本中可以使用misaki风格的标记修正单个词的发音：`[Kokoro](/kˈOkəɹO/)`直接指定音标，`[重庆](chong2 qing4)`指定拼音，`[word](+1)`或`[word](-1)`调整英语单词的重音。标记优先于用户词典，且不会出现在输出的音标中。
+#[derive(Clone, Default)]
+pub struct PhonemizerChain {
+    v11: bool,
+    phonemizers: HashMap<Script, Arc<dyn Phonemizer>>,
+    lexicon: Option<Arc<Lexicon>>,
+    variant: ChineseVariant,
+    emoji: EmojiReading,
+    language: Language,
+    dialect: EnglishDialect,
+    selection: PronunciationSelection,
+    english_lexicons: HashMap<EnglishDialect, Arc<Lexicon>>,
+    acronyms: Arc<Acronyms>,
+    fallback: Fallback,
+    // 是否使用内置的前端，修改语言或英语设置时需要重新注册
+    builtin: bool,
+}
+
+impl PhonemizerChain {
+    /// 创建带有内置英语、普通话、日语和印地语前端的前端链，汉字读作普通话
+    ///
+    /// # 参数
+    ///
+    /// * `use_v11` - 是否为v1.1模型生成音标。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{G2PError, Phonemizer, PhonemizerChain, Script};
+    ///
+    /// struct Spell;
+    ///
+    /// impl Phonemizer for Spell {
+    ///     fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+    ///         Ok(text.to_lowercase())
+    ///     }
+    /// }
+    ///
+    /// let mut chain = PhonemizerChain::new(false);
+    /// chain.register(Script::Latin, Spell);
+    /// assert_eq!(chain.phonemize("ABC").unwrap(), "abc");
+    /// ```
+    ///
+    pub fn new(use_v11: bool) -> Self {
+        let mut chain = Self {
+            v11: use_v11,
+            builtin: true,
+            ..Default::default()
+        };
+        chain.register(Script::Han, MandarinPhonemizer::new(use_v11));
+        // v1.1的词表没有`↓`
+        chain.register(
+            Script::Kana,
+            JapanesePhonemizer::new().with_pitch_accent(!use_v11),
+        );
+        chain.register(Script::Devanagari, HindiPhonemizer);
+        chain.register_latin();
+        chain
+    }
+
+    /// 按当前的语言和英语设置重新注册内置的拉丁字母前端
+    fn register_latin(&mut self) {
+        if !self.builtin {
+            return;
+        }
+        match self.language {
+            Language::Spanish => self.register(Script::Latin, SpanishPhonemizer),
+            Language::Italian => self.register(Script::Latin, ItalianPhonemizer),
+            Language::Portuguese => self.register(Script::Latin, PortuguesePhonemizer),
+            Language::French => self.register(Script::Latin, FrenchPhonemizer),
+            Language::Hindi => self.register(Script::Latin, HindiPhonemizer),
+            _ => {
+                let mut english = EnglishPhonemizer::new()
+                    .with_dialect(self.dialect)
+                    .with_selection(self.selection);
+                english.lexicon = self.english_lexicons.get(&self.dialect).cloned();
+                english.acronyms = self.acronyms.clone();
+                self.register(Script::Latin, english)
+            }
+        };
+    }
+
+    /// 为一种文字注册前端，替换已有的前端
+    ///
+    /// # 参数
+    ///
+    /// * `script` - 文字类别。
+    /// * `phonemizer` - 音素化前端。
+    pub fn register<P>(&mut self, script: Script, phonemizer: P) -> &mut Self
+    where
+        P: Phonemizer + 'static,
+    {
+        self.phonemizers.insert(script, Arc::new(phonemizer));
+        self
+    }
+
+    /// 设置用户词典，替换已有的词典
+    ///
+    /// # 参数
+    ///
+    /// * `lexicon` - 用户词典。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Lexicon, Phonemizer, PhonemizerChain, Pronunciation};
+    ///
+    /// let mut lexicon = Lexicon::new();
+    /// lexicon.insert("Kokoro", Pronunciation::Ipa("kəkˈɔːɹəʊ".into()));
+    /// let mut chain = PhonemizerChain::new(false);
+    /// chain.set_lexicon(lexicon);
+    /// assert_eq!(chain.phonemize("Kokoro").unwrap(), "kəkˈɔɹO");
+    /// ```
+    ///
+    pub fn set_lexicon(&mut self, lexicon: Lexicon) -> &mut Self {
+        self.lexicon = Some(Arc::new(lexicon));
+        self
+    }
+
+    /// 设置没有注册前端的文本的处理方式，默认跳过
+    ///
+    /// # 参数
+    ///
+    /// * `fallback` - 处理方式。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Fallback, Phonemizer, PhonemizerChain};
+    ///
+    /// let mut chain = PhonemizerChain::new(false);
+    /// chain.set_fallback(Fallback::Spell);
+    /// assert!(!chain.phonemize("Москва").unwrap().is_empty());
+    /// ```
+    ///
+    pub fn set_fallback(&mut self, fallback: Fallback) -> &mut Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// 设置汉语文本规范化输出的字形，默认为简体
+    ///
+    /// # 参数
+    ///
+    /// * `variant` - 字形。
+    pub fn set_chinese_variant(&mut self, variant: ChineseVariant) -> &mut Self {
+        self.variant = variant;
+        self
+    }
+
+    /// 设置表情符号的读法，默认读出名称
+    ///
+    /// # 参数
+    ///
+    /// * `emoji` - 读法。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{EmojiReading, Phonemizer, PhonemizerChain};
+    ///
+    /// let mut chain = PhonemizerChain::new(false);
+    /// chain.set_emoji_reading(EmojiReading::Pause);
+    /// assert_eq!(chain.phonemize("好的👍").unwrap(), chain.phonemize("好的，").unwrap());
+    /// ```
+    ///
+    pub fn set_emoji_reading(&mut self, emoji: EmojiReading) -> &mut Self {
+        self.emoji = emoji;
+        self
+    }
+
+    /// 设置语言，默认为普通话
+    ///
+    /// 日语的汉字由内置的日语前端读出，数字读作日语；英语设置对应的口音；西班牙语、意大利语、葡萄牙语、法语和印地语的拉丁字母和数字由对应的内置前端读出。
+    ///
+    /// # 参数
+    ///
+    /// * `language` - 语言。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Language, Phonemizer, PhonemizerChain};
+    ///
+    /// let mut chain = PhonemizerChain::new(false);
+    /// chain.set_language(Language::Japanese);
+    /// assert_eq!(chain.phonemize("日本").unwrap(), "ɲiho↓ɴ");
+    /// ```
+    ///
+    pub fn set_language(&mut self, language: Language) -> &mut Self {
+        self.language = language;
+        if let Language::English(dialect) = language {
+            self.dialect = dialect;
+        }
+        if self.builtin {
+            match language {
+                Language::Japanese => self.register(
+                    Script::Han,
+                    JapanesePhonemizer::new().with_pitch_accent(!self.v11),
+                ),
+                _ => self.register(Script::Han, MandarinPhonemizer::new(self.v11)),
+            };
+        }
+        self.register_latin();
+        self
+    }
+
+    /// 设置内置英语前端的口音，默认为美式英语
+    ///
+    /// # 参数
+    ///
+    /// * `dialect` - 口音。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{EnglishDialect, Phonemizer, PhonemizerChain};
+    ///
+    /// let mut chain = PhonemizerChain::new(false);
+    /// chain.set_english_dialect(EnglishDialect::British);
+    /// assert_eq!(chain.phonemize("schedule").unwrap(), "ʃˈɛdjuːl");
+    /// ```
+    ///
+    pub fn set_english_dialect(&mut self, dialect: EnglishDialect) -> &mut Self {
+        self.dialect = dialect;
+        self.register_latin();
+        self
+    }
+
+    /// 设置内置英语前端在词典中有多个发音时的选择方式
+    ///
+    /// # 参数
+    ///
+    /// * `selection` - 选择方式。
+    pub fn set_pronunciation_selection(&mut self, selection: PronunciationSelection) -> &mut Self {
+        self.selection = selection;
+        self.register_latin();
+        self
+    }
+
+    /// 替换内置英语前端某种口音的词典，默认使用`EnglishDialect::lexicon`
+    ///
+    /// # 参数
+    ///
+    /// * `dialect` - 口音。
+    /// * `lexicon` - 口音词典，音标应使用对应口音的misaki音标。
+    pub fn set_english_lexicon(&mut self, dialect: EnglishDialect, lexicon: Lexicon) -> &mut Self {
+        self.english_lexicons.insert(dialect, Arc::new(lexicon));
+        self.register_latin();
+        self
+    }
+
+    /// 设置内置英语前端的缩略词读法，默认使用内置列表
+    ///
+    /// # 参数
+    ///
+    /// * `acronyms` - 缩略词的读法。
+    pub fn set_acronyms(&mut self, acronyms: Acronyms) -> &mut Self {
+        self.acronyms = Arc::new(acronyms);
+        self.register_latin();
+        self
+    }
+
+    /// 用另一个前端链中注册的前端、词典和设置覆盖当前的设置，语言和英语的口音保持不变
+    pub(crate) fn extend(&mut self, other: &Self) {
+        self.variant = other.variant;
+        self.emoji = other.emoji;
+        self.fallback = other.fallback.clone();
+        self.selection = other.selection;
+        self.acronyms = other.acronyms.clone();
+        self.english_lexicons.extend(
+            other
+                .english_lexicons
+                .iter()
+                .map(|(dialect, lexicon)| (*dialect, lexicon.clone())),
+        );
+        self.register_latin();
+        if other.lexicon.is_some() {
+            self.lexicon = other.lexicon.clone();
+        }
+        self.phonemizers.extend(
+            other
+                .phonemizers
+                .iter()
+                .map(|(script, phonemizer)| (*script, phonemizer.clone())),
+        );
+    }
+
+    fn push(result: &mut String, phonemes: &str) {
+        let phonemes = if result.is_empty() || result.ends_with(' ') {
+            phonemes.trim_start()
+        } else {
+            phonemes
+        };
+        if !result.is_empty()
+            && !result.ends_with(' ')
+            && !phonemes.starts_with(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+        {
+            result.push(' ');
+        }
+        result.push_str(phonemes);
+    }
+
+    /// 词条或标记给出的发音，英语单词的音标转换为misaki的音标
+    fn pronounce(&self, word: &str, pronunciation: &Pronunciation) -> Result<String, G2PError> {
+        match pronunciation {
+            Pronunciation::Ipa(ipa) if self.is_english(word) => {
+                Ok(misaki::to_misaki(ipa, self.dialect))
+            }
+            Pronunciation::Ipa(ipa) => Ok(ipa.to_owned()),
+            Pronunciation::Pinyin(pinyin) if self.v11 => Ok(v11::pinyin_to_phonemes(pinyin)),
+            Pronunciation::Pinyin(pinyin) => pinyin.split_whitespace().map(v10::py2ipa).collect(),
+            // 替换文本不再查词典，避免循环替换
+            Pronunciation::Alias(text) => self.phonemize_with(text, None),
+        }
+    }
+
+    /// 单词是否由英语前端读出，即不含汉字和假名，并且当前语言的拉丁字母读作英语
+    fn is_english(&self, word: &str) -> bool {
+        matches!(
+            self.language,
+            Language::Mandarin | Language::English(_) | Language::Japanese
+        ) && !word.chars().any(ja::is_japanese)
+    }
+
+    /// 追加汉字的音标，与前一段汉字之间使用对应版本内置前端的分词符
+    fn push_han(&self, result: &mut String, phonemes: &str, han: &mut bool) {
+        if phonemes.is_empty() {
+            return;
+        }
+        if *han && result.ends_with(' ') {
+            result.pop();
+            result.push(if self.v11 { '/' } else { ' ' });
+            result.push_str(phonemes);
+        } else {
+            Self::push(result, phonemes);
+        }
+        result.push(' ');
+        *han = true;
+    }
+
+    /// 按Unicode的文字属性切分文本：汉字或假名、Latin-1以外的标点和空白、拉丁字母和Latin-1字符、天城文，其他文字的字母连成一段，剩下的符号各自成为一段。单词中间的弯撇号（don’t）属于拉丁字母
+    ///
+    /// 日语的汉字和假名连成一段，以便按词典分词；其他语言的汉字和假名分开。
+    fn sentence_pattern(&self) -> Result<&'static Regex, G2PError> {
+        // 汉字和假名之后的各组
+        const OTHERS: &str = r"|((?:·|[\p{P}\p{Zs}--\x00-\xFF]|[\u200B-\u200F\u2060\uFEFF])+)|((?:[\x00-\xFF\p{Latin}\u0300-\u036F]|’\p{Latin})+)|([\p{Devanagari}\u200C\u200D]+)|([\p{L}\p{M}\p{N}--\p{Han}--\p{Latin}--\p{Devanagari}--\x00-\xFF--\u3040-\u30FF]+|.)";
+        Ok(match self.language {
+            Language::Japanese => regex!(&format!(r"([\p{{Han}}\u3040-\u30FF]+){}", OTHERS)),
+            _ => regex!(&format!(r"(\p{{Han}}+|[\u3040-\u30FF]+){}", OTHERS)),
+        }?)
+    }
+
+    /// 汉字或假名所属的文字类别
+    fn cjk_script(text: &str) -> Script {
+        if text.chars().any(ja::is_kana) {
+            Script::Kana
+        } else {
+            Script::Han
+        }
+    }
+
+    /// 其他文字按第一个字符的Unicode文字属性分类，不属于任何已知文字的符号为`Script::Other`
+    fn other_script(text: &str) -> Result<Script, G2PError> {
+        const SCRIPTS: [Script; 6] = [
+            Script::Greek,
+            Script::Cyrillic,
+            Script::Arabic,
+            Script::Hebrew,
+            Script::Hangul,
+            Script::Thai,
+        ];
+        let pattern = regex!(
+            r"^(?:(\p{Greek})|(\p{Cyrillic})|(\p{Arabic})|(\p{Hebrew})|(\p{Hangul})|(\p{Thai}))",
+        )?;
+        Ok(pattern
+            .captures(text)
+            .and_then(|caps| (1..=SCRIPTS.len()).find(|i| caps.get(*i).is_some()))
+            .map_or(Script::Other, |i| SCRIPTS[i - 1]))
+    }
+
+    /// 切分得到的一段文本的文字类别，标点为`None`
+    fn script(caps: &Captures) -> Result<Option<Script>, G2PError> {
+        Ok(
+            match (
+                caps.get(1),
+                caps.get(2),
+                caps.get(3),
+                caps.get(4),
+                caps.get(5),
+            ) {
+                (Some(cjk), ..) => Some(Self::cjk_script(cjk.as_str())),
+                (_, Some(_), ..) => None,
+                (_, _, Some(_), ..) => Some(Script::Latin),
+                (_, _, _, Some(_), _) => Some(Script::Devanagari),
+                (.., Some(other)) => Some(Self::other_script(other.as_str())?),
+                _ => None,
+            },
+        )
+    }
+
+    /// 按`Fallback`读出没有注册前端的文本，返回`None`表示跳过
+    fn fall_back(&self, text: &str, script: Script) -> Result<Option<String>, G2PError> {
+        let phonemes = match &self.fallback {
+            Fallback::Skip => None,
+            Fallback::Spell => match (
+                translit::to_latin(text, script),
+                self.phonemizers.get(&Script::Latin),
+            ) {
+                (Some(latin), Some(phonemizer)) => Some(phonemizer.phonemize(&latin)?),
+                _ => None,
+            },
+            // 替换文本中无法读出的字符直接跳过，避免循环替换
+            Fallback::Replace(replacement) => {
+                let mut chain = self.clone();
+                chain.fallback = Fallback::Skip;
+                Some(chain.phonemize_with(replacement, None)?)
+            }
+        };
+        if phonemes.is_none() {
+            warn!("Unsupported text {}, skipped.", text);
+        }
+        Ok(phonemes)
+    }
+
+    fn phonemize_segments(
+        &self,
+        text: &str,
+        result: &mut String,
+        han: &mut bool,
+    ) -> Result<(), G2PError> {
+        let text = normalize(text, self.variant, self.language, self.emoji)?.text;
+        let sentence_pattern = self.sentence_pattern()?;
+        for i in sentence_pattern.captures_iter(&text) {
+            let Some(m) = i.get(0) else {
+                continue;
+            };
+            let Some(script) = Self::script(&i)? else {
+                let text = to_half_shape(m.as_str());
+                *result = result.trim_end().to_string();
+                result.push_str(&text);
+                result.push(' ');
+                *han = false;
+                continue;
+            };
+            let phonemes = match self.phonemizers.get(&script) {
+                Some(phonemizer) => phonemizer.phonemize(m.as_str())?,
+                None => match self.fall_back(m.as_str(), script)? {
+                    // 替换为标点时与标点一样紧跟前面的单词
+                    Some(phonemes) if phonemes.starts_with(|c: char| c.is_ascii_punctuation()) => {
+                        *result = result.trim_end().to_string();
+                        phonemes
+                    }
+                    Some(phonemes) => phonemes,
+                    None => continue,
+                },
+            };
+            if script == Script::Han {
+                self.push_han(result, &phonemes, han);
+            } else {
+                Self::push(result, &phonemes);
+                *han = false;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn phonemize_lexicon(
+        &self,
+        text: &str,
+        lexicon: Option<&Lexicon>,
+        result: &mut String,
+        han: &mut bool,
+    ) -> Result<(), G2PError> {
+        let pieces = match lexicon {
+            Some(lexicon) => lexicon.split(text)?,
+            None => vec![(text, None)],
+        };
+        for (text, pronunciation) in pieces {
+            match pronunciation {
+                Some(pronunciation) => {
+                    self.push_word(result, text, &self.pronounce(text, pronunciation)?, han)
+                }
+                None => self.phonemize_segments(text, result, han)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 追加一个单词的音标，汉字使用汉字的拼接规则
+    fn push_word(&self, result: &mut String, word: &str, phonemes: &str, han: &mut bool) {
+        if word.chars().any(is_han) {
+            self.push_han(result, phonemes, han)
+        } else {
+            Self::push(result, phonemes);
+            *han = false;
+        }
+    }
+
+    fn phonemize_with(&self, text: &str, lexicon: Option<&Lexicon>) -> Result<String, G2PError> {
+        let mut result = String::new();
+        // 上一段是否为汉字
+        let mut han = false;
+        for (text, markup) in markup::parse(text)? {
+            match markup {
+                Some(Markup::Phonemes(phonemes)) => {
+                    self.push_word(&mut result, text, phonemes, &mut han)
+                }
+                Some(Markup::Pinyin(pinyin)) => {
+                    let phonemes =
+                        self.pronounce(text, &Pronunciation::Pinyin(pinyin.to_owned()))?;
+                    self.push_word(&mut result, text, &phonemes, &mut han)
+                }
+                // 汉语靠声调区分轻重，重音标记只对英语单词有效
+                Some(Markup::Stress(stress)) if !text.chars().any(is_han) => {
+                    let phonemes = self.phonemize_with(text, lexicon)?;
+                    self.push_word(
+                        &mut result,
+                        text,
+                        &markup::apply_stress(&phonemes, stress),
+                        &mut han,
+                    )
+                }
+                _ => self.phonemize_lexicon(text, lexicon, &mut result, &mut han)?,
+            }
+        }
+
+        Ok(result.trim().to_string())
+    }
+
+    /// 分析文本的音素化过程
+    ///
+    /// 返回按文字类别的切分结果、每个单词的音标及其来源、最终的音标和词元，以及被丢弃或无法转换的字符。偏移都是输入文本中的字节偏移。
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 要分析的文本。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{DropReason, PhonemizerChain};
+    ///
+    /// let analysis = PhonemizerChain::new(false).analyze("你好🦩").unwrap();
+    /// assert_eq!(analysis.segments[0].words[0].text, "你好");
+    /// assert_eq!(analysis.dropped[0].offset, 6);
+    /// assert_eq!(analysis.dropped[0].reason, DropReason::UnsupportedScript);
+    /// ```
+    ///
+    pub fn analyze(&self, text: &str) -> Result<Analysis, G2PError> {
+        let lexicon = self.lexicon.as_deref();
+        let mut analysis = Analysis::default();
+        for (piece, markup) in markup::parse(text)? {
+            let offset = piece.as_ptr() as usize - text.as_ptr() as usize;
+            let phonemes = match markup {
+                Some(Markup::Phonemes(phonemes)) => phonemes.to_owned(),
+                Some(Markup::Pinyin(pinyin)) => {
+                    self.pronounce(piece, &Pronunciation::Pinyin(pinyin.to_owned()))?
+                }
+                Some(Markup::Stress(stress)) if !piece.chars().any(is_han) => {
+                    markup::apply_stress(&self.phonemize_with(piece, lexicon)?, stress)
+                }
+                _ => {
+                    self.analyze_lexicon(piece, offset, lexicon, &mut analysis)?;
+                    continue;
+                }
+            };
+            Self::push_pronounced(
+                &mut analysis,
+                piece,
+                offset,
+                phonemes,
+                PhonemeSource::Markup,
+            );
+        }
+
+        for word in analysis.segments.iter().flat_map(|i| i.words.iter()) {
+            for c in word.phonemes.chars() {
+                if !v11::UNK.starts_with(c) && !is_in_vocab(c, self.v11) {
+                    analysis.dropped.push(Dropped {
+                        character: c,
+                        offset: word.offset,
+                        reason: DropReason::NotInVocab,
+                    });
+                }
+            }
+        }
+        analysis.dropped.sort_by_key(|i| i.offset);
+        analysis.phonemes = self.phonemize(text)?;
+        analysis.tokens = get_token_ids(&analysis.phonemes, self.v11);
+
+        Ok(analysis)
+    }
+
+    /// 记录由标记或词典直接给出发音的单词
+    fn push_pronounced(
+        analysis: &mut Analysis,
+        text: &str,
+        offset: usize,
+        phonemes: String,
+        source: PhonemeSource,
+    ) {
+        let script = if text.chars().any(ja::is_japanese) {
+            Self::cjk_script(text)
+        } else {
+            Script::Latin
+        };
+        analysis.segments.push(Segment {
+            text: text.to_owned(),
+            offset,
+            script: Some(script),
+            words: vec![Word {
+                text: text.to_owned(),
+                offset,
+                tag: None,
+                phonemes,
+                source,
+            }],
+        });
+    }
+
+    fn analyze_lexicon(
+        &self,
+        text: &str,
+        offset: usize,
+        lexicon: Option<&Lexicon>,
+        analysis: &mut Analysis,
+    ) -> Result<(), G2PError> {
+        let pieces = match lexicon {
+            Some(lexicon) => lexicon.split(text)?,
+            None => vec![(text, None)],
+        };
+        for (piece, pronunciation) in pieces {
+            let offset = offset + (piece.as_ptr() as usize - text.as_ptr() as usize);
+            match pronunciation {
+                Some(pronunciation) => Self::push_pronounced(
+                    analysis,
+                    piece,
+                    offset,
+                    self.pronounce(piece, pronunciation)?,
+                    PhonemeSource::Lexicon,
+                ),
+                None => self.analyze_segments(piece, offset, analysis)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn analyze_segments(
+        &self,
+        text: &str,
+        offset: usize,
+        analysis: &mut Analysis,
+    ) -> Result<(), G2PError> {
+        let normalized = normalize(text, self.variant, self.language, self.emoji)?;
+        // 规范化后的字节位置在输入文本中的偏移
+        let original = |i: usize| offset + normalized.original(i);
+        let normalized = &normalized.text;
+        let sentence_pattern = self.sentence_pattern()?;
+        let mut end = 0;
+        for i in sentence_pattern.captures_iter(normalized) {
+            let Some(m) = i.get(0) else {
+                continue;
+            };
+            for (j, c) in normalized[end..m.start()].char_indices() {
+                analysis.dropped.push(Dropped {
+                    character: c,
+                    offset: original(end + j),
+                    reason: DropReason::UnsupportedScript,
+                });
+            }
+            end = m.end();
+
+            let (start, stop) = (original(m.start()), original(m.end()));
+            let mut segment = Segment {
+                text: text
+                    .get(start - offset..stop.max(start) - offset)
+                    .unwrap_or_default()
+                    .to_owned(),
+                offset: start,
+                script: None,
+                words: Vec::new(),
+            };
+            let Some(script) = Self::script(&i)? else {
+                segment.words.push(Word {
+                    text: m.as_str().to_owned(),
+                    offset: start,
+                    tag: None,
+                    phonemes: to_half_shape(m.as_str()),
+                    source: PhonemeSource::Punctuation,
+                });
+                analysis.segments.push(segment);
+                continue;
+            };
+            segment.script = Some(script);
+
+            let Some(phonemizer) = self.phonemizers.get(&script) else {
+                if let Some(phonemes) = self.fall_back(m.as_str(), script)? {
+                    segment.words.push(Word {
+                        text: m.as_str().to_owned(),
+                        offset: start,
+                        tag: None,
+                        phonemes,
+                        source: PhonemeSource::Fallback,
+                    });
+                } else {
+                    let reason = match script {
+                        Script::Other => DropReason::UnsupportedScript,
+                        _ => DropReason::NoPhonemizer,
+                    };
+                    for (j, c) in m.as_str().char_indices() {
+                        analysis.dropped.push(Dropped {
+                            character: c,
+                            offset: original(m.start() + j),
+                            reason,
+                        });
+                    }
+                }
+                analysis.segments.push(segment);
+                continue;
+            };
+            for mut word in phonemizer.analyze(m.as_str())? {
+                let position = m.start() + word.offset;
+                if word.phonemes.contains(v11::UNK) {
+                    for (j, c) in word.text.char_indices() {
+                        if !c.is_whitespace() {
+                            analysis.dropped.push(Dropped {
+                                character: c,
+                                offset: original(position + j),
+                                reason: DropReason::Unknown,
+                            });
+                        }
+                    }
+                }
+                word.offset = original(position);
+                segment.words.push(word);
+            }
+            analysis.segments.push(segment);
+        }
+        for (j, c) in normalized[end..].char_indices() {
+            analysis.dropped.push(Dropped {
+                character: c,
+                offset: original(end + j),
+                reason: DropReason::UnsupportedScript,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Phonemizer for PhonemizerChain {
+    fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+        self.phonemize_with(text, self.lexicon.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Upper;
+
+    impl Phonemizer for Upper {
+        fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+            Ok(text.to_uppercase())
+        }
+    }
+
+    #[test]
+    fn test_phonemizer_chain() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::new(false);
+        chain.register(Script::Latin, Upper);
+        assert_eq!("ni↓xau↓ HELLO", chain.phonemize("你好hello")?);
+        assert_eq!("HELLO, ni↓xau↓.", chain.phonemize("hello，你好。")?);
+
+        let mut chain = PhonemizerChain::default();
+        chain.register(Script::Han, Upper);
+        assert_eq!("你好", chain.phonemize("你好hello")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lexicon() -> Result<(), G2PError> {
+        let lexicon = Lexicon::from_tsv(
+            "长城\tA\n长\tB\nnew york\tnuːjˈɔːk\n重庆\tchong2 qing4\nW3C\tworld wide web\talias",
+        )?;
+        let mut chain = PhonemizerChain::new(false);
+        chain.register(Script::Latin, Upper).set_lexicon(lexicon);
+        assert_eq!("A B", chain.phonemize("长城长")?);
+        // 英语词条的音标转换为misaki的音标
+        assert_eq!("I LOVE nujˈɔk!", chain.phonemize("I love New York!")?);
+        assert_eq!("WORLD WIDE WEB", chain.phonemize("w3c")?);
+        assert_eq!(
+            format!("{}{}", v10::py2ipa("chong2")?, v10::py2ipa("qing4")?),
+            chain.phonemize("重庆")?
+        );
+
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("AT&T", Pronunciation::Alias("A T and T".into()));
+        lexicon.insert("Kokoro", Pronunciation::Ipa("kəkˈɔːɹəʊ".into()));
+        let mut chain = PhonemizerChain::new(false);
+        chain.set_lexicon(lexicon);
+        // 词条中的`&`在连续的文本中也能匹配
+        assert!(
+            chain
+                .phonemize("I called AT&T today")?
+                .contains(&chain.phonemize("A T and T")?)
+        );
+        assert_eq!("kəkˈɔɹO", chain.phonemize("Kokoro")?);
+        chain.set_english_dialect(EnglishDialect::British);
+        assert_eq!("kəkˈɔːɹQ", chain.phonemize("Kokoro")?);
+        chain.set_language(Language::French);
+        assert_eq!("kəkˈɔːɹəʊ", chain.phonemize("Kokoro")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_markup() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::new(false);
+        chain.register(Script::Latin, Upper);
+        assert_eq!(
+            "I LOVE kˈOkəɹO!",
+            chain.phonemize("I love [Kokoro](/kˈOkəɹO/)!")?
+        );
+        assert_eq!(
+            format!("{}{}", v10::py2ipa("chong2")?, v10::py2ipa("qing4")?),
+            chain.phonemize("[重庆](chong2 qing4)")?
+        );
+        assert_eq!(
+            format!(
+                "ni↓xau↓ {}{}",
+                v10::py2ipa("chong2")?,
+                v10::py2ipa("qing4")?
+            ),
+            chain.phonemize("你好[重庆](/chong2 qing4/)")?
+        );
+        assert_eq!("WORD", chain.phonemize("[word](???)")?);
+
+        struct Stressed;
+
+        impl Phonemizer for Stressed {
+            fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+                Ok(text.replace("the", "ðə").replace("and", "ˈænd"))
+            }
+        }
+
+        chain.register(Script::Latin, Stressed);
+        assert_eq!("ðˈə cat ˌænd", chain.phonemize("[the](+2) cat [and](-1)")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::new(false);
+        chain.register(Script::Latin, Upper);
+        let text = "我有3个🦩，[Kokoro](/kˈOkəɹO/) hello";
+        let analysis = chain.analyze(text)?;
+        assert_eq!(chain.phonemize(text)?, analysis.phonemes);
+        assert_eq!(get_token_ids(&analysis.phonemes, false), analysis.tokens);
+
+        let han = &analysis.segments[0];
+        assert_eq!(
+            ("我有3个", Some(Script::Han)),
+            (han.text.as_str(), han.script)
+        );
+        assert_eq!(
+            vec![("我", 0, "r"), ("有", 3, "v"), ("三个", 6, "m")],
+            han.words
+                .iter()
+                .map(|i| (
+                    i.text.as_str(),
+                    i.offset,
+                    i.tag.as_deref().unwrap_or_default()
+                ))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some(Script::Other), analysis.segments[1].script);
+        assert_eq!(
+            PhonemeSource::Punctuation,
+            analysis.segments[2].words[0].source
+        );
+        let markup = &analysis.segments[3].words[0];
+        assert_eq!((18, PhonemeSource::Markup), (markup.offset, markup.source));
+
+        assert_eq!(
+            Dropped {
+                character: '🦩',
+                offset: 10,
+                reason: DropReason::UnsupportedScript
+            },
+            analysis.dropped[0]
+        );
+        // 大写字母不在词表中
+        assert!(
+            analysis.dropped[1..]
+                .iter()
+                .all(|i| i.offset == 39 && i.reason == DropReason::NotInVocab)
+        );
+
+        let analysis = PhonemizerChain::default().analyze("你好")?;
+        assert_eq!(DropReason::NoPhonemizer, analysis.dropped[0].reason);
+        assert_eq!(3, analysis.dropped[1].offset);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_english_phonemizer() -> Result<(), G2PError> {
+        let en = EnglishPhonemizer::new();
+        let text = "I will read it. I had read it yesterday.";
+        assert_eq!(en.phonemize(text)?, en.phonemize(text)?);
+        let words = en.analyze(text)?;
+        let read = words
+            .iter()
+            .filter(|i| i.text == "read")
+            .map(|i| i.phonemes.as_str())
+            .collect::<Vec<_>>();
+        assert_ne!(read[0], read[1]);
+        // 句号之后的单词不作为语境
+        assert_eq!(
+            en.analyze("Had. Read")?[2].phonemes,
+            en.analyze("Read")?[0].phonemes
+        );
+
+        // 缩约形式、所有格和复合词都是一个单词
+        let words = en.analyze("Don’t take John's state-of-the-art car")?;
+        assert_eq!(
+            vec!["Don’t", "take", "John's", "state-of-the-art", "car"],
+            words.iter().map(|i| i.text.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(en.phonemize("don't")?, words[0].phonemes);
+        assert_eq!(4, words[3].phonemes.split(' ').count());
+
+        // 两种口音的输出都在两个词表中
+        for dialect in [EnglishDialect::American, EnglishDialect::British] {
+            let phonemes = EnglishPhonemizer::new().with_dialect(dialect).phonemize(
+                "The quick brown fox jumps over the lazy dog's church, judging its measure.",
+            )?;
+            assert!(
+                phonemes
+                    .chars()
+                    .all(|c| is_in_vocab(c, false) && is_in_vocab(c, true))
+            );
+        }
+
+        // 英式英语不读元音后的ɹ，BATH词读作ɑː，并使用英式的读法
+        let gb = EnglishPhonemizer::new().with_dialect(EnglishDialect::British);
+        let us = EnglishPhonemizer::new();
+        assert!(!gb.phonemize("car")?.contains('ɹ'));
+        assert!(gb.phonemize("bath")?.contains("ɑː"));
+        assert!(us.phonemize("bath")?.contains('æ'));
+        // 带附加符号的拉丁字母转写后读出
+        for (text, ascii) in [
+            ("Łódź", "Lodz"),
+            ("Győr", "Gyor"),
+            ("Beşiktaş", "Besiktas"),
+            ("straße", "strasse"),
+        ] {
+            assert_eq!(us.phonemize(ascii)?, us.phonemize(text)?, "{}", text);
+        }
+        let analysis = PhonemizerChain::new(false).analyze("Łódź is nice")?;
+        assert!(analysis.dropped.is_empty());
+        assert_eq!(3, analysis.segments[0].words.len());
+        // 美式英语读元音后的ɹ
+        for (word, phonemes) in [
+            ("car", "kˈɑɹ"),
+            ("far", "fˈɑɹ"),
+            ("park", "pˈɑɹk"),
+            ("heart", "hˈɑɹt"),
+            ("north", "nˈɔɹθ"),
+            ("more", "mˈɔɹ"),
+            ("color", "kˈʌləɹ"),
+            ("hour", "ˈWəɹ"),
+        ] {
+            assert_eq!(phonemes, us.phonemize(word)?, "{}", word);
+        }
+        assert_eq!(1, us.phonemize("carry")?.matches('ɹ').count());
+        assert_eq!("ʃˈɛdjuːl", gb.phonemize("schedule")?);
+        assert_eq!("skˈɛʤul", us.phonemize("schedule")?);
+        let mut lexicon = Lexicon::new();
+        lexicon.insert("schedule", Pronunciation::Ipa("skˈɛʤuːl".into()));
+        assert_eq!("skˈɛʤuːl", gb.with_lexicon(lexicon).phonemize("schedule")?);
+
+        let seeded = EnglishPhonemizer::new().with_selection(PronunciationSelection::Seeded(42));
+        assert_eq!(seeded.phonemize(text)?, seeded.phonemize(text)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_acronyms() -> Result<(), G2PError> {
+        use super::super::AcronymReading;
+
+        let en = EnglishPhonemizer::new();
+        // 全部大写的缩略词逐个字母读出，内置列表中的缩略词按单词读出
+        assert_eq!("ʤˌipˌijˈu", en.phonemize("GPU")?);
+        assert_eq!("ˌAʧtˌitˌipˌiˈɛs", en.phonemize("HTTPS")?);
+        assert_eq!("nˈAtO", en.phonemize("NATO")?);
+        assert_eq!("ʤˌipˌijˈuz", en.phonemize("GPUs")?);
+        // 大小写混合的单词拆分后分别转换
+        assert_eq!(
+            format!("ˈI{}", en.phonemize("phone")?),
+            en.phonemize("iPhone")?
+        );
+        assert_eq!(
+            format!("{}ˌOˈɛs", en.phonemize("mac")?),
+            en.phonemize("macOS")?
+        );
+        // 单独的A和I不是缩略词，整段大写的文本按普通单词读
+        assert_eq!(en.phonemize("a")?, en.phonemize("A")?);
+        assert_eq!(en.phonemize("hello world")?, en.phonemize("HELLO WORLD")?);
+        // 大小写混合的句子中，小写形式是普通单词的大写单词是强调
+        assert_eq!(
+            en.phonemize("This is very important")?,
+            en.phonemize("This is VERY important")?
+        );
+        assert_eq!(en.phonemize("hello world")?, en.phonemize("HELLO world")?);
+        assert_eq!(
+            format!("{} ʤˌipˌijˈu", en.phonemize("a")?),
+            en.phonemize("a GPU")?
+        );
+
+        // 与字母相连的数字单独读出
+        let mut chain = PhonemizerChain::new(false);
+        chain.set_language(Language::English(EnglishDialect::American));
+        assert_eq!(chain.phonemize("GPT four o")?, chain.phonemize("GPT-4o")?);
+        assert_eq!(
+            chain.phonemize("COVID nineteen")?,
+            chain.phonemize("COVID-19")?
+        );
+
+        let mut acronyms = Acronyms::new();
+        acronyms
+            .insert("NATO", AcronymReading::Spell)
+            .insert("SIGGRAPH", AcronymReading::Word);
+        chain.set_acronyms(acronyms);
+        assert_eq!("ˌɛnˌAtˌiˈO", chain.phonemize("NATO")?);
+        assert_eq!(chain.phonemize("siggraph")?, chain.phonemize("SIGGRAPH")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_japanese() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::new(false);
+        chain.set_language(Language::Japanese);
+        let phonemes = chain.phonemize("私は学生です。3人で東京へ行きます！")?;
+        assert_eq!("ɰataɕiɰaɡakɯseːde↓sɯ. saɲɲindetoːkʲoːeikʲima↓sɯ!", phonemes);
+        assert!(phonemes.chars().all(|c| is_in_vocab(c, false)));
+        assert_eq!(
+            "ɰataɕiɰaɡakɯseːdesɯ",
+            JapanesePhonemizer::new()
+                .with_pitch_accent(false)
+                .phonemize("私は学生です")?
+        );
+
+        // 假名不再被丢弃，普通话的汉字不受影响
+        let analysis = PhonemizerChain::new(false).analyze("你好こんにちは")?;
+        assert!(analysis.dropped.is_empty());
+        assert_eq!(Some(Script::Kana), analysis.segments[1].script);
+        assert_eq!("ni↓xau↓ koɲɲiʨiɰa", analysis.phonemes);
+
+        let analysis = chain.analyze("日本語を話します")?;
+        assert_eq!(
+            vec!["日本語", "を", "話します"],
+            analysis.segments[0]
+                .words
+                .iter()
+                .map(|i| i.text.as_str())
+                .collect::<Vec<_>>()
+        );
+        let analysis = chain.analyze("鬱")?;
+        assert_eq!(DropReason::Unknown, analysis.dropped[0].reason);
+
+        // 活用形、量词和日期
+        assert_eq!("kʲino↓ːikʲima↓ɕita", chain.phonemize("昨日行きました")?);
+        assert_eq!("ɡoɡaʦɯiʦɯka", chain.phonemize("5月5日")?);
+
+        // v1.1不标注声调
+        let mut chain = PhonemizerChain::new(true);
+        chain.set_language(Language::Japanese);
+        let analysis = chain.analyze("日本語です")?;
+        assert!(!analysis.phonemes.contains('↓'));
+        assert!(analysis.dropped.is_empty());
+        assert_eq!("koːçiːoɲihai", chain.phonemize("コーヒーを2杯")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_romance() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::new(false);
+        chain.set_language(Language::Spanish);
+        let phonemes = chain.phonemize("¡Hola! Tengo 25 años.")?;
+        assert_eq!("ˈola! tˈeŋɡo bejntiθˈiŋko ˈaɲos.", phonemes);
+        chain.set_language(Language::Italian);
+        assert_eq!("ʧˈao, kˈome stˈaj?", chain.phonemize("Ciao, come stai?")?);
+        chain.set_language(Language::Portuguese);
+        let phonemes = chain.phonemize("Obrigado pela atenção, até 2024!")?;
+        assert_eq!(
+            "obɾiɡˈadʊ pˈelɐ ate\u{303}sˈɐ\u{303}w, atˈɛ dˈojs mˈiw ɪ vˈi\u{303}ʧɪ ɪ kwˈatɾʊ!",
+            phonemes
+        );
+        assert!(phonemes.chars().all(|c| is_in_vocab(c, false)));
+
+        let words = PortuguesePhonemizer.analyze("d’água, ok")?;
+        assert_eq!(
+            vec!["d’água", ", ", "ok"],
+            words.iter().map(|i| i.text.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!("dˈaɡwɐ", words[0].phonemes);
+
+        // 切换回英语时恢复英语前端
+        chain.set_language(Language::English(EnglishDialect::American));
+        assert_eq!("həlˈO", chain.phonemize("hello")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_french() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::new(false);
+        chain.set_language(Language::French);
+        for line in include_str!("../../dict/fr_corpus.tsv").lines() {
+            let Some((text, expected)) = line.split_once('\t') else {
+                continue;
+            };
+            let phonemes = chain.phonemize(text)?;
+            assert_eq!(expected, phonemes, "{}", text);
+            assert!(phonemes.chars().all(|c| is_in_vocab(c, false)), "{}", text);
+        }
+
+        let words = FrenchPhonemizer.analyze("« Oui », les amis")?;
+        assert_eq!(
+            vec!["« ", "Oui", " », ", "les", "amis"],
+            words.iter().map(|i| i.text.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!("“", words[0].phonemes);
+        assert_eq!("lez", words[3].phonemes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hindi() -> Result<(), G2PError> {
+        // 其他语言的语音也能读出天城文
+        let mut chain = PhonemizerChain::new(false);
+        assert_eq!("ni↓xau↓ nəmˈəsteː", chain.phonemize("你好 नमस्ते")?);
+
+        chain.set_language(Language::Hindi);
+        let phonemes = chain.phonemize("मेरे पास 25 रुपये हैं।")?;
+        assert_eq!("mˈeːɾeː pˈaːs pəʧʧˈiːs ɾʊpəjˈeː hɛ\u{303}ː.", phonemes);
+        assert!(phonemes.chars().all(|c| is_in_vocab(c, false)));
+        assert_eq!(
+            chain.phonemize("नमस्ते, आप कैसे हैं?")?,
+            chain.phonemize("namaste, aap kaise hain?")?
+        );
+
+        let analysis = chain.analyze("नमस्ते, दोस्त।")?;
+        assert!(analysis.dropped.is_empty());
+        assert_eq!(Some(Script::Devanagari), analysis.segments[0].script);
+        assert_eq!("dˈoːst", analysis.segments[2].words[0].phonemes);
+        assert_eq!(".", analysis.segments[3].words[0].phonemes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fallback() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::default();
+        chain
+            .register(Script::Han, Upper)
+            .register(Script::Latin, Upper);
+        // 扩展A区的汉字、全角字母和数字、扩展拉丁字母、弯引号和破折号都不会丢失
+        let analysis = chain.analyze("㐀 ＡＢＣ１２３ Łódź ‘hi’–")?;
+        // 大写字母不在词表中
+        assert!(
+            analysis
+                .dropped
+                .iter()
+                .all(|i| i.reason == DropReason::NotInVocab)
+        );
+        assert_eq!(Some(Script::Han), analysis.segments[0].script);
+        assert_eq!(
+            "㐀 ABC ONE HUNDRED TWENTY THREE ŁÓDŹ“ HI”—",
+            chain.phonemize("㐀 ＡＢＣ１２３ Łódź ‘hi’–")?
+        );
+
+        let analysis = chain.analyze("Москва")?;
+        assert_eq!(Some(Script::Cyrillic), analysis.segments[0].script);
+        assert_eq!(6, analysis.dropped.len());
+        assert!(
+            analysis
+                .dropped
+                .iter()
+                .all(|i| i.reason == DropReason::NoPhonemizer)
+        );
+
+        chain.set_fallback(Fallback::Spell);
+        assert_eq!("MOSKVA", chain.phonemize("Москва")?);
+        let analysis = chain.analyze("Москва")?;
+        assert_eq!(
+            PhonemeSource::Fallback,
+            analysis.segments[0].words[0].source
+        );
+        // 阿拉伯字母无法转写
+        assert_eq!(
+            DropReason::NoPhonemizer,
+            chain.analyze("سلام")?.dropped[0].reason
+        );
+
+        chain.set_fallback(Fallback::Replace(",".into()));
+        assert_eq!("A, B", chain.phonemize("a 🦩 b")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_emoji() -> Result<(), G2PError> {
+        let mut chain = PhonemizerChain::new(false);
+        assert_eq!(chain.phonemize("好的拇指向上")?, chain.phonemize("好的👍🏻")?);
+        assert_eq!(
+            chain.phonemize("R and D, family man woman girl boy")?,
+            chain.phonemize("R&D, 👨‍👩‍👧‍👦")?
+        );
+        assert!(chain.analyze("I ❤️ you.")?.dropped.is_empty());
+
+        chain.set_emoji_reading(EmojiReading::Skip);
+        let analysis = chain.analyze("好的👍")?;
+        assert_eq!(DropReason::UnsupportedScript, analysis.dropped[0].reason);
+        Ok(())
+    }
+}
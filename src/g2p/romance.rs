@@ -0,0 +1,181 @@
+mod es;
+mod it;
+mod pt;
+
+/// 音段
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(super) struct Phone {
+    pub(super) ipa: String,
+    pub(super) vowel: bool,
+    /// 元音是否带有表示重音的符号，例如á、è
+    pub(super) accented: bool,
+}
+
+impl Phone {
+    pub(super) fn vowel(ipa: &str, accented: bool) -> Self {
+        Self {
+            ipa: ipa.to_owned(),
+            vowel: true,
+            accented,
+        }
+    }
+
+    pub(super) fn consonant(ipa: &str) -> Self {
+        Self {
+            ipa: ipa.to_owned(),
+            vowel: false,
+            accented: false,
+        }
+    }
+}
+
+/// 单词没有重音符号时重音的位置
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum Stress {
+    /// 倒数第二个音节
+    Penultimate,
+    /// 最后一个音节
+    Last,
+}
+
+/// 一种语言的拼写规则
+pub(super) struct Rules {
+    /// 把小写的单词转换为音段，忽略字母以外的字符
+    pub(super) phones: fn(&[char]) -> Vec<Phone>,
+    /// 是否把元音前面的i、u读作半元音，否则只有元音后面的读作半元音
+    pub(super) rising: bool,
+    /// 根据拼写判断没有重音符号时重音的位置
+    pub(super) stress: fn(&str) -> Stress,
+    /// 确定重音后调整音段，参数是重读元音的位置，例如葡萄牙语词尾元音的弱化
+    pub(super) adjust: fn(&mut [Phone], Option<usize>),
+    /// 不重读的虚词，例如冠词和介词
+    pub(super) unstressed: &'static [&'static str],
+}
+
+/// 是否为元音字母，包括带附加符号的
+pub(super) fn is_vowel(c: char) -> bool {
+    "aeiouyàáâãèéêìíîòóôõùúûü".contains(c)
+}
+
+/// 是否为e、i及带附加符号的e、i，c、g在它们前面改变读音
+pub(super) fn is_front(c: Option<&char>) -> bool {
+    c.is_some_and(|c| "eiéèêíìî".contains(*c))
+}
+
+/// 把与其他元音相邻、不带重音符号的i、u读作半元音j、w
+fn glides(phones: &mut [Phone], rising: bool) {
+    for i in 0..phones.len() {
+        let phone = &phones[i];
+        if !phone.vowel || phone.accented || !matches!(phone.ipa.as_str(), "i" | "u") {
+            continue;
+        }
+        let after_vowel = i > 0 && phones[i - 1].vowel;
+        let before_vowel = phones.get(i + 1).is_some_and(|i| i.vowel);
+        if after_vowel || (rising && before_vowel) {
+            phones[i] = Phone::consonant(if phones[i].ipa == "i" { "j" } else { "w" });
+        }
+    }
+}
+
+/// 重读元音的位置，有重音符号时以第一个带符号的元音为准
+fn stressed(phones: &[Phone], stress: Stress) -> Option<usize> {
+    if let Some(i) = phones.iter().position(|i| i.vowel && i.accented) {
+        return Some(i);
+    }
+    let vowels = phones
+        .iter()
+        .enumerate()
+        .filter(|(_, i)| i.vowel)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    match stress {
+        Stress::Penultimate if vowels.len() > 1 => Some(vowels[vowels.len() - 2]),
+        _ => vowels.last().copied(),
+    }
+}
+
+fn convert(word: &str, rules: &Rules, stress: bool) -> String {
+    let chars = word.chars().collect::<Vec<_>>();
+    let mut phones = (rules.phones)(&chars);
+    glides(&mut phones, rules.rising);
+    let stressed = if stress {
+        stressed(&phones, (rules.stress)(word))
+    } else {
+        None
+    };
+    (rules.adjust)(&mut phones, stressed);
+
+    let mut result = String::new();
+    for (i, phone) in phones.iter().enumerate() {
+        // 与espeak相同，重音符号放在重读元音前面
+        if stressed == Some(i) {
+            result.push('ˈ');
+        }
+        result.push_str(&phone.ipa);
+    }
+    result
+}
+
+/// 按拼写规则把一个单词转换为国际音标
+///
+/// 撇号前面省略了元音的虚词（l'amico、d'água）与后面的单词连读，不重读。
+fn word_to_ipa(word: &str, rules: &Rules) -> String {
+    let word = word.to_lowercase().replace('’', "'");
+    let (clitic, word) = word.rsplit_once('\'').unwrap_or(("", &word));
+    let mut result = convert(clitic, rules, false);
+    result.push_str(&convert(word, rules, !rules.unstressed.contains(&word)));
+    result
+}
+
+/// 把一个西班牙语单词转换为国际音标，不区分大小写
+pub(super) fn spanish(word: &str) -> String {
+    word_to_ipa(word, &es::RULES)
+}
+
+/// 把一个意大利语单词转换为国际音标，不区分大小写
+pub(super) fn italian(word: &str) -> String {
+    word_to_ipa(word, &it::RULES)
+}
+
+/// 把一个巴西葡萄牙语单词转换为国际音标，不区分大小写
+pub(super) fn portuguese(word: &str) -> String {
+    word_to_ipa(word, &pt::RULES)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::tokenizer::is_in_vocab};
+
+    #[test]
+    fn test_glides() {
+        let mut phones = ["b", "u", "e", "i"]
+            .iter()
+            .map(|i| match *i {
+                "b" => Phone::consonant(i),
+                _ => Phone::vowel(i, false),
+            })
+            .collect::<Vec<_>>();
+        glides(&mut phones, false);
+        assert_eq!(
+            vec!["b", "u", "e", "j"],
+            phones.iter().map(|i| i.ipa.as_str()).collect::<Vec<_>>()
+        );
+        glides(&mut phones, true);
+        assert_eq!("w", phones[1].ipa);
+    }
+
+    #[test]
+    fn test_vocab() {
+        let words = [
+            "Cigüeña jamás quería llevar whisky y pan, exhaustos, ¿ñandú? Zorro guapo bebe agua",
+            "Gli gnocchi, la scienza e il ghiaccio: perché chiedere pizza, jazz e caffè?",
+            "Irmã, o coração das lições: põe pães, hoje também chove; tinha olhos, quilos e guerra",
+        ];
+        for (text, convert) in words.iter().zip([spanish, italian, portuguese]) {
+            for word in text.split(|c: char| !c.is_alphabetic() && c != '\'') {
+                let ipa = convert(word);
+                assert!(ipa.chars().all(|c| is_in_vocab(c, false)), "{}", ipa);
+            }
+        }
+    }
+}
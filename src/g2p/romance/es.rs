@@ -0,0 +1,160 @@
+use super::{Phone, Rules, Stress, is_front, is_vowel};
+
+/// 不重读的虚词
+const UNSTRESSED: [&str; 34] = [
+    "el", "la", "los", "las", "lo", "le", "les", "me", "te", "se", "nos", "os", "un", "una",
+    "unos", "unas", "de", "del", "a", "al", "en", "con", "por", "para", "sin", "que", "y", "o",
+    "u", "e", "ni", "mi", "tu", "su",
+];
+
+/// 西班牙语（欧洲）的拼写规则，c、z读作θ
+pub(super) const RULES: Rules = Rules {
+    phones,
+    rising: true,
+    stress,
+    adjust,
+    unstressed: &UNSTRESSED,
+};
+
+fn phones(chars: &[char]) -> Vec<Phone> {
+    let mut phones = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let next = chars.get(i + 1);
+        let ipa = match chars[i] {
+            c @ ('a' | 'e' | 'i' | 'o' | 'u') => {
+                phones.push(Phone::vowel(&c.to_string(), false));
+                i += 1;
+                continue;
+            }
+            c @ ('á' | 'é' | 'í' | 'ó' | 'ú') => {
+                let vowel = match c {
+                    'á' => "a",
+                    'é' => "e",
+                    'í' => "i",
+                    'ó' => "o",
+                    _ => "u",
+                };
+                phones.push(Phone::vowel(vowel, true));
+                i += 1;
+                continue;
+            }
+            'ü' => {
+                phones.push(Phone::vowel("u", false));
+                i += 1;
+                continue;
+            }
+            // 词尾和单独的y是元音
+            'y' if !next.is_some_and(|c| is_vowel(*c)) => {
+                phones.push(Phone::vowel("i", false));
+                i += 1;
+                continue;
+            }
+            'b' | 'v' => "b",
+            'c' if next == Some(&'h') => {
+                i += 1;
+                "ʧ"
+            }
+            'c' if is_front(next) => "θ",
+            'c' | 'k' => "k",
+            'd' => "d",
+            'f' => "f",
+            // gue、gui中的u不发音
+            'g' if next == Some(&'u') && is_front(chars.get(i + 2)) => {
+                i += 1;
+                "ɡ"
+            }
+            'g' if is_front(next) => "x",
+            'g' => "ɡ",
+            'j' => "x",
+            'l' if next == Some(&'l') => {
+                i += 1;
+                "ʎ"
+            }
+            'l' => "l",
+            'm' => "m",
+            'n' => "n",
+            'ñ' => "ɲ",
+            'p' => "p",
+            // que、qui中的u不发音
+            'q' => {
+                if next == Some(&'u') {
+                    i += 1;
+                }
+                "k"
+            }
+            'r' if next == Some(&'r') => {
+                i += 1;
+                "r"
+            }
+            'r' if i == 0 || i > 0 && "lns".contains(chars[i - 1]) => "r",
+            'r' => "ɾ",
+            's' => "s",
+            't' => "t",
+            'w' => "w",
+            'x' => {
+                phones.push(Phone::consonant("k"));
+                "s"
+            }
+            'y' => "ʝ",
+            'z' => "θ",
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        phones.push(Phone::consonant(ipa));
+        i += 1;
+    }
+    phones
+}
+
+/// 以元音、n、s结尾的单词重读倒数第二个音节，其他重读最后一个音节
+fn stress(word: &str) -> Stress {
+    if word.ends_with(|c: char| "aeiouns".contains(c)) {
+        Stress::Penultimate
+    } else {
+        Stress::Last
+    }
+}
+
+/// b、d、g在词首和鼻音后面读作塞音，d在l后面也读作塞音，其他位置读作擦音；n同化为后面辅音的部位
+fn adjust(phones: &mut [Phone], _: Option<usize>) {
+    for i in 0..phones.len() {
+        let prev = i.checked_sub(1).map(|i| phones[i].ipa.clone());
+        let next = phones.get(i + 1).map(|i| i.ipa.clone());
+        let ipa = match (phones[i].ipa.as_str(), prev.as_deref(), next.as_deref()) {
+            ("b", Some(prev), _) if !matches!(prev, "m" | "n") => "β",
+            ("d", Some(prev), _) if !matches!(prev, "m" | "n" | "l") => "ð",
+            ("ɡ", Some(prev), _) if !matches!(prev, "m" | "n" | "ŋ") => "ɣ",
+            ("n", _, Some("b" | "p" | "m")) => "m",
+            ("n", _, Some("k" | "ɡ" | "x")) => "ŋ",
+            _ => continue,
+        };
+        phones[i].ipa = ipa.to_owned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::word_to_ipa, *};
+
+    #[test]
+    fn test_word_to_ipa() {
+        let ipa = |word| word_to_ipa(word, &RULES);
+        assert_eq!("ˈola", ipa("hola"));
+        assert_eq!("ɡɾˈaθjas", ipa("gracias"));
+        assert_eq!("pˈero", ipa("perro"));
+        assert_eq!("kaβˈaʎo", ipa("caballo"));
+        assert_eq!("kanθjˈon", ipa("Canción"));
+        assert_eq!("relˈox", ipa("reloj"));
+        assert_eq!("θjuðˈað", ipa("ciudad"));
+        assert_eq!("ɡˈera", ipa("guerra"));
+        assert_eq!("piŋɡwˈino", ipa("pingüino"));
+        assert_eq!("ˈeksito", ipa("éxito"));
+        assert_eq!("ʝˈo", ipa("yo"));
+        assert_eq!("ˈoj", ipa("hoy"));
+        assert_eq!("el", ipa("el"));
+        assert_eq!("kjˈeɾo", ipa("quiero"));
+    }
+}
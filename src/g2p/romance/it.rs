@@ -0,0 +1,167 @@
+use super::{Phone, Rules, Stress, is_front, is_vowel};
+
+/// 不重读的虚词
+const UNSTRESSED: [&str; 36] = [
+    "il", "lo", "la", "i", "gli", "le", "un", "uno", "una", "di", "a", "da", "in", "con", "su",
+    "per", "tra", "fra", "e", "o", "ma", "che", "non", "mi", "ti", "si", "ci", "vi", "ne", "del",
+    "della", "dei", "al", "alla", "nel", "nella",
+];
+
+/// 意大利语的拼写规则，双写的辅音读作长辅音
+pub(super) const RULES: Rules = Rules {
+    phones,
+    rising: true,
+    stress: |_| Stress::Penultimate,
+    adjust,
+    unstressed: &UNSTRESSED,
+};
+
+/// ci、gi、sci后面跟着元音时，i只表示前面的辅音读作ʧ、ʤ、ʃ
+fn silent_i(chars: &[char], i: usize) -> bool {
+    chars.get(i) == Some(&'i') && chars.get(i + 1).is_some_and(|c| is_vowel(*c))
+}
+
+fn phones(chars: &[char]) -> Vec<Phone> {
+    let mut phones = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let next = chars.get(i + 1);
+        let vowel = match chars[i] {
+            c @ ('a' | 'e' | 'i' | 'o' | 'u') => Some((c.to_string(), false)),
+            'y' => Some(("i".to_owned(), false)),
+            'à' => Some(("a".to_owned(), true)),
+            'è' => Some(("ɛ".to_owned(), true)),
+            'é' => Some(("e".to_owned(), true)),
+            'ì' | 'í' | 'î' => Some(("i".to_owned(), true)),
+            'ò' => Some(("ɔ".to_owned(), true)),
+            'ó' => Some(("o".to_owned(), true)),
+            'ù' | 'ú' => Some(("u".to_owned(), true)),
+            _ => None,
+        };
+        if let Some((vowel, accented)) = vowel {
+            phones.push(Phone::vowel(&vowel, accented));
+            i += 1;
+            continue;
+        }
+
+        let ipa = match chars[i] {
+            'b' => "b",
+            'c' if next == Some(&'h') => {
+                i += 1;
+                "k"
+            }
+            // cc在e、i前面读作tʧ
+            'c' if next == Some(&'c') && is_front(chars.get(i + 2)) => "t",
+            'c' if silent_i(chars, i + 1) => {
+                i += 1;
+                "ʧ"
+            }
+            'c' if is_front(next) => "ʧ",
+            'c' | 'k' | 'q' => "k",
+            'd' => "d",
+            'f' => "f",
+            'g' if next == Some(&'h') => {
+                i += 1;
+                "ɡ"
+            }
+            'g' if next == Some(&'g') && is_front(chars.get(i + 2)) => "d",
+            'g' if next == Some(&'n') => {
+                i += 1;
+                "ɲ"
+            }
+            // gli读作ʎi，后面跟着元音时i不发音
+            'g' if next == Some(&'l') && chars.get(i + 2) == Some(&'i') => {
+                i += if silent_i(chars, i + 2) { 2 } else { 1 };
+                "ʎ"
+            }
+            'g' if silent_i(chars, i + 1) => {
+                i += 1;
+                "ʤ"
+            }
+            'g' if is_front(next) => "ʤ",
+            'g' => "ɡ",
+            'j' => "j",
+            'l' => "l",
+            'm' => "m",
+            'n' => "n",
+            'p' => "p",
+            'r' => "r",
+            's' if next == Some(&'c') && chars.get(i + 2) == Some(&'h') => {
+                phones.push(Phone::consonant("s"));
+                i += 2;
+                "k"
+            }
+            's' if next == Some(&'c') && silent_i(chars, i + 2) => {
+                i += 2;
+                "ʃ"
+            }
+            's' if next == Some(&'c') && is_front(chars.get(i + 2)) => {
+                i += 1;
+                "ʃ"
+            }
+            // 元音之间和浊辅音前面的s读作z
+            's' if (i > 0 && is_vowel(chars[i - 1]) && next.is_some_and(|c| is_vowel(*c)))
+                || next.is_some_and(|c| "bdglmnrv".contains(*c)) =>
+            {
+                "z"
+            }
+            's' => "s",
+            't' => "t",
+            'v' => "v",
+            'w' => "w",
+            'x' => {
+                phones.push(Phone::consonant("k"));
+                "s"
+            }
+            'z' => "ʦ",
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        phones.push(Phone::consonant(ipa));
+        i += 1;
+    }
+    phones
+}
+
+/// 长的塞擦音只重复塞音部分，例如pizza读作pitʦa
+fn adjust(phones: &mut [Phone], _: Option<usize>) {
+    for i in 1..phones.len() {
+        if phones[i - 1] != phones[i] {
+            continue;
+        }
+        let stop = match phones[i].ipa.as_str() {
+            "ʧ" | "ʦ" => "t",
+            "ʤ" | "ʣ" => "d",
+            _ => continue,
+        };
+        phones[i - 1].ipa = stop.to_owned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::word_to_ipa, *};
+
+    #[test]
+    fn test_word_to_ipa() {
+        let ipa = |word| word_to_ipa(word, &RULES);
+        assert_eq!("ʧˈao", ipa("Ciao"));
+        assert_eq!("ɡˈatto", ipa("gatto"));
+        assert_eq!("pˈitʦa", ipa("pizza"));
+        assert_eq!("famˈiʎa", ipa("famiglia"));
+        assert_eq!("ʎi", ipa("gli"));
+        assert_eq!("perkˈe", ipa("perché"));
+        assert_eq!("ʃˈenʦa", ipa("scienza"));
+        assert_eq!("skˈerʦo", ipa("scherzo"));
+        assert_eq!("atʧˈento", ipa("accento"));
+        assert_eq!("ɲˈokki", ipa("gnocchi"));
+        assert_eq!("kwˈando", ipa("quando"));
+        assert_eq!("kˈaza", ipa("casa"));
+        assert_eq!("zmˈalto", ipa("smalto"));
+        assert_eq!("lamˈiko", ipa("l'amico"));
+        assert_eq!("ʤˈorno", ipa("giorno"));
+        assert_eq!("kaffˈɛ", ipa("caffè"));
+    }
+}
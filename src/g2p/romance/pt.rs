@@ -0,0 +1,244 @@
+use super::{Phone, Rules, Stress, is_front, is_vowel};
+
+/// 鼻化元音的附加符号
+const TILDE: char = '\u{0303}';
+
+/// 不重读的虚词
+const UNSTRESSED: [&str; 31] = [
+    "o", "a", "os", "as", "um", "uma", "de", "do", "da", "dos", "das", "em", "no", "na", "nos",
+    "nas", "por", "com", "e", "ou", "que", "se", "me", "te", "lhe", "vos", "mas", "ao", "aos", "à",
+    "às",
+];
+
+/// 巴西葡萄牙语的拼写规则，词尾不重读的元音弱化，t、d在i前面读作ʧ、ʤ
+pub(super) const RULES: Rules = Rules {
+    phones,
+    // dia、rio等词的i、u与后面的元音分属两个音节
+    rising: false,
+    stress,
+    adjust,
+    unstressed: &UNSTRESSED,
+};
+
+/// 元音字母的读音和是否带有重音符号，ã、õ也表示重音
+fn vowel(c: char) -> Option<(String, bool)> {
+    let (ipa, accented) = match c {
+        'a' | 'à' => ("a", false),
+        'e' => ("e", false),
+        'i' | 'y' => ("i", false),
+        'o' => ("o", false),
+        'u' | 'ü' => ("u", false),
+        'á' => ("a", true),
+        'â' => ("ɐ", true),
+        'ã' => ("ɐ\u{0303}", true),
+        'é' => ("ɛ", true),
+        'ê' => ("e", true),
+        'í' => ("i", true),
+        'ó' => ("ɔ", true),
+        'ô' => ("o", true),
+        'õ' => ("o\u{0303}", true),
+        'ú' => ("u", true),
+        _ => return None,
+    };
+    Some((ipa.to_owned(), accented))
+}
+
+/// 把元音鼻化，鼻化元音都是闭的，例如também中的é读作ẽ
+fn nasalize(vowel: &str) -> String {
+    match vowel {
+        "a" | "ɐ" => format!("ɐ{}", TILDE),
+        "ɛ" => format!("e{}", TILDE),
+        "ɔ" => format!("o{}", TILDE),
+        v if v.ends_with(TILDE) => v.to_owned(),
+        v => format!("{}{}", v, TILDE),
+    }
+}
+
+fn phones(chars: &[char]) -> Vec<Phone> {
+    let mut phones = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1);
+        if let Some((ipa, accented)) = vowel(c) {
+            let after = chars.get(i + 2);
+            // m、n后面没有元音时表示前面的元音是鼻化元音，nh除外
+            if next.is_some_and(|c| *c == 'm' || *c == 'n')
+                && !after.is_some_and(|c| is_vowel(*c) || *c == 'h')
+            {
+                let nasal = nasalize(&ipa);
+                let last = i + 2 >= chars.len() || (i + 3 == chars.len() && after == Some(&'s'));
+                phones.push(Phone::vowel(&nasal, accented));
+                // 词尾的em、am读作鼻化的二合元音
+                match (nasal.chars().next(), last) {
+                    (Some('e'), true) => phones.push(Phone::consonant("j")),
+                    (Some('ɐ'), true) if next == Some(&'m') => phones.push(Phone::consonant("w")),
+                    _ => (),
+                }
+                i += 2;
+                continue;
+            }
+            phones.push(Phone::vowel(&ipa, accented));
+            // ão、ãe、õe
+            match (c, next) {
+                ('ã', Some('o')) => phones.push(Phone::consonant("w")),
+                ('ã' | 'õ', Some('e')) => phones.push(Phone::consonant("j")),
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            }
+            i += 2;
+            continue;
+        }
+
+        let ipa = match c {
+            'b' => "b",
+            'c' if next == Some(&'h') => {
+                i += 1;
+                "ʃ"
+            }
+            'c' if is_front(next) => "s",
+            'c' | 'k' => "k",
+            'ç' => "s",
+            'd' => "d",
+            'f' => "f",
+            'g' if next == Some(&'u') && is_front(chars.get(i + 2)) => {
+                i += 1;
+                "ɡ"
+            }
+            // gua、guo中的u读作w
+            'g' if next == Some(&'u') && chars.get(i + 2).is_some_and(|c| is_vowel(*c)) => {
+                phones.push(Phone::consonant("ɡ"));
+                i += 1;
+                "w"
+            }
+            'g' if is_front(next) => "ʒ",
+            'g' => "ɡ",
+            'j' => "ʒ",
+            'l' if next == Some(&'h') => {
+                i += 1;
+                "ʎ"
+            }
+            // 音节末尾的l读作w
+            'l' if !next.is_some_and(|c| is_vowel(*c)) => "w",
+            'l' => "l",
+            'm' => "m",
+            'n' if next == Some(&'h') => {
+                i += 1;
+                "ɲ"
+            }
+            'n' => "n",
+            'p' => "p",
+            // que、qui中的u不发音，qua、quo中的u读作w
+            'q' => {
+                if next == Some(&'u') {
+                    i += 1;
+                    if !is_front(chars.get(i + 1)) {
+                        phones.push(Phone::consonant("k"));
+                        i += 1;
+                        phones.push(Phone::consonant("w"));
+                        continue;
+                    }
+                }
+                "k"
+            }
+            'r' if next == Some(&'r') => {
+                i += 1;
+                "x"
+            }
+            'r' if i == 0 || "lns".contains(chars[i - 1]) => "x",
+            'r' => "ɾ",
+            's' if next == Some(&'s') => {
+                i += 1;
+                "s"
+            }
+            's' if i > 0 && is_vowel(chars[i - 1]) && next.is_some_and(|c| is_vowel(*c)) => "z",
+            's' => "s",
+            't' => "t",
+            'v' => "v",
+            'w' => "w",
+            'x' => "ʃ",
+            'z' if next.is_none() => "s",
+            'z' => "z",
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        phones.push(Phone::consonant(ipa));
+        i += 1;
+    }
+    phones
+}
+
+/// 以a、e、o（可以加s）、am、em、ens结尾的单词重读倒数第二个音节，其他重读最后一个音节
+fn stress(word: &str) -> Stress {
+    let word = word.strip_suffix('s').unwrap_or(word);
+    if word.ends_with(['a', 'e', 'o']) || word.ends_with("am") || word.ends_with("em") {
+        Stress::Penultimate
+    } else {
+        Stress::Last
+    }
+}
+
+/// 词尾不重读的a、e、o弱化为ɐ、ɪ、ʊ，t、d在i、ɪ前面读作ʧ、ʤ
+fn adjust(phones: &mut [Phone], stressed: Option<usize>) {
+    let end = phones
+        .iter()
+        .rposition(|i| i.vowel || i.ipa != "s")
+        .unwrap_or_default();
+    if let Some(last) = phones.get_mut(end)
+        && last.vowel
+        && !last.accented
+        && stressed != Some(end)
+    {
+        let reduced = match last.ipa.as_str() {
+            "a" => "ɐ",
+            "e" => "ɪ",
+            "o" => "ʊ",
+            _ => "",
+        };
+        if !reduced.is_empty() {
+            last.ipa = reduced.to_owned();
+        }
+    }
+
+    for i in 1..phones.len() {
+        if !phones[i].ipa.starts_with(['i', 'ɪ']) {
+            continue;
+        }
+        let affricate = match phones[i - 1].ipa.as_str() {
+            "t" => "ʧ",
+            "d" => "ʤ",
+            _ => continue,
+        };
+        phones[i - 1].ipa = affricate.to_owned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::word_to_ipa, *};
+
+    #[test]
+    fn test_word_to_ipa() {
+        let ipa = |word| word_to_ipa(word, &RULES);
+        assert_eq!("obɾiɡˈadʊ", ipa("obrigado"));
+        assert_eq!("sidˈaʤɪ", ipa("cidade"));
+        assert_eq!("koɾasˈɐ̃w", ipa("coração"));
+        assert_eq!("bˈo\u{303}", ipa("bom"));
+        assert_eq!("mˈujtʊ", ipa("muito"));
+        assert_eq!("bɾazˈiw", ipa("Brasil"));
+        assert_eq!("poɾtuɡˈes", ipa("português"));
+        assert_eq!("ʤˈiɐ", ipa("dia"));
+        assert_eq!("kˈazɐs", ipa("casas"));
+        assert_eq!("tɐ\u{303}bˈe\u{303}j", ipa("também"));
+        assert_eq!("kwˈɐ̃dʊ", ipa("quando"));
+        assert_eq!("kˈejʒʊ", ipa("queijo"));
+        assert_eq!("fˈiʎʊ", ipa("filho"));
+        assert_eq!("xˈozɐ", ipa("rosa"));
+        assert_eq!("kˈaxʊ", ipa("carro"));
+        assert_eq!("dʊ", ipa("do"));
+    }
+}
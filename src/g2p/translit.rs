@@ -0,0 +1,211 @@
+use super::Script;
+
+/// 韩文音节的初声
+const INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+/// 韩文音节的中声
+const MEDIALS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+/// 韩文音节的终声，按代表音读出
+const FINALS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+/// 希腊字母的转写，带重音符号的元音去掉重音符号
+fn greek(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' | 'ά' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' | 'έ' => "e",
+        'ζ' => "z",
+        'η' | 'ή' => "i",
+        'θ' => "th",
+        'ι' | 'ί' | 'ϊ' | 'ΐ' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' | 'ό' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' | 'ύ' | 'ϋ' | 'ΰ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' | 'ώ' => "o",
+        _ => return None,
+    })
+}
+
+/// 西里尔字母的转写，包括俄语、乌克兰语、白俄罗斯语和塞尔维亚语的字母，硬音符号和软音符号不转写
+fn cyrillic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' | 'э' => "e",
+        'ё' => "yo",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ї' => "yi",
+        'й' | 'ы' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ў' => "w",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' | 'ћ' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'є' => "ye",
+        'ю' => "yu",
+        'я' => "ya",
+        'ј' => "j",
+        'љ' => "lj",
+        'њ' => "nj",
+        'ђ' => "dj",
+        'џ' => "dzh",
+        _ => return None,
+    })
+}
+
+/// 按韩国的罗马字标记法转写一个韩文音节，不考虑音节之间的音变
+fn hangul(c: char) -> Option<String> {
+    let index = (c as u32).checked_sub(0xAC00).filter(|i| *i < 11172)? as usize;
+    Some(format!(
+        "{}{}{}",
+        INITIALS[index / 588],
+        MEDIALS[index % 588 / 28],
+        FINALS[index % 28]
+    ))
+}
+
+/// 带附加符号的拉丁字母和ß、ł、ø等特殊字母的ASCII转写，只处理小写字母
+fn latin(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĳ' => "ij",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ŋ' => "ng",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
+/// 是否为可以转写为ASCII的拉丁字母，包括ASCII字母本身
+pub(super) fn is_latin(c: char) -> bool {
+    c.is_ascii_alphabetic() || c.to_lowercase().next().and_then(latin).is_some()
+}
+
+/// 把带附加符号的拉丁字母和ß、ł、ø等特殊字母转写为ASCII字母，大写字母转写后首字母大写，其他字符原样保留
+///
+/// 英语的后端只能读出ASCII字母，例如Łódź转写为Lodz，straße转写为strasse。
+pub(super) fn latin_to_ascii(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        match latin(lower) {
+            Some(ascii) if c != lower => {
+                let mut chars = ascii.chars();
+                result.extend(chars.next().map(|i| i.to_ascii_uppercase()));
+                result.push_str(chars.as_str());
+            }
+            Some(ascii) => result.push_str(ascii),
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+/// 把希腊字母、西里尔字母或韩文转写为小写的拉丁字母，其他文字返回`None`
+///
+/// 文本中无法转写的字符（例如标点）原样保留。
+pub(super) fn to_latin(text: &str, script: Script) -> Option<String> {
+    let convert: fn(char) -> Option<String> = match script {
+        Script::Greek => |c| greek(c).map(str::to_owned),
+        Script::Cyrillic => |c| cyrillic(c).map(str::to_owned),
+        Script::Hangul => hangul,
+        _ => return None,
+    };
+    Some(
+        text.chars()
+            .flat_map(char::to_lowercase)
+            .map(|c| convert(c).unwrap_or_else(|| c.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_latin() {
+        assert_eq!(Some("alfa".to_owned()), to_latin("Άλφα", Script::Greek));
+        assert_eq!(
+            Some("moskva".to_owned()),
+            to_latin("Москва", Script::Cyrillic)
+        );
+        assert_eq!(Some("kiyiv".to_owned()), to_latin("Київ", Script::Cyrillic));
+        assert_eq!(
+            Some("hangukeo".to_owned()),
+            to_latin("한국어", Script::Hangul)
+        );
+        assert_eq!(None, to_latin("שלום", Script::Hebrew));
+    }
+
+    #[test]
+    fn test_latin_to_ascii() {
+        assert_eq!("Lodz", latin_to_ascii("Łódź"));
+        assert_eq!("Gyor", latin_to_ascii("Győr"));
+        assert_eq!("Besiktas", latin_to_ascii("Beşiktaş"));
+        assert_eq!("strasse", latin_to_ascii("straße"));
+        assert_eq!("Oeuvre", latin_to_ascii("Œuvre"));
+        assert_eq!("cafe!", latin_to_ascii("café!"));
+        assert!(is_latin('ł') && is_latin('Ő') && is_latin('a'));
+        assert!(!is_latin('α') && !is_latin('1'));
+    }
+}
@@ -147,7 +147,7 @@ const NOT_ERHUA: [&str; 44] = [
     "狗儿",
     "少儿",
 ];
-const UNK: &str = "❓";
+pub(super) const UNK: &str = "❓";
 
 static PHRASES_DICT: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| {
     let mut map = HashMap::new();
@@ -757,6 +757,12 @@ fn get_pinyin_fine(word: &str) -> Vec<String> {
             .collect::<Vec<_>>()
     };
 
+    fix_syllables(&mut pinyin);
+    pinyin
+}
+
+/// 区分整体认读音节中的i、ii和iii
+fn fix_syllables(pinyin: &mut [String]) {
     for p in pinyin.iter_mut() {
         let Some(tone) = p.chars().next_back() else {
             continue;
@@ -781,7 +787,6 @@ fn get_pinyin_fine(word: &str) -> Vec<String> {
             p.push(tone);
         }
     }
-    pinyin
 }
 
 /// * `word`: 分词
@@ -839,9 +844,36 @@ fn merge_erhua(word: &str, pos: &str, pinyins: &mut [String]) {
     }
 }
 
+/// 分词后的单词
+pub(super) struct MToken {
+    /// 单词
+    pub(super) word: String,
+    /// 单词在文本中的字节偏移
+    pub(super) offset: usize,
+    /// 词性
+    pub(super) tag: String,
+    /// 音标，无法转换时为空
+    pub(super) phonemes: String,
+    /// 与下一个单词之间的分隔符
+    pub(super) whitespace: String,
+}
+
 /// Return: string of phonemes.
 /// 'ㄋㄧ2ㄏㄠ3/ㄕ十4ㄐㄝ4'
 pub(super) fn g2p(text: &str, with_erhua: bool) -> String {
+    g2p_words(text, with_erhua)
+        .iter()
+        .map(|tk| {
+            if tk.phonemes.is_empty() {
+                return UNK.to_owned() + &tk.whitespace;
+            }
+            tk.phonemes.to_owned() + &tk.whitespace
+        })
+        .collect()
+}
+
+/// 分词并转换为音标，保留每个单词的词性和位置
+pub(super) fn g2p_words(text: &str, with_erhua: bool) -> Vec<MToken> {
     let mut seg_cut = JIEBA
         .tag(text, true)
         .iter()
@@ -851,16 +883,13 @@ pub(super) fn g2p(text: &str, with_erhua: bool) -> String {
     // fix wordseg bad case for sandhi
     pre_merge_for_modify(&mut seg_cut);
 
-    struct MToken {
-        tag: String,
-        phonemes: String,
-        whitespace: String,
-    }
-
     // 为了多音词获得更好的效果，这里采用整句预测
     let mut tokens = Vec::with_capacity(seg_cut.len());
+    let mut offset = 0;
     // pypinyin, g2pM
     for (word, pos) in seg_cut.iter() {
+        let word_offset = offset;
+        offset += word.len();
         let tag = if pos == "x"
             && word
                 .chars()
@@ -880,6 +909,8 @@ pub(super) fn g2p(text: &str, with_erhua: bool) -> String {
             pos.to_owned()
         };
         let mut tk = MToken {
+            word: word.to_owned(),
+            offset: word_offset,
             tag,
             whitespace: Default::default(),
             phonemes: Default::default(),
@@ -919,45 +950,52 @@ pub(super) fn g2p(text: &str, with_erhua: bool) -> String {
             merge_erhua(word, pos, &mut pinyins);
         }
 
-        let mut phones = Vec::with_capacity(pinyins.len());
-        for p in pinyins.iter() {
-            // NOTE: post process for pypinyin outputs
-            // we discriminate i, ii and iii
-            let (c, v) = split_initial(p);
-            let mut v = v.to_owned();
-            convert_pinyin(c, &mut v);
-            let (f, t) = split_tone(v.as_str());
-            if !c.is_empty() {
-                phones.push(c.to_owned());
-            }
-            // replace punctuation by ` `
-            if !v.is_empty() {
-                // and v not in rhy_phns:
-                if !PUNC.contains(v.as_str()) {
-                    phones.push(f.to_owned());
-                    phones.push(t.to_string());
-                } else if v != c {
-                    phones.push(v.to_owned());
-                }
-            }
-        }
-        let phones = phones.join("_").replace("_eR", "_er").replace('R', "_R");
-        tk.phonemes = phones
-            .split('_')
-            .map(|c| *ZH_MAP.get(c).unwrap_or(&UNK))
-            .collect::<String>();
+        tk.phonemes = to_phonemes(&pinyins);
         tokens.push(tk);
     }
 
     tokens
-        .iter()
-        .map(|tk| {
-            if tk.phonemes.is_empty() {
-                return UNK.to_owned() + &tk.whitespace;
+}
+
+/// 把带调拼音转换为音标
+fn to_phonemes(pinyins: &[String]) -> String {
+    let mut phones = Vec::with_capacity(pinyins.len());
+    for p in pinyins.iter() {
+        // NOTE: post process for pypinyin outputs
+        // we discriminate i, ii and iii
+        let (c, v) = split_initial(p);
+        let mut v = v.to_owned();
+        convert_pinyin(c, &mut v);
+        let (f, t) = split_tone(v.as_str());
+        if !c.is_empty() {
+            phones.push(c.to_owned());
+        }
+        // replace punctuation by ` `
+        if !v.is_empty() {
+            // and v not in rhy_phns:
+            if !PUNC.contains(v.as_str()) {
+                phones.push(f.to_owned());
+                phones.push(t.to_string());
+            } else if v != c {
+                phones.push(v.to_owned());
             }
-            tk.phonemes.to_owned() + &tk.whitespace
-        })
-        .collect()
+        }
+    }
+    let phones = phones.join("_").replace("_eR", "_er").replace('R', "_R");
+    phones
+        .split('_')
+        .map(|c| *ZH_MAP.get(c).unwrap_or(&UNK))
+        .collect::<String>()
+}
+
+/// 把空格分隔的带调拼音（例如`chong2 qing4`）直接转换为音标，不进行变调处理
+pub(super) fn pinyin_to_phonemes(pinyin: &str) -> String {
+    let mut pinyins = pinyin
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    fix_syllables(&mut pinyins);
+    to_phonemes(&pinyins)
 }
 
 fn convert_pinyin(initial_part: &str, final_part: &mut String) {
@@ -1260,4 +1298,10 @@ mod tests {
         assert_eq!(g2p("借还款", true), "ㄐㄝ4ㄏ万2ㄎ万3");
         assert_eq!(g2p("时间为", true), "ㄕ十2ㄐ言1为2");
     }
+
+    #[test]
+    fn test_pinyin_to_phonemes() {
+        assert_eq!(pinyin_to_phonemes("jie4 huan2 kuan3"), "ㄐㄝ4ㄏ万2ㄎ万3");
+        assert_eq!(pinyin_to_phonemes("Shi2 jian1"), "ㄕ十2ㄐ言1");
+    }
 }
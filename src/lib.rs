@@ -1,22 +1,50 @@
+/// 在第一次使用时编译正则表达式并缓存，返回`Result<&'static Regex, regex::Error>`
+macro_rules! regex {
+    ($pattern:expr $(,)?) => {{
+        static REGEX: std::sync::LazyLock<Result<regex::Regex, regex::Error>> =
+            std::sync::LazyLock::new(|| regex::Regex::new($pattern));
+        REGEX.as_ref().map_err(Clone::clone)
+    }};
+}
+
 mod error;
+mod frame;
 mod g2p;
+mod output;
+mod pitch;
+mod ring;
+mod rtp;
 mod stream;
 mod synthesizer;
 mod tokenizer;
 mod transcription;
 mod voice;
+mod watermark;
 
 use {
     bincode::{config::standard, decode_from_slice},
     ort::{execution_providers::CUDAExecutionProvider, session::Session},
-    std::{collections::HashMap, path::Path, sync::Arc, time::Duration},
+    std::{
+        collections::HashMap,
+        path::Path,
+        sync::{Arc, Weak},
+        time::Duration,
+    },
+    synthesizer::Input,
     tokio::{fs::read, sync::Mutex},
 };
-pub use {error::*, g2p::*, stream::*, tokenizer::*, transcription::*, voice::*};
+pub use {
+    error::*, frame::*, g2p::*, output::*, pitch::*, ring::*, rtp::*, stream::*, tokenizer::*,
+    transcription::*, voice::*, watermark::*,
+};
+
+/// 模型输出音频的采样率
+pub const SAMPLE_RATE: u32 = 24000;
 
 pub struct KokoroTts {
     model: Arc<Mutex<Session>>,
     voices: Arc<HashMap<String, Vec<Vec<Vec<f32>>>>>,
+    phonemizers: Arc<PhonemizerChain>,
 }
 
 impl KokoroTts {
@@ -30,6 +58,7 @@ impl KokoroTts {
         Ok(Self {
             model: Arc::new(model.into()),
             voices,
+            phonemizers: Default::default(),
         })
     }
 
@@ -45,10 +74,304 @@ impl KokoroTts {
         Ok(Self {
             model: Arc::new(model.into()),
             voices,
+            phonemizers: Default::default(),
         })
     }
 
+    /// 为一种文字注册自定义的音素化前端
+    ///
+    /// 注册的前端会替换v1.0和v1.1模型对应的内置前端，之后的所有合成（包括流式合成）都会使用它。
+    ///
+    /// # 参数
+    ///
+    /// * `script` - 文字类别。
+    /// * `phonemizer` - 音素化前端。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{G2PError, KokoroTts, Phonemizer, Script, Voice};
+    ///
+    /// struct Spell;
+    ///
+    /// impl Phonemizer for Spell {
+    ///     fn phonemize(&self, text: &str) -> Result<String, G2PError> {
+    ///         Ok(text.to_lowercase())
+    ///     }
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let tts = tts.with_phonemizer(Script::Latin, Spell);
+    ///     let _ = tts.synth("kokoro", Voice::AfBella(1.0)).await;
+    /// }
+    /// ```
+    ///
+    pub fn with_phonemizer<P>(mut self, script: Script, phonemizer: P) -> Self
+    where
+        P: Phonemizer + 'static,
+    {
+        Arc::make_mut(&mut self.phonemizers).register(script, phonemizer);
+        self
+    }
+
+    /// 设置用户发音词典
+    ///
+    /// 词典中的词条会在音素化前端之前查找，用于纠正产品名、姓氏和术语等的发音。
+    ///
+    /// # 参数
+    ///
+    /// * `lexicon` - 用户词典。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Lexicon, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let Ok(lexicon) = Lexicon::load("../lexicon.pls").await else {
+    ///         return;
+    ///     };
+    ///     let tts = tts.with_lexicon(lexicon);
+    ///     let _ = tts.synth("Kokoro", Voice::AfBella(1.0)).await;
+    /// }
+    /// ```
+    ///
+    pub fn with_lexicon(mut self, lexicon: Lexicon) -> Self {
+        Arc::make_mut(&mut self.phonemizers).set_lexicon(lexicon);
+        self
+    }
+
+    /// 设置汉语文本规范化输出的字形
+    ///
+    /// 数字、日期、单位等会先展开为汉字再转换为音标，该设置决定展开时使用简体还是繁体，默认为简体。
+    ///
+    /// # 参数
+    ///
+    /// * `variant` - 字形。
+    pub fn with_chinese_variant(mut self, variant: ChineseVariant) -> Self {
+        Arc::make_mut(&mut self.phonemizers).set_chinese_variant(variant);
+        self
+    }
+
+    /// 设置英语单词有多个发音时的选择方式
+    ///
+    /// 默认总是使用词典中的第一个发音，同一段文本每次合成的读音都相同。只对内置的英语前端有效。
+    ///
+    /// # 参数
+    ///
+    /// * `selection` - 选择方式，`PronunciationSelection::Seeded`在种子相同时结果相同。
+    pub fn with_pronunciation_selection(mut self, selection: PronunciationSelection) -> Self {
+        Arc::make_mut(&mut self.phonemizers).set_pronunciation_selection(selection);
+        self
+    }
+
+    /// 设置表情符号的读法
+    ///
+    /// 默认读出表情符号的名称，汉语语境读汉语名称，例如👍读作“拇指向上”，其他语境读英语名称。
+    ///
+    /// # 参数
+    ///
+    /// * `emoji` - 读法。
+    pub fn with_emoji_reading(mut self, emoji: EmojiReading) -> Self {
+        Arc::make_mut(&mut self.phonemizers).set_emoji_reading(emoji);
+        self
+    }
+
+    /// 设置没有注册前端的文本的处理方式
+    ///
+    /// 内置前端不支持的文字（例如希腊字母、西里尔字母、韩文）和表情等符号默认跳过并记录警告日志。
+    ///
+    /// # 参数
+    ///
+    /// * `fallback` - 处理方式。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{Fallback, KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let tts = tts.with_fallback(Fallback::Spell);
+    ///     let _ = tts.synth("I live in Москва.", Voice::AfBella(1.0)).await;
+    /// }
+    /// ```
+    ///
+    pub fn with_fallback(mut self, fallback: Fallback) -> Self {
+        Arc::make_mut(&mut self.phonemizers).set_fallback(fallback);
+        self
+    }
+
+    /// 替换某种英语口音的词典
+    ///
+    /// 美式语音（例如`af_`、`am_`）使用美式词典，英式语音（`bf_`、`bm_`）使用英式词典，默认使用`EnglishDialect::lexicon`。只对内置的英语前端有效。
+    ///
+    /// # 参数
+    ///
+    /// * `dialect` - 口音。
+    /// * `lexicon` - 口音词典，音标应使用对应口音的misaki音标。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{EnglishDialect, KokoroTts, Pronunciation, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let mut lexicon = EnglishDialect::British.lexicon();
+    ///     lexicon.insert("scone", Pronunciation::Ipa("skˈɒn".into()));
+    ///     let tts = tts.with_english_lexicon(EnglishDialect::British, lexicon);
+    ///     let _ = tts.synth("A scone, please.", Voice::BfEmma(1.0)).await;
+    /// }
+    /// ```
+    ///
+    pub fn with_english_lexicon(mut self, dialect: EnglishDialect, lexicon: Lexicon) -> Self {
+        Arc::make_mut(&mut self.phonemizers).set_english_lexicon(dialect, lexicon);
+        self
+    }
+
+    /// 设置英语缩略词的读法
+    ///
+    /// 默认把全部大写的缩略词逐个字母读出，大小写混合的句子中表示强调的大写单词（VERY）按普通单词读出，内置列表中的缩略词（例如NASA、NATO）按单词读出。只对内置的英语前端有效。
+    ///
+    /// # 参数
+    ///
+    /// * `acronyms` - 缩略词的读法。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{AcronymReading, Acronyms, KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let mut acronyms = Acronyms::new();
+    ///     acronyms.insert("SIGGRAPH", AcronymReading::Word);
+    ///     let tts = tts.with_acronyms(acronyms);
+    ///     let _ = tts.synth("See you at SIGGRAPH.", Voice::AfBella(1.0)).await;
+    /// }
+    /// ```
+    ///
+    pub fn with_acronyms(mut self, acronyms: Acronyms) -> Self {
+        Arc::make_mut(&mut self.phonemizers).set_acronyms(acronyms);
+        self
+    }
+
+    /// 分析文本在合成前的音素化过程
+    ///
+    /// 使用与`synth`相同的前端和词典，返回文本的切分结果、每个单词的音标及其来源、最终的词元，以及被丢弃或无法转换的字符，用于排查发音问题。
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 要分析的文本。
+    /// * `voice` - 语音，用于选择模型版本和语言。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     if let Ok(analysis) = tts.analyze("你好，世界", Voice::ZfXiaoxiao(1.0)) {
+    ///         for i in analysis.dropped {
+    ///             println!("{:?} at {}: {:?}", i.character, i.offset, i.reason);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub fn analyze(&self, text: &str, voice: Voice) -> Result<Analysis, KokoroError> {
+        let mut chain = PhonemizerChain::new(voice.is_v11_supported());
+        chain.set_language(voice.language());
+        chain.extend(&self.phonemizers);
+        Ok(chain.analyze(text)?)
+    }
+
     pub async fn synth<S>(&self, text: S, voice: Voice) -> Result<(Vec<f32>, Duration), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        self.synth_input(Input::Text(text), voice).await
+    }
+
+    /// 跳过音素化前端，直接从音标合成语音
+    ///
+    /// 适合调试或手动调整发音。音标中的每个符号都必须在对应版本模型的词表中，否则返回`KokoroError::UnknownPhoneme`；v1.0模型的音标过长时返回`KokoroError::TooManyTokens`。英语音标应使用与语音口音一致的misaki音标，例如美式英语的`O`（oʊ）和`ɹ`。
+    ///
+    /// # 参数
+    ///
+    /// * `phonemes` - Kokoro使用的音标，可以先通过`g2p`获得再手动修改。
+    /// * `voice` - 语音。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let _ = tts.synth_phonemes("həlˈO wˈɜɹld", Voice::AfBella(1.0)).await;
+    /// }
+    /// ```
+    ///
+    pub async fn synth_phonemes<S>(
+        &self,
+        phonemes: S,
+        voice: Voice,
+    ) -> Result<(Vec<f32>, Duration), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        let tokens = try_get_token_ids(phonemes.as_ref(), voice.is_v11_supported())?;
+        self.synth_input(Input::<&str>::Tokens(tokens), voice).await
+    }
+
+    /// 跳过音素化前端，直接从词元合成语音
+    ///
+    /// 每个词元都必须在对应版本模型的词表中，否则返回`KokoroError::InvalidToken`；v1.0模型的词元过多时返回`KokoroError::TooManyTokens`。首尾的填充符会自动加上，不需要包含在`tokens`中。
+    ///
+    /// # 参数
+    ///
+    /// * `tokens` - 词元。
+    /// * `voice` - 语音。
+    pub async fn synth_tokens(
+        &self,
+        tokens: &[i64],
+        voice: Voice,
+    ) -> Result<(Vec<f32>, Duration), KokoroError> {
+        let tokens = check_token_ids(tokens, voice.is_v11_supported())?;
+        self.synth_input(Input::<&str>::Tokens(tokens), voice).await
+    }
+
+    async fn synth_input<S>(
+        &self,
+        input: Input<S>,
+        voice: Voice,
+    ) -> Result<(Vec<f32>, Duration), KokoroError>
     where
         S: AsRef<str>,
     {
@@ -57,7 +380,112 @@ impl KokoroTts {
             .voices
             .get(name)
             .ok_or(KokoroError::VoiceNotFound(name.to_owned()))?;
-        synthesizer::synth(Arc::downgrade(&self.model), text, pack, voice).await
+        synthesizer::synth(
+            Arc::downgrade(&self.model),
+            &self.phonemizers,
+            input,
+            pack,
+            voice,
+        )
+        .await
+    }
+
+    /// 合成语音并直接写入调用方提供的缓冲区
+    ///
+    /// 模型输出直接从推理结果复制到`buffer`中，不分配中间的`Vec`。缓冲区放不下的样本会被丢弃，并在返回值中报告。
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 要合成的文本内容。
+    /// * `voice` - 语音。
+    /// * `buffer` - 接收音频的缓冲区。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let mut buffer = vec![0.; 24000 * 10];
+    ///     if let Ok(report) = tts.synth_into("你好", Voice::ZfXiaoxiao(1.0), &mut buffer).await {
+    ///         let _audio = &buffer[..report.position];
+    ///     }
+    /// }
+    /// ```
+    ///
+    pub async fn synth_into<S>(
+        &self,
+        text: S,
+        voice: Voice,
+        buffer: &mut [f32],
+    ) -> Result<WriteReport, KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        let name = voice.get_name();
+        let pack = self
+            .voices
+            .get(name)
+            .ok_or(KokoroError::VoiceNotFound(name.to_owned()))?;
+        let mut sink = SliceSink {
+            buffer,
+            position: 0,
+            overflow: 0,
+        };
+        let took = synthesizer::synth_with(
+            Arc::downgrade(&self.model),
+            &self.phonemizers,
+            Input::Text(text),
+            pack,
+            voice,
+            &mut sink,
+        )
+        .await?;
+
+        Ok(WriteReport {
+            position: sink.position,
+            overflow: sink.overflow,
+            underruns: 0,
+            took,
+        })
+    }
+
+    /// 合成语音并写入环形缓冲区
+    ///
+    /// 模型输出直接写入`producer`，缓冲区已满时会等待消费端读取，不会丢弃样本。
+    ///
+    /// # 参数
+    ///
+    /// * `text` - 要合成的文本内容。
+    /// * `voice` - 语音。
+    /// * `producer` - 环形缓冲区的生产端。
+    pub async fn synth_to_ring<S>(
+        &self,
+        text: S,
+        voice: Voice,
+        producer: &mut RingProducer,
+    ) -> Result<WriteReport, KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        let name = voice.get_name();
+        let pack = self
+            .voices
+            .get(name)
+            .ok_or(KokoroError::VoiceNotFound(name.to_owned()))?;
+        synth_to_ring(
+            Arc::downgrade(&self.model),
+            &self.phonemizers,
+            Input::Text(text),
+            pack,
+            voice,
+            producer,
+        )
+        .await
     }
 
     pub fn stream<S>(&self, voice: Voice) -> (SynthSink<S>, SynthStream)
@@ -66,18 +494,97 @@ impl KokoroTts {
     {
         let voices = Arc::downgrade(&self.voices);
         let model = Arc::downgrade(&self.model);
+        let phonemizers = self.phonemizers.clone();
 
-        start_synth_session(voice, move |text, voice| {
+        start_synth_session(voice, move |input, voice| {
             let voices = voices.clone();
             let model = model.clone();
+            let phonemizers = phonemizers.clone();
             async move {
                 let name = voice.get_name();
                 let voices = voices.upgrade().ok_or(KokoroError::ModelReleased)?;
                 let pack = voices
                     .get(name)
                     .ok_or(KokoroError::VoiceNotFound(name.to_owned()))?;
-                synthesizer::synth(model, text, pack, voice).await
+                synthesizer::synth(model, &phonemizers, input, pack, voice).await
             }
         })
     }
+
+    /// 创建写入环形缓冲区的流式合成会话
+    ///
+    /// 与`stream`类似，但合成结果不经过`SynthStream`，而是直接写入`producer`，适合在音频回调中通过`RingConsumer`读取。缓冲区有空间时样本直接从推理结果写入，只有缓冲区已满需要等待时才复制剩余的样本。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice, audio_ring};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (producer, mut consumer) = audio_ring(24000);
+    ///     let mut sink = tts.stream_to_ring(Voice::ZfXiaoxiao(1.0), producer);
+    ///     let _ = sink.synth("你好").await;
+    ///     // 在音频回调中：
+    ///     let mut out = [0.; 480];
+    ///     consumer.read(&mut out);
+    /// }
+    /// ```
+    ///
+    pub fn stream_to_ring<S>(&self, voice: Voice, producer: RingProducer) -> SynthSink<S>
+    where
+        S: AsRef<str> + Send + 'static,
+    {
+        let voices = Arc::downgrade(&self.voices);
+        let model = Arc::downgrade(&self.model);
+        let phonemizers = self.phonemizers.clone();
+        let producer = Arc::new(Mutex::new(producer));
+
+        start_session(voice, move |input, voice| {
+            let voices = voices.clone();
+            let model = model.clone();
+            let phonemizers = phonemizers.clone();
+            let producer = producer.clone();
+            async move {
+                let name = voice.get_name();
+                let voices = voices.upgrade().ok_or(KokoroError::ModelReleased)?;
+                let pack = voices
+                    .get(name)
+                    .ok_or(KokoroError::VoiceNotFound(name.to_owned()))?;
+                let mut producer = producer.lock().await;
+                synth_to_ring(model, &phonemizers, input, pack, voice, &mut producer).await?;
+                Ok(())
+            }
+        })
+    }
+}
+
+async fn synth_to_ring<P, S>(
+    model: Weak<Mutex<Session>>,
+    phonemizers: &PhonemizerChain,
+    input: Input<S>,
+    pack: P,
+    voice: Voice,
+    producer: &mut RingProducer,
+) -> Result<WriteReport, KokoroError>
+where
+    P: AsRef<Vec<Vec<Vec<f32>>>>,
+    S: AsRef<str>,
+{
+    producer.set_active(true);
+    // 每段音频写入后才开始下一段推理，缓冲区已满时在这里等待消费端
+    let took =
+        synthesizer::synth_with(model, phonemizers, input, pack, voice, &mut *producer).await;
+    producer.set_active(false);
+    let took = took?;
+
+    Ok(WriteReport {
+        position: producer.position(),
+        overflow: 0,
+        underruns: producer.underruns(),
+        took,
+    })
 }
@@ -0,0 +1,394 @@
+use {
+    crate::{KokoroError, SAMPLE_RATE},
+    futures::{Stream, StreamExt},
+    std::{path::Path, pin::pin, time::Duration},
+    tokio::{
+        fs::File,
+        io::{AsyncSeekExt, AsyncWrite, AsyncWriteExt, SeekFrom, Stdout, stdout},
+        time::{Instant, sleep_until},
+    },
+};
+
+/// 音频输出
+///
+/// 该trait描述了合成结果的去向，例如文件、管道、内存或者声卡。
+pub trait AudioOutput {
+    /// 写入一段音频
+    fn write(&mut self, audio: &[f32]) -> impl Future<Output = Result<(), KokoroError>>;
+
+    /// 结束输出，写入文件尾或等待缓冲区清空
+    fn finish(&mut self) -> impl Future<Output = Result<(), KokoroError>> {
+        async { Ok(()) }
+    }
+
+    /// 是否按实时速率消费音频
+    ///
+    /// 实时输出（例如声卡）在写入后需要一段时间才能播放完毕，`play_stream`会据此等待播放位置追上写入位置。
+    fn is_realtime(&self) -> bool {
+        false
+    }
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1., 1.) * i16::MAX as f32) as i16
+}
+
+/// WAV文件最多能保存的样本数，RIFF的长度字段是32位的，包括文件头中的36字节
+const MAX_WAV_SAMPLES: u32 = (u32::MAX - 36) / 2;
+
+/// WAV文件输出
+///
+/// 以16位单声道PCM格式写入，`finish`时回填文件头中的长度字段。RIFF格式的文件不能超过4 GiB，约24.8小时的音频，超出时`write`返回`KokoroError::WavTooLarge`。
+pub struct WavOutput {
+    file: File,
+    samples: u32,
+}
+
+impl WavOutput {
+    /// 创建WAV文件
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径，已存在的文件会被覆盖。
+    pub async fn create<P: AsRef<Path>>(path: P) -> Result<Self, KokoroError> {
+        let mut file = File::create(path).await?;
+        file.write_all(&Self::header(0)).await?;
+        Ok(Self { file, samples: 0 })
+    }
+
+    fn header(samples: u32) -> Vec<u8> {
+        let data_len = samples * 2;
+        let mut header = Vec::with_capacity(44);
+        header.extend(b"RIFF");
+        header.extend((36 + data_len).to_le_bytes());
+        header.extend(b"WAVEfmt ");
+        header.extend(16u32.to_le_bytes());
+        // PCM，单声道
+        header.extend(1u16.to_le_bytes());
+        header.extend(1u16.to_le_bytes());
+        header.extend(SAMPLE_RATE.to_le_bytes());
+        header.extend((SAMPLE_RATE * 2).to_le_bytes());
+        header.extend(2u16.to_le_bytes());
+        header.extend(16u16.to_le_bytes());
+        header.extend(b"data");
+        header.extend(data_len.to_le_bytes());
+        header
+    }
+}
+
+impl AudioOutput for WavOutput {
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        let samples = u32::try_from(audio.len())
+            .ok()
+            .and_then(|i| self.samples.checked_add(i))
+            .filter(|i| *i <= MAX_WAV_SAMPLES)
+            .ok_or(KokoroError::WavTooLarge)?;
+        let data = audio
+            .iter()
+            .flat_map(|i| to_i16(*i).to_le_bytes())
+            .collect::<Vec<_>>();
+        self.file.write_all(&data).await?;
+        self.samples = samples;
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> Result<(), KokoroError> {
+        self.file.seek(SeekFrom::Start(0)).await?;
+        self.file.write_all(&Self::header(self.samples)).await?;
+        self.file.seek(SeekFrom::End(0)).await?;
+        self.file.flush().await?;
+        Ok(())
+    }
+}
+
+/// 原始PCM的样本格式
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum PcmFormat {
+    /// 32位小端浮点
+    #[default]
+    F32Le,
+    /// 16位小端整数
+    S16Le,
+}
+
+/// 原始PCM输出
+///
+/// 把样本按指定格式写入任意异步写入端，例如标准输出，便于通过管道交给其他程序处理。
+pub struct PcmOutput<W> {
+    writer: W,
+    format: PcmFormat,
+}
+
+impl<W> PcmOutput<W> {
+    /// 创建原始PCM输出
+    ///
+    /// # 参数
+    ///
+    /// * `writer` - 异步写入端。
+    /// * `format` - 样本格式。
+    pub fn new(writer: W, format: PcmFormat) -> Self {
+        Self { writer, format }
+    }
+
+    /// 取回写入端
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl PcmOutput<Stdout> {
+    /// 写入标准输出
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, PcmFormat, PcmOutput, Voice, play_stream};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (mut sink, stream) = tts.stream(Voice::ZfXiaoxiao(1.0));
+    ///     let _ = sink.synth("你好").await;
+    ///     drop(sink);
+    ///     // 例如：cargo run | ffplay -f f32le -ar 24000 -ac 1 -
+    ///     let mut output = PcmOutput::stdout(PcmFormat::F32Le);
+    ///     let _ = play_stream(stream, &mut output, |_| ()).await;
+    /// }
+    /// ```
+    ///
+    pub fn stdout(format: PcmFormat) -> Self {
+        Self::new(stdout(), format)
+    }
+}
+
+impl<W> AudioOutput for PcmOutput<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        let data = match self.format {
+            PcmFormat::F32Le => audio.iter().flat_map(|i| i.to_le_bytes()).collect(),
+            PcmFormat::S16Le => audio
+                .iter()
+                .flat_map(|i| to_i16(*i).to_le_bytes())
+                .collect::<Vec<_>>(),
+        };
+        self.writer.write_all(&data).await?;
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> Result<(), KokoroError> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
+
+/// 内存输出
+///
+/// 把所有样本收集到内存中。
+#[derive(Clone, Debug, Default)]
+pub struct MemoryOutput {
+    samples: Vec<f32>,
+}
+
+impl MemoryOutput {
+    /// 创建内存输出
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 已收集的样本
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    /// 取回已收集的样本
+    pub fn into_inner(self) -> Vec<f32> {
+        self.samples
+    }
+}
+
+impl AudioOutput for MemoryOutput {
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        self.samples.extend_from_slice(audio);
+        Ok(())
+    }
+}
+
+/// 空输出
+///
+/// 丢弃所有样本，只记录样本数，适合测试或性能评估。
+#[derive(Clone, Debug, Default)]
+pub struct NullOutput {
+    samples: u64,
+}
+
+impl NullOutput {
+    /// 创建空输出
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 已丢弃的样本数
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+}
+
+impl AudioOutput for NullOutput {
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        self.samples += audio.len() as u64;
+        Ok(())
+    }
+}
+
+/// 声卡播放输出
+///
+/// 通过系统默认的音频设备播放，需要启用`playback`特性。
+#[cfg(feature = "playback")]
+pub struct PlaybackOutput {
+    player: voxudio::AudioPlayer,
+}
+
+#[cfg(feature = "playback")]
+impl PlaybackOutput {
+    /// 打开默认的音频设备并开始播放
+    pub fn new() -> Result<Self, KokoroError> {
+        let mut player =
+            voxudio::AudioPlayer::new().map_err(|e| KokoroError::Playback(e.to_string()))?;
+        player
+            .play()
+            .map_err(|e| KokoroError::Playback(e.to_string()))?;
+        Ok(Self { player })
+    }
+}
+
+#[cfg(feature = "playback")]
+impl AudioOutput for PlaybackOutput {
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        self.player
+            .write::<24000>(audio, 1)
+            .await
+            .map_err(|e| KokoroError::Playback(e.to_string()))
+    }
+
+    fn is_realtime(&self) -> bool {
+        true
+    }
+}
+
+/// 把语音合成流写入音频输出
+///
+/// 该函数依次把每段合成结果写入输出，并通过`progress`报告每段音频开始播放时的播放位置。对于实时输出，函数会等待播放位置追上写入位置，因此返回时音频已经播放完毕。
+///
+/// # 参数
+///
+/// * `stream` - 语音合成流或其他产生音频的流。
+/// * `output` - 音频输出。
+/// * `progress` - 播放位置回调。
+///
+/// # 返回值
+///
+/// 返回输出的音频总时长。
+pub async fn play_stream<S, O, F>(
+    stream: S,
+    output: &mut O,
+    mut progress: F,
+) -> Result<Duration, KokoroError>
+where
+    S: Stream<Item = (Vec<f32>, Duration)>,
+    O: AudioOutput,
+    F: FnMut(Duration),
+{
+    let mut stream = pin!(stream);
+    let mut position = Duration::ZERO;
+    let mut start = None;
+    while let Some((audio, _)) = stream.next().await {
+        if output.is_realtime() {
+            // 第一段音频写入时开始计时，之后等待上一段播放到末尾附近再写入下一段
+            let start = *start.get_or_insert_with(Instant::now);
+            sleep_until(start + position.saturating_sub(Duration::from_millis(200))).await;
+        }
+        progress(position);
+        output.write(&audio).await?;
+        position += Duration::from_secs_f64(audio.len() as f64 / SAMPLE_RATE as f64);
+    }
+
+    output.finish().await?;
+    if let Some(start) = start {
+        sleep_until(start + position).await;
+    }
+
+    Ok(position)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, futures::stream::iter};
+
+    #[tokio::test]
+    async fn test_play_stream() -> Result<(), KokoroError> {
+        let items = vec![
+            (vec![0.5; SAMPLE_RATE as usize / 2], Duration::ZERO),
+            (vec![-0.5; SAMPLE_RATE as usize], Duration::ZERO),
+        ];
+
+        let mut output = MemoryOutput::new();
+        let mut positions = Vec::new();
+        let duration = play_stream(iter(items.clone()), &mut output, |p| positions.push(p)).await?;
+        assert_eq!(Duration::from_millis(1500), duration);
+        assert_eq!(vec![Duration::ZERO, Duration::from_millis(500)], positions);
+        assert_eq!(36000, output.samples().len());
+
+        let mut output = NullOutput::new();
+        play_stream(iter(items.clone()), &mut output, |_| ()).await?;
+        assert_eq!(36000, output.samples());
+
+        let mut output = PcmOutput::new(Vec::new(), PcmFormat::S16Le);
+        play_stream(iter(items.clone()), &mut output, |_| ()).await?;
+        let data = output.into_inner();
+        assert_eq!(72000, data.len());
+        assert_eq!(to_i16(0.5).to_le_bytes(), data[..2]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wav_output() -> Result<(), KokoroError> {
+        let path = std::env::temp_dir().join("kokoro_test_wav_output.wav");
+        let mut output = WavOutput::create(&path).await?;
+        output.write(&[0.; 100]).await?;
+        output.write(&[1.; 50]).await?;
+        output.finish().await?;
+
+        let data = tokio::fs::read(&path).await?;
+        let _ = tokio::fs::remove_file(&path).await;
+        assert_eq!(44 + 300, data.len());
+        assert_eq!(b"RIFF", &data[..4]);
+        assert_eq!(336u32.to_le_bytes(), data[4..8]);
+        assert_eq!(300u32.to_le_bytes(), data[40..44]);
+        assert_eq!(i16::MAX.to_le_bytes(), data[44 + 200..44 + 202]);
+
+        // 超出RIFF的长度限制时返回错误，不写入任何数据
+        let mut output = WavOutput::create(&path).await?;
+        output.samples = MAX_WAV_SAMPLES - 10;
+        assert!(matches!(
+            output.write(&[0.; 20]).await,
+            Err(KokoroError::WavTooLarge)
+        ));
+        output.write(&[0.; 10]).await?;
+        assert!(matches!(
+            output.write(&[0.]).await,
+            Err(KokoroError::WavTooLarge)
+        ));
+        output.finish().await?;
+        let data = tokio::fs::read(&path).await?;
+        let _ = tokio::fs::remove_file(&path).await;
+        assert_eq!(44 + 20, data.len());
+        assert_eq!((u32::MAX - 1).to_le_bytes(), data[4..8]);
+
+        Ok(())
+    }
+}
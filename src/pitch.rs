@@ -0,0 +1,416 @@
+use {
+    crate::SAMPLE_RATE,
+    futures::Stream,
+    pin_project::pin_project,
+    std::{
+        f32::consts::PI,
+        pin::Pin,
+        task::{Context, Poll},
+        time::Duration,
+    },
+};
+
+/// WSOLA的帧长（40毫秒）
+const FRAME_LEN: usize = SAMPLE_RATE as usize / 25;
+/// 合成跳长，帧之间重叠一半
+const SYNTHESIS_HOP: usize = FRAME_LEN / 2;
+/// 波形相似度的搜索范围（±10毫秒）
+const SEARCH_RANGE: usize = SAMPLE_RATE as usize / 100;
+
+/// 流式线性重采样，按`ratio`的步长读取输入
+struct Resampler {
+    ratio: f64,
+    history: Vec<f32>,
+    // history[0]在输入中的绝对位置
+    offset: usize,
+    // 已输出的样本数，用整数计数保证分块处理与整体处理结果一致
+    produced: u64,
+}
+
+impl Resampler {
+    fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            history: Vec::new(),
+            offset: 0,
+            produced: 0,
+        }
+    }
+
+    fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        self.history.extend_from_slice(input);
+        loop {
+            let pos = self.produced as f64 * self.ratio - self.offset as f64;
+            let i = pos as usize;
+            if i + 1 >= self.history.len() {
+                break;
+            }
+            let frac = (pos - i as f64) as f32;
+            out.push(self.history[i] * (1. - frac) + self.history[i + 1] * frac);
+            self.produced += 1;
+        }
+
+        let pos = (self.produced as f64 * self.ratio) as usize;
+        let consumed = pos.saturating_sub(self.offset).min(self.history.len());
+        self.history.drain(..consumed);
+        self.offset += consumed;
+    }
+
+    fn flush(&mut self, out: &mut Vec<f32>) {
+        // 补一个零，让最后一个样本也能被插值
+        self.process(&[0.], out);
+    }
+}
+
+/// 流式WSOLA（波形相似重叠相加）时间伸缩，适合语音且不改变音高
+struct Wsola {
+    // 输出长度与输入长度之比
+    stretch: f64,
+    input: Vec<f32>,
+    // input[0]在输入中的绝对位置
+    offset: usize,
+    // 已经收到的输入样本数
+    received: usize,
+    // 已经合成的帧数
+    frames: usize,
+    // 上一帧实际选取的绝对位置
+    prev: usize,
+    // 尚未完成重叠相加的后半帧
+    overlap: Vec<f32>,
+    window: Vec<f32>,
+}
+
+impl Wsola {
+    fn new(stretch: f64) -> Self {
+        // 周期汉宁窗，半帧重叠相加之和恒为1
+        let window = (0..FRAME_LEN)
+            .map(|i| 0.5 - 0.5 * (2. * PI * i as f32 / FRAME_LEN as f32).cos())
+            .collect();
+
+        Self {
+            stretch,
+            input: Vec::new(),
+            offset: 0,
+            received: 0,
+            frames: 0,
+            prev: 0,
+            overlap: vec![0.; SYNTHESIS_HOP],
+            window,
+        }
+    }
+
+    fn nominal(&self, frame: usize) -> usize {
+        (frame as f64 * SYNTHESIS_HOP as f64 / self.stretch).round() as usize
+    }
+
+    fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        self.input.extend_from_slice(input);
+        self.received += input.len();
+        self.synthesize(out);
+    }
+
+    fn synthesize(&mut self, out: &mut Vec<f32>) {
+        let end = self.offset + self.input.len();
+        while self.nominal(self.frames) + SEARCH_RANGE + FRAME_LEN <= end {
+            let pos = self.seek(self.nominal(self.frames));
+            let frame = &self.input[pos - self.offset..pos - self.offset + FRAME_LEN];
+            let (head, tail) = self.window.split_at(SYNTHESIS_HOP);
+            out.extend(
+                self.overlap
+                    .iter()
+                    .zip(frame.iter().zip(head))
+                    .map(|(o, (s, w))| o + s * w),
+            );
+            for (o, (s, w)) in self
+                .overlap
+                .iter_mut()
+                .zip(frame[SYNTHESIS_HOP..].iter().zip(tail))
+            {
+                *o = s * w;
+            }
+            self.prev = pos;
+            self.frames += 1;
+
+            // 丢弃之后不会再用到的输入
+            let keep = self
+                .nominal(self.frames)
+                .saturating_sub(SEARCH_RANGE)
+                .min(self.prev + SYNTHESIS_HOP);
+            let drop = keep.saturating_sub(self.offset).min(self.input.len());
+            self.input.drain(..drop);
+            self.offset += drop;
+        }
+    }
+
+    /// 在名义位置附近寻找与上一帧的自然延续最相似的位置
+    fn seek(&self, nominal: usize) -> usize {
+        if self.frames == 0 {
+            return nominal;
+        }
+
+        let target = self.prev + SYNTHESIS_HOP;
+        let target = &self.input[target - self.offset..target - self.offset + SYNTHESIS_HOP];
+        let lower = nominal.saturating_sub(SEARCH_RANGE).max(self.offset);
+        let mut best = (nominal, f32::MIN);
+        for pos in lower..=nominal + SEARCH_RANGE {
+            let candidate = &self.input[pos - self.offset..pos - self.offset + SYNTHESIS_HOP];
+            let score = candidate
+                .iter()
+                .zip(target)
+                .map(|(a, b)| a * b)
+                .sum::<f32>();
+            if score > best.1 {
+                best = (pos, score);
+            }
+        }
+
+        best.0
+    }
+
+    fn flush(&mut self, out: &mut Vec<f32>) {
+        // 补零直到所有覆盖真实输入的帧都合成完毕
+        while self.nominal(self.frames) < self.received {
+            let need = self.nominal(self.frames) + SEARCH_RANGE + FRAME_LEN;
+            let end = self.offset + self.input.len();
+            self.input
+                .resize(self.input.len() + need.saturating_sub(end), 0.);
+            self.synthesize(out);
+        }
+        out.extend_from_slice(&self.overlap);
+    }
+}
+
+/// 变调器
+///
+/// 该结构体用于在不改变语速的情况下调整音高。它先通过重采样改变音高，再用WSOLA把时长还原，可以对完整的音频或任意切分的音频块进行处理，块与块之间的状态是连续的，不会产生边界杂音。
+pub struct PitchShifter {
+    semitones: f32,
+    resampler: Resampler,
+    wsola: Wsola,
+    bypass: bool,
+    // 输出开头需要丢弃的延迟样本数
+    latency: usize,
+    skipped: usize,
+    received: usize,
+    emitted: usize,
+}
+
+impl PitchShifter {
+    /// 创建变调器
+    ///
+    /// # 参数
+    ///
+    /// * `semitones` - 音高偏移的半音数，正数升调，负数降调，建议在±12以内。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::PitchShifter;
+    ///
+    /// let mut shifter = PitchShifter::new(2.);
+    /// let mut audio = shifter.process(&[0.; 4800]);
+    /// audio.extend(shifter.flush());
+    /// assert_eq!(audio.len(), 4800);
+    /// ```
+    ///
+    pub fn new(semitones: f32) -> Self {
+        let factor = 2f64.powf(semitones as f64 / 12.);
+        let mut wsola = Wsola::new(factor);
+        // 预先填充半帧静音，避免开头被窗函数削弱
+        wsola.process(&[0.; SYNTHESIS_HOP], &mut Vec::new());
+
+        Self {
+            semitones,
+            resampler: Resampler::new(factor),
+            wsola,
+            bypass: semitones == 0.,
+            latency: (SYNTHESIS_HOP as f64 * factor).round() as usize,
+            skipped: 0,
+            received: 0,
+            emitted: 0,
+        }
+    }
+
+    /// 处理一块音频
+    ///
+    /// 由于算法需要向后看若干样本，返回的数据可能比输入短，剩余的部分会在后续调用或`flush`中输出。
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.bypass {
+            return input.to_vec();
+        }
+
+        self.received += input.len();
+        let mut resampled = Vec::with_capacity(input.len());
+        self.resampler.process(input, &mut resampled);
+        let mut out = Vec::with_capacity(input.len());
+        self.wsola.process(&resampled, &mut out);
+        self.emit(out)
+    }
+
+    /// 输出所有剩余的音频并重置状态
+    ///
+    /// 处理完成后，输出的总长度与输入的总长度完全相同。
+    pub fn flush(&mut self) -> Vec<f32> {
+        if self.bypass {
+            return Vec::new();
+        }
+
+        let mut resampled = Vec::new();
+        self.resampler.flush(&mut resampled);
+        let mut out = Vec::new();
+        self.wsola.process(&resampled, &mut out);
+        self.wsola.flush(&mut out);
+        let mut out = self.emit(out);
+        out.resize(out.len() + self.received - self.emitted, 0.);
+
+        *self = Self::new(self.semitones);
+        out
+    }
+
+    fn emit(&mut self, mut out: Vec<f32>) -> Vec<f32> {
+        let skip = (self.latency - self.skipped).min(out.len());
+        out.drain(..skip);
+        self.skipped += skip;
+        out.truncate(self.received - self.emitted);
+        self.emitted += out.len();
+        out
+    }
+}
+
+/// 对一段完整的音频进行变调，输出长度与输入相同
+///
+/// # 参数
+///
+/// * `audio` - 要处理的音频数据。
+/// * `semitones` - 音高偏移的半音数。
+pub fn pitch_shift(audio: &[f32], semitones: f32) -> Vec<f32> {
+    let mut shifter = PitchShifter::new(semitones);
+    let mut out = shifter.process(audio);
+    out.extend(shifter.flush());
+    out
+}
+
+/// 变调流
+///
+/// 该结构体包装一个语音合成流，对合成结果进行变调。所有结果共用一个变调器，段与段之间的状态是连续的；由于算法需要向后看，每段输出会略有延迟，剩余的音频在合成流结束后作为最后一段输出。
+#[pin_project]
+pub struct PitchShiftStream<S> {
+    #[pin]
+    inner: S,
+    shifter: PitchShifter,
+    finished: bool,
+}
+
+impl<S> PitchShiftStream<S> {
+    pub(super) fn new(inner: S, semitones: f32) -> Self {
+        Self {
+            inner,
+            shifter: PitchShifter::new(semitones),
+            finished: false,
+        }
+    }
+}
+
+impl<S> Stream for PitchShiftStream<S>
+where
+    S: Stream<Item = (Vec<f32>, Duration)>,
+{
+    type Item = (Vec<f32>, Duration);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.finished {
+            return Poll::Ready(None);
+        }
+
+        match this.inner.poll_next(cx) {
+            Poll::Ready(Some((data, took))) => {
+                Poll::Ready(Some((this.shifter.process(&data), took)))
+            }
+            Poll::Ready(None) => {
+                *this.finished = true;
+                let rest = this.shifter.flush();
+                if rest.is_empty() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Ready(Some((rest, Duration::ZERO)))
+                }
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        futures::{StreamExt, executor::block_on, stream::iter},
+    };
+
+    fn sine(freq: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2. * PI * freq * i as f32 / SAMPLE_RATE as f32).sin() * 0.5)
+            .collect()
+    }
+
+    fn zero_crossings(audio: &[f32]) -> usize {
+        audio
+            .windows(2)
+            .filter(|w| (w[0] < 0.) != (w[1] < 0.))
+            .count()
+    }
+
+    #[test]
+    fn test_pitch_shift_length() {
+        for semitones in [-7., -1., 0., 3., 12.] {
+            let audio = sine(220., 12345);
+            assert_eq!(audio.len(), pitch_shift(&audio, semitones).len());
+        }
+    }
+
+    #[test]
+    fn test_pitch_shift_frequency() {
+        let audio = sine(200., SAMPLE_RATE as usize);
+        let up = pitch_shift(&audio, 12.);
+        let down = pitch_shift(&audio, -12.);
+        // 只统计中间部分，避开首尾的过渡
+        let range = 2400..21600;
+        let base = zero_crossings(&audio[range.clone()]) as f32;
+        let ratio = zero_crossings(&up[range.clone()]) as f32 / base;
+        assert!((ratio - 2.).abs() < 0.1, "ratio: {}", ratio);
+        let ratio = zero_crossings(&down[range]) as f32 / base;
+        assert!((ratio - 0.5).abs() < 0.05, "ratio: {}", ratio);
+    }
+
+    #[test]
+    fn test_pitch_shift_chunks() {
+        let audio = sine(180., 20000);
+        let whole = pitch_shift(&audio, 4.);
+        let mut shifter = PitchShifter::new(4.);
+        let mut chunked = Vec::new();
+        for chunk in audio.chunks(777) {
+            chunked.extend(shifter.process(chunk));
+        }
+        chunked.extend(shifter.flush());
+        assert_eq!(whole, chunked);
+    }
+
+    #[test]
+    fn test_pitch_shift_stream() {
+        let audio = sine(180., 20000);
+        let whole = pitch_shift(&audio, -3.);
+        let items = audio
+            .chunks(6000)
+            .map(|chunk| (chunk.to_vec(), Duration::ZERO))
+            .collect::<Vec<_>>();
+        let streamed = block_on(PitchShiftStream::new(iter(items), -3.).collect::<Vec<_>>());
+        // 最后一段是结束时输出的剩余音频
+        assert_eq!(5, streamed.len());
+        let streamed = streamed
+            .into_iter()
+            .flat_map(|(data, _)| data)
+            .collect::<Vec<_>>();
+        assert_eq!(whole, streamed);
+    }
+}
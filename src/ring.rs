@@ -0,0 +1,316 @@
+use {
+    crate::{KokoroError, SAMPLE_RATE, synthesizer::AudioSink},
+    std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        },
+        time::Duration,
+    },
+    tokio::time::sleep,
+};
+
+struct RingShared {
+    // 以位模式存放的样本，使用原子类型避免不安全代码
+    data: Box<[AtomicU32]>,
+    mask: usize,
+    // 累计写入和读取的样本数，按容量取模得到下标
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    underruns: AtomicUsize,
+    // 生产端是否正在输出一段语音
+    active: AtomicBool,
+}
+
+impl RingShared {
+    fn len(&self) -> usize {
+        self.head
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.tail.load(Ordering::Acquire))
+    }
+}
+
+/// 创建单生产者单消费者的无锁音频环形缓冲区
+///
+/// 生产端由语音合成写入，消费端通常在音频回调中读取。读写都不会加锁或分配内存。
+///
+/// # 参数
+///
+/// * `capacity` - 缓冲区能容纳的样本数，会向上取整到2的幂。
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::audio_ring;
+///
+/// let (mut producer, mut consumer) = audio_ring(1024);
+/// assert_eq!(producer.write(&[0.5; 100]), 100);
+/// let mut out = [0.; 64];
+/// assert_eq!(consumer.read(&mut out), 64);
+/// assert_eq!(consumer.len(), 36);
+/// ```
+///
+pub fn audio_ring(capacity: usize) -> (RingProducer, RingConsumer) {
+    let capacity = capacity.max(1).next_power_of_two();
+    let shared = Arc::new(RingShared {
+        data: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+        mask: capacity - 1,
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+        underruns: AtomicUsize::new(0),
+        active: AtomicBool::new(false),
+    });
+
+    (
+        RingProducer {
+            shared: shared.clone(),
+        },
+        RingConsumer { shared },
+    )
+}
+
+/// 环形缓冲区的生产端
+pub struct RingProducer {
+    shared: Arc<RingShared>,
+}
+
+impl RingProducer {
+    /// 缓冲区能容纳的样本数
+    pub fn capacity(&self) -> usize {
+        self.shared.data.len()
+    }
+
+    /// 当前可写入的样本数
+    pub fn free_len(&self) -> usize {
+        self.capacity() - self.shared.len()
+    }
+
+    /// 累计写入的样本数，即当前的写入位置
+    pub fn position(&self) -> usize {
+        self.shared.head.load(Ordering::Acquire)
+    }
+
+    /// 消费端发生欠载的次数
+    pub fn underruns(&self) -> usize {
+        self.shared.underruns.load(Ordering::Acquire)
+    }
+
+    /// 写入尽可能多的样本，返回实际写入的样本数
+    pub fn write(&mut self, audio: &[f32]) -> usize {
+        let head = self.shared.head.load(Ordering::Relaxed);
+        let len = audio.len().min(self.free_len());
+        for (i, sample) in audio[..len].iter().enumerate() {
+            self.shared.data[head.wrapping_add(i) & self.shared.mask]
+                .store(sample.to_bits(), Ordering::Relaxed);
+        }
+        self.shared
+            .head
+            .store(head.wrapping_add(len), Ordering::Release);
+        len
+    }
+
+    /// 写入全部样本，缓冲区已满时等待消费端腾出空间
+    ///
+    /// 样本不会在堆上另行暂存，消费端被丢弃时返回错误。
+    pub(super) async fn push(&mut self, mut audio: &[f32]) -> Result<(), KokoroError> {
+        loop {
+            let len = self.write(audio);
+            audio = &audio[len..];
+            if audio.is_empty() {
+                return Ok(());
+            }
+            if Arc::strong_count(&self.shared) == 1 {
+                return Err(KokoroError::Send("RingConsumerDropped".to_owned()));
+            }
+
+            // 按照消费端腾出空间所需的时间等待
+            let wait = audio.len().min(self.capacity()) as f64 / SAMPLE_RATE as f64;
+            sleep(Duration::from_secs_f64(wait.clamp(0.001, 0.02))).await;
+        }
+    }
+
+    pub(super) fn set_active(&self, active: bool) {
+        self.shared.active.store(active, Ordering::Release);
+    }
+}
+
+impl AudioSink for RingProducer {
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        self.push(audio).await
+    }
+
+    fn try_write(&mut self, audio: &[f32]) -> usize {
+        RingProducer::write(self, audio)
+    }
+}
+
+/// 环形缓冲区的消费端
+pub struct RingConsumer {
+    shared: Arc<RingShared>,
+}
+
+impl RingConsumer {
+    /// 当前可读取的样本数
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    /// 缓冲区是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 累计读取的样本数，即当前的播放位置
+    pub fn position(&self) -> usize {
+        self.shared.tail.load(Ordering::Acquire)
+    }
+
+    /// 发生欠载的次数
+    pub fn underruns(&self) -> usize {
+        self.shared.underruns.load(Ordering::Acquire)
+    }
+
+    /// 读取样本到`out`，返回实际读取的样本数
+    ///
+    /// 可读的样本不足时，剩余部分填充静音；如果此时正在合成语音，则记为一次欠载。该方法不会阻塞，可以在实时音频回调中调用。
+    pub fn read(&mut self, out: &mut [f32]) -> usize {
+        let tail = self.shared.tail.load(Ordering::Relaxed);
+        let len = out.len().min(self.len());
+        for (i, sample) in out[..len].iter_mut().enumerate() {
+            *sample = f32::from_bits(
+                self.shared.data[tail.wrapping_add(i) & self.shared.mask].load(Ordering::Relaxed),
+            );
+        }
+        self.shared
+            .tail
+            .store(tail.wrapping_add(len), Ordering::Release);
+
+        if len < out.len() {
+            out[len..].fill(0.);
+            if self.shared.active.load(Ordering::Acquire) {
+                self.shared.underruns.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+        len
+    }
+}
+
+/// 写入调用方缓冲区的结果
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct WriteReport {
+    /// 写入结束后的位置（样本数）
+    pub position: usize,
+    /// 因缓冲区空间不足而丢弃的样本数
+    pub overflow: usize,
+    /// 消费端累计发生欠载的次数
+    pub underruns: usize,
+    /// 推理耗时
+    pub took: Duration,
+}
+
+/// 写入调用方缓冲区，放不下的样本计入溢出
+pub(super) struct SliceSink<'a> {
+    pub(super) buffer: &'a mut [f32],
+    pub(super) position: usize,
+    pub(super) overflow: usize,
+}
+
+impl AudioSink for SliceSink<'_> {
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        self.try_write(audio);
+        Ok(())
+    }
+
+    fn try_write(&mut self, audio: &[f32]) -> usize {
+        let len = audio.len().min(self.buffer.len() - self.position);
+        self.buffer[self.position..self.position + len].copy_from_slice(&audio[..len]);
+        self.position += len;
+        self.overflow += audio.len() - len;
+        // 放不下的样本已经计入溢出并丢弃
+        audio.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_ring() {
+        let (mut producer, mut consumer) = audio_ring(100);
+        assert_eq!(128, producer.capacity());
+        assert_eq!(128, producer.write(&[1.; 200]));
+        assert_eq!(0, producer.free_len());
+
+        let mut out = [0.; 100];
+        assert_eq!(100, consumer.read(&mut out));
+        assert_eq!(50, producer.write(&[2.; 50]));
+        let mut out = [0.; 100];
+        assert_eq!(78, consumer.read(&mut out));
+        assert_eq!(1., out[27]);
+        assert_eq!(2., out[28]);
+        // 没有合成任务时的空读不算欠载
+        assert_eq!(0, consumer.underruns());
+
+        producer.set_active(true);
+        assert_eq!(0, consumer.read(&mut out));
+        assert_eq!(0., out[0]);
+        assert_eq!(1, producer.underruns());
+        assert_eq!(178, producer.position());
+        assert_eq!(178, consumer.position());
+    }
+
+    #[tokio::test]
+    async fn test_ring_push() -> Result<(), KokoroError> {
+        let (mut producer, mut consumer) = audio_ring(64);
+        let reader = tokio::spawn(async move {
+            let mut total = 0;
+            let mut out = [0.; 16];
+            while total < 1000 {
+                total += consumer.read(&mut out);
+                tokio::task::yield_now().await;
+            }
+            total
+        });
+
+        producer.push(&[0.5; 1000]).await?;
+        assert_eq!(1000, reader.await.unwrap_or_default());
+        assert_eq!(1000, producer.position());
+        assert!(producer.push(&[0.5; 1000]).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ring_realtime() -> Result<(), KokoroError> {
+        // 一段语音远大于缓冲区，消费端按实时速度每10毫秒读取一次
+        const CHUNK: usize = 2400;
+        const CHUNKS: usize = 8;
+        let (mut producer, mut consumer) = audio_ring(1024);
+        let reader = tokio::spawn(async move {
+            while consumer.is_empty() {
+                tokio::task::yield_now().await;
+            }
+            let mut interval = tokio::time::interval(Duration::from_millis(10));
+            let mut out = [0.; SAMPLE_RATE as usize / 100];
+            let mut total = 0;
+            while total < CHUNK * CHUNKS {
+                interval.tick().await;
+                total += consumer.read(&mut out);
+            }
+            consumer.underruns()
+        });
+
+        producer.set_active(true);
+        for _ in 0..CHUNKS {
+            // 模拟比实时更快的推理
+            sleep(Duration::from_millis(1)).await;
+            producer.push(&[0.5; CHUNK]).await?;
+        }
+        producer.set_active(false);
+        assert_eq!(Ok(0), reader.await.map_err(|_| ()));
+        assert_eq!(CHUNK * CHUNKS, producer.position());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+use {
+    crate::{KokoroError, SAMPLE_RATE},
+    futures::{Stream, StreamExt},
+    std::{
+        io::{Error as IoError, ErrorKind},
+        net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+        pin::pin,
+        time::Duration,
+    },
+    tokio::{
+        net::{ToSocketAddrs, UdpSocket, lookup_host},
+        time::{Instant, sleep_until},
+    },
+};
+
+const RTP_VERSION: u8 = 2;
+const HEADER_LEN: usize = 12;
+
+/// RTP负载格式
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RtpPayload {
+    /// G.711 μ律（静态负载类型0），音频会被降采样到8kHz
+    Pcmu,
+    /// 16位大端线性PCM，保持24kHz采样率，默认使用动态负载类型96
+    L16,
+    /// Opus（RFC 7587），时间戳按48kHz计算，默认使用动态负载类型111
+    #[cfg(feature = "opus")]
+    Opus,
+}
+
+impl RtpPayload {
+    fn default_payload_type(&self) -> u8 {
+        match self {
+            Self::Pcmu => 0,
+            Self::L16 => 96,
+            #[cfg(feature = "opus")]
+            Self::Opus => 111,
+        }
+    }
+
+    /// RTP时间戳的时钟频率
+    pub fn clock_rate(&self) -> u32 {
+        match self {
+            Self::Pcmu => 8000,
+            Self::L16 => SAMPLE_RATE,
+            #[cfg(feature = "opus")]
+            Self::Opus => 48000,
+        }
+    }
+}
+
+/// 把线性样本编码为G.711 μ律
+fn linear_to_ulaw(sample: f32) -> u8 {
+    const BIAS: i32 = 0x84;
+    const CLIP: i32 = 32635;
+
+    let mut pcm = (sample.clamp(-1., 1.) * i16::MAX as f32) as i32;
+    let sign = if pcm < 0 {
+        pcm = -pcm;
+        0x80
+    } else {
+        0
+    };
+    let pcm = pcm.min(CLIP) + BIAS;
+    let exponent = (7 - (pcm >> 7).leading_zeros() as i32 + 24).clamp(0, 7);
+    let mantissa = (pcm >> (exponent + 3)) & 0x0F;
+
+    !(sign | (exponent << 4) as u8 | mantissa as u8)
+}
+
+/// RTP发送端
+///
+/// 该结构体把定长音频帧封装为RTP包发送到指定的UDP地址，自动维护序列号和时间戳。
+pub struct RtpSender {
+    socket: UdpSocket,
+    payload: RtpPayload,
+    payload_type: u8,
+    sequence: u16,
+    timestamp: u32,
+    ssrc: u32,
+    marker: bool,
+    #[cfg(feature = "opus")]
+    encoder: Option<audiopus::coder::Encoder>,
+}
+
+impl RtpSender {
+    /// 连接到远端地址
+    ///
+    /// 序列号、时间戳的初始值以及SSRC都是随机生成的。本地套接字绑定到与远端地址相同协议族（IPv4或IPv6）的任意地址，远端地址解析出多个结果时依次尝试。
+    ///
+    /// # 参数
+    ///
+    /// * `addr` - 接收端的UDP地址。
+    /// * `payload` - 负载格式。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{RtpPayload, RtpSender};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(mut sender) = RtpSender::connect("127.0.0.1:5004", RtpPayload::Pcmu).await else {
+    ///         return;
+    ///     };
+    ///     let _ = sender.send_frame(&[0.; 480]).await;
+    /// }
+    /// ```
+    ///
+    pub async fn connect<A>(addr: A, payload: RtpPayload) -> Result<Self, KokoroError>
+    where
+        A: ToSocketAddrs,
+    {
+        let mut result = Err(IoError::new(
+            ErrorKind::InvalidInput,
+            "could not resolve to any address",
+        ));
+        for addr in lookup_host(addr).await? {
+            result = Self::connect_addr(addr).await;
+            if result.is_ok() {
+                break;
+            }
+        }
+        let socket = result?;
+
+        #[cfg(feature = "opus")]
+        let encoder = match payload {
+            RtpPayload::Opus => Some(audiopus::coder::Encoder::new(
+                audiopus::SampleRate::Hz24000,
+                audiopus::Channels::Mono,
+                audiopus::Application::Voip,
+            )?),
+            _ => None,
+        };
+
+        Ok(Self {
+            socket,
+            payload,
+            payload_type: payload.default_payload_type(),
+            sequence: rand::random(),
+            timestamp: rand::random(),
+            ssrc: rand::random(),
+            marker: true,
+            #[cfg(feature = "opus")]
+            encoder,
+        })
+    }
+
+    /// 绑定与`addr`同一协议族的本地地址并连接
+    async fn connect_addr(addr: SocketAddr) -> Result<UdpSocket, IoError> {
+        let local = match addr {
+            SocketAddr::V4(_) => SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)),
+            SocketAddr::V6(_) => SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)),
+        };
+        let socket = UdpSocket::bind(local).await?;
+        socket.connect(addr).await?;
+        Ok(socket)
+    }
+
+    /// 设置负载类型，用于与对端协商的动态负载类型
+    pub fn set_payload_type(&mut self, payload_type: u8) {
+        self.payload_type = payload_type & 0x7F
+    }
+
+    /// 同步源标识
+    pub fn ssrc(&self) -> u32 {
+        self.ssrc
+    }
+
+    /// 下一个包的序列号
+    pub fn sequence(&self) -> u16 {
+        self.sequence
+    }
+
+    /// 下一个包的时间戳
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// 标记新的语音段开始，下一个包会带上标记位
+    pub fn mark(&mut self) {
+        self.marker = true
+    }
+
+    fn encode(&mut self, frame: &[f32], packet: &mut Vec<u8>) -> Result<(), KokoroError> {
+        match self.payload {
+            RtpPayload::Pcmu => packet.extend(
+                // 每3个样本取平均，作为简单的抗混叠滤波后降采样到8kHz
+                frame
+                    .chunks(3)
+                    .map(|i| linear_to_ulaw(i.iter().sum::<f32>() / i.len() as f32)),
+            ),
+            RtpPayload::L16 => {
+                for i in frame {
+                    packet.extend(((i.clamp(-1., 1.) * i16::MAX as f32) as i16).to_be_bytes());
+                }
+            }
+            #[cfg(feature = "opus")]
+            RtpPayload::Opus => {
+                let Some(encoder) = self.encoder.as_ref() else {
+                    return Ok(());
+                };
+                let start = packet.len();
+                packet.resize(start + 4000, 0);
+                let len = encoder.encode_float(frame, &mut packet[start..])?;
+                packet.truncate(start + len);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 发送一帧音频
+    ///
+    /// 帧的长度应当固定，可以使用`Packetizer`或`SynthStream::frames`来切分。Opus要求帧长为2.5、5、10、20、40或60毫秒。
+    pub async fn send_frame(&mut self, frame: &[f32]) -> Result<(), KokoroError> {
+        let mut packet = Vec::with_capacity(HEADER_LEN + frame.len() * 2);
+        packet.push(RTP_VERSION << 6);
+        packet.push((self.marker as u8) << 7 | self.payload_type);
+        packet.extend(self.sequence.to_be_bytes());
+        packet.extend(self.timestamp.to_be_bytes());
+        packet.extend(self.ssrc.to_be_bytes());
+        self.encode(frame, &mut packet)?;
+        self.socket.send(&packet).await?;
+
+        let ticks = frame.len() as u64 * self.payload.clock_rate() as u64 / SAMPLE_RATE as u64;
+        self.sequence = self.sequence.wrapping_add(1);
+        self.timestamp = self.timestamp.wrapping_add(ticks as u32);
+        self.marker = false;
+        Ok(())
+    }
+
+    /// 按实时速率发送一个帧流中的所有帧
+    ///
+    /// 每一帧按其时长进行节拍控制，适合直接对接实时通话。
+    pub async fn send_stream<S>(&mut self, frames: S) -> Result<(), KokoroError>
+    where
+        S: Stream<Item = Vec<f32>>,
+    {
+        let mut frames = pin!(frames);
+        let mut deadline = Instant::now();
+        while let Some(frame) = frames.next().await {
+            sleep_until(deadline).await;
+            self.send_frame(&frame).await?;
+            deadline += Duration::from_secs_f64(frame.len() as f64 / SAMPLE_RATE as f64);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_linear_to_ulaw() {
+        assert_eq!(0xFF, linear_to_ulaw(0.));
+        assert_eq!(0x80, linear_to_ulaw(1.));
+        assert_eq!(0x00, linear_to_ulaw(-1.));
+    }
+
+    #[tokio::test]
+    async fn test_rtp_sender() -> Result<(), KokoroError> {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await?;
+        for (payload, payload_len, ticks) in
+            [(RtpPayload::L16, 960, 480), (RtpPayload::Pcmu, 160, 160)]
+        {
+            let mut sender = RtpSender::connect(receiver.local_addr()?, payload).await?;
+            let frames = futures::stream::iter(vec![vec![0.25; 480]; 3]);
+            sender.send_stream(frames).await?;
+
+            let mut last: Option<(u16, u32)> = None;
+            let mut buf = [0u8; 2048];
+            for i in 0..3 {
+                let len = receiver.recv(&mut buf).await?;
+                assert_eq!(HEADER_LEN + payload_len, len);
+                assert_eq!(RTP_VERSION, buf[0] >> 6);
+                assert_eq!(i == 0, buf[1] >> 7 == 1);
+                assert_eq!(payload.default_payload_type(), buf[1] & 0x7F);
+                assert_eq!(sender.ssrc().to_be_bytes(), buf[8..12]);
+                let sequence = u16::from_be_bytes([buf[2], buf[3]]);
+                let timestamp = u32::from_be_bytes([buf[4], buf[5], buf[6], buf[7]]);
+                if let Some((s, t)) = last {
+                    assert_eq!(s.wrapping_add(1), sequence);
+                    assert_eq!(t.wrapping_add(ticks), timestamp);
+                }
+                last = Some((sequence, timestamp));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rtp_sender_ipv6() -> Result<(), KokoroError> {
+        // 没有IPv6环回地址的环境跳过
+        let Ok(receiver) = UdpSocket::bind("[::1]:0").await else {
+            return Ok(());
+        };
+        let mut sender = RtpSender::connect(receiver.local_addr()?, RtpPayload::Pcmu).await?;
+        sender.send_frame(&[0.; 480]).await?;
+        let mut buf = [0u8; 2048];
+        assert_eq!(HEADER_LEN + 160, receiver.recv(&mut buf).await?);
+
+        Ok(())
+    }
+}
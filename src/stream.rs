@@ -1,5 +1,8 @@
 use {
-    crate::{KokoroError, Voice},
+    crate::{
+        FrameFlush, FrameStream, KokoroError, PitchShiftStream, Voice, Watermark, WatermarkStream,
+        synthesizer::Input, tokenizer::check_token_ids, try_get_token_ids,
+    },
     futures::{Sink, SinkExt, Stream},
     pin_project::pin_project,
     std::{
@@ -12,7 +15,7 @@ use {
 
 struct Request<S> {
     voice: Voice,
-    text: S,
+    input: Input<S>,
 }
 
 struct Response {
@@ -29,6 +32,98 @@ pub struct SynthStream {
     rx: UnboundedReceiver<Response>,
 }
 
+impl SynthStream {
+    /// 对合成结果变调
+    ///
+    /// 该方法返回一个新的流，在不改变语速的情况下调整合成结果的音高，段与段之间的衔接处不会产生杂音。
+    ///
+    /// # 参数
+    ///
+    /// * `semitones` - 音高偏移的半音数，正数升调，负数降调。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (_, stream) = tts.stream::<&str>(Voice::ZfXiaoxiao(1.0));
+    ///     // 升高两个半音
+    ///     let _stream = stream.pitch_shift(2.);
+    /// }
+    /// ```
+    ///
+    pub fn pitch_shift(self, semitones: f32) -> PitchShiftStream<Self> {
+        PitchShiftStream::new(self, semitones)
+    }
+
+    /// 把合成结果切分为定长帧
+    ///
+    /// 该方法返回一个新的流，每个元素都是固定时长的一帧音频，可以配合`RtpSender`进行实时传输。
+    ///
+    /// # 参数
+    ///
+    /// * `frame_duration` - 每帧的时长，例如10毫秒或20毫秒。
+    /// * `flush` - 不足一帧的剩余音频的处理方式。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{FrameFlush, KokoroTts, RtpPayload, RtpSender, Voice};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (mut sink, stream) = tts.stream(Voice::ZfXiaoxiao(1.0));
+    ///     let _ = sink.synth("你好").await;
+    ///     drop(sink);
+    ///     let frames = stream.frames(Duration::from_millis(20), FrameFlush::OnEnd);
+    ///     let Ok(mut sender) = RtpSender::connect("127.0.0.1:5004", RtpPayload::Pcmu).await else {
+    ///         return;
+    ///     };
+    ///     let _ = sender.send_stream(frames).await;
+    /// }
+    /// ```
+    ///
+    pub fn frames(self, frame_duration: Duration, flush: FrameFlush) -> FrameStream<Self> {
+        FrameStream::new(self, frame_duration, flush)
+    }
+
+    /// 对合成结果嵌入水印
+    ///
+    /// 该方法返回一个新的流，在每段合成结果中嵌入由密钥派生的不可听水印，之后可以通过`Watermark::detect`证明音频的来源。
+    ///
+    /// # 参数
+    ///
+    /// * `watermark` - 水印。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice, Watermark};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (_, stream) = tts.stream::<&str>(Voice::ZfXiaoxiao(1.0));
+    ///     let _stream = stream.watermark(&Watermark::new("my deployment"));
+    /// }
+    /// ```
+    ///
+    pub fn watermark(self, watermark: &Watermark) -> WatermarkStream<Self> {
+        WatermarkStream::new(self, watermark)
+    }
+}
+
 impl Stream for SynthStream {
     type Item = (Vec<f32>, Duration);
 
@@ -108,6 +203,58 @@ impl<S> SynthSink<S> {
     pub async fn synth(&mut self, text: S) -> Result<(), KokoroError> {
         self.send((self.voice, text)).await
     }
+
+    /// 发送音标合成请求
+    ///
+    /// 音标会跳过音素化前端直接合成。音标在发送前按当前语音对应版本的词表检查，不在词表中的符号会返回`KokoroError::UnknownPhoneme`，v1.0模型的音标过长时返回`KokoroError::TooManyTokens`。
+    ///
+    /// # 参数
+    ///
+    /// * `phonemes` - Kokoro使用的音标。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::{KokoroTts, Voice};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let Ok(tts) = KokoroTts::new("../kokoro-v1.0.int8.onnx", "../voices.bin").await else {
+    ///         return;
+    ///     };
+    ///     let (mut sink, _) = tts.stream(Voice::AfBella(1.0));
+    ///     let _ = sink.synth_phonemes("həlˈO wˈɜɹld").await;
+    /// }
+    /// ```
+    ///
+    pub async fn synth_phonemes(&mut self, phonemes: S) -> Result<(), KokoroError>
+    where
+        S: AsRef<str>,
+    {
+        let tokens = try_get_token_ids(phonemes.as_ref(), self.voice.is_v11_supported())?;
+        self.send_input(Input::Tokens(tokens))
+    }
+
+    /// 发送词元合成请求
+    ///
+    /// 词元会跳过音素化前端直接合成，不需要包含首尾的填充符。不在当前语音对应版本词表中的词元会返回`KokoroError::InvalidToken`。
+    ///
+    /// # 参数
+    ///
+    /// * `tokens` - 词元。
+    pub async fn synth_tokens(&mut self, tokens: &[i64]) -> Result<(), KokoroError> {
+        let tokens = check_token_ids(tokens, self.voice.is_v11_supported())?;
+        self.send_input(Input::Tokens(tokens))
+    }
+
+    fn send_input(&self, input: Input<S>) -> Result<(), KokoroError> {
+        self.tx
+            .send(Request {
+                voice: self.voice,
+                input,
+            })
+            .map_err(|e| KokoroError::Send(e.to_string()))
+    }
 }
 
 impl<S> Sink<(Voice, S)> for SynthSink<S> {
@@ -119,7 +266,10 @@ impl<S> Sink<(Voice, S)> for SynthSink<S> {
 
     fn start_send(self: Pin<&mut Self>, (voice, text): (Voice, S)) -> Result<(), Self::Error> {
         self.tx
-            .send(Request { voice, text })
+            .send(Request {
+                voice,
+                input: Input::Text(text),
+            })
             .map_err(|e| KokoroError::Send(e.to_string()))
     }
 
@@ -137,21 +287,39 @@ pub(super) fn start_synth_session<F, R, S>(
     synth_request_callback: F,
 ) -> (SynthSink<S>, SynthStream)
 where
-    F: Fn(S, Voice) -> R + Send + 'static,
+    F: Fn(Input<S>, Voice) -> R + Send + 'static,
     R: Future<Output = Result<(Vec<f32>, Duration), KokoroError>> + Send,
     S: AsRef<str> + Send + 'static,
 {
-    let (tx, mut rx) = unbounded_channel::<Request<S>>();
     let (tx2, rx2) = unbounded_channel();
+    let sink = start_session(voice, move |input, voice| {
+        let tx2 = tx2.clone();
+        let response = synth_request_callback(input, voice);
+        async move {
+            let (data, took) = response.await?;
+            tx2.send(Response { data, took })
+                .map_err(|e| KokoroError::Send(e.to_string()))
+        }
+    });
+
+    (sink, SynthStream { rx: rx2 })
+}
+
+/// 启动合成会话，每个请求都交给`synth_request_callback`处理，结果由回调自行输出
+pub(super) fn start_session<F, R, S>(voice: Voice, synth_request_callback: F) -> SynthSink<S>
+where
+    F: Fn(Input<S>, Voice) -> R + Send + 'static,
+    R: Future<Output = Result<(), KokoroError>> + Send,
+    S: AsRef<str> + Send + 'static,
+{
+    let (tx, mut rx) = unbounded_channel::<Request<S>>();
     tokio::spawn(async move {
         while let Some(req) = rx.recv().await {
-            let (data, took) = synth_request_callback(req.text, req.voice).await?;
-            tx2.send(Response { data, took })
-                .map_err(|e| KokoroError::Send(e.to_string()))?;
+            synth_request_callback(req.input, req.voice).await?;
         }
 
         Ok::<_, KokoroError>(())
     });
 
-    (SynthSink { tx, voice }, SynthStream { rx: rx2 })
+    SynthSink { tx, voice }
 }
@@ -1,5 +1,5 @@
 use {
-    crate::{KokoroError, Voice, g2p, get_token_ids},
+    crate::{KokoroError, Phonemizer, PhonemizerChain, Voice, get_token_ids},
     ndarray::Array,
     ort::{
         inputs,
@@ -14,23 +14,76 @@ use {
     tokio::sync::Mutex,
 };
 
-async fn synth_v10<P, S>(
+/// 接收合成音频的下游
+pub(super) trait AudioSink {
+    /// 写入一段音频，返回之前不会开始下一段推理
+    fn write(&mut self, audio: &[f32]) -> impl Future<Output = Result<(), KokoroError>> + Send;
+
+    /// 不等待地写入尽可能多的样本，返回实际写入的样本数
+    ///
+    /// 合成时先在持有推理结果的情况下调用，剩余的样本才会复制出来交给`write`等待写入。
+    fn try_write(&mut self, _audio: &[f32]) -> usize {
+        0
+    }
+}
+
+impl AudioSink for Vec<f32> {
+    async fn write(&mut self, audio: &[f32]) -> Result<(), KokoroError> {
+        self.extend_from_slice(audio);
+        Ok(())
+    }
+
+    fn try_write(&mut self, audio: &[f32]) -> usize {
+        self.extend_from_slice(audio);
+        audio.len()
+    }
+}
+
+impl<T> AudioSink for &mut T
+where
+    T: AudioSink + Send,
+{
+    fn write(&mut self, audio: &[f32]) -> impl Future<Output = Result<(), KokoroError>> + Send {
+        (**self).write(audio)
+    }
+
+    fn try_write(&mut self, audio: &[f32]) -> usize {
+        (**self).try_write(audio)
+    }
+}
+
+/// 合成请求的输入
+pub(super) enum Input<S> {
+    /// 文本，先经过音素化前端再合成
+    Text(S),
+    /// 已经检查过并加上填充符的词元
+    Tokens(Vec<i64>),
+}
+
+async fn synth_v10<P, F>(
     model: Weak<Mutex<Session>>,
-    phonemes: S,
+    phonemes: Vec<i64>,
     pack: P,
     speed: f32,
-) -> Result<(Vec<f32>, Duration), KokoroError>
+    on_audio: &mut F,
+) -> Result<Duration, KokoroError>
 where
     P: AsRef<Vec<Vec<Vec<f32>>>>,
-    S: AsRef<str>,
+    F: AudioSink,
 {
     let model = model.upgrade().ok_or(KokoroError::ModelReleased)?;
-    let phonemes = get_token_ids(phonemes.as_ref(), false);
-    let phonemes = Array::from_shape_vec((1, phonemes.len()), phonemes)?;
-    let ref_s = pack.as_ref()[phonemes.len() - 1]
+    // 文本输入没有经过长度检查，超出语音包的长度时返回错误而不是越界
+    let ref_s = pack
+        .as_ref()
+        .get(phonemes.len().saturating_sub(1))
+        .ok_or(KokoroError::TooManyTokens(
+            phonemes.len(),
+            pack.as_ref().len(),
+        ))?
         .first()
         .cloned()
         .unwrap_or_default();
+    let phonemes = Array::from_shape_vec((1, phonemes.len()), phonemes)?;
 
     let style = Array::from_shape_vec((1, ref_s.len()), ref_s)?;
     let speed = Array::from_vec(vec![speed]);
@@ -49,24 +102,31 @@ where
         .await?;
     let elapsed = t.elapsed()?;
     let (_, audio) = kokoro_output["audio"].try_extract_tensor::<f32>()?;
+    // 直接从推理结果写入下游，放不下的部分才复制出来，先释放模型，等待下游时不阻塞其他合成任务
+    let written = on_audio.try_write(audio);
+    let rest = audio[written..].to_vec();
+    drop(kokoro_output);
+    drop(model);
+    if !rest.is_empty() {
+        on_audio.write(&rest).await?;
+    }
 
-    Ok((audio.to_owned(), elapsed))
+    Ok(elapsed)
 }
 
-async fn synth_v11<P, S>(
+async fn synth_v11<P, F>(
     model: Weak<Mutex<Session>>,
-    phonemes: S,
+    mut phonemes: Vec<i64>,
     pack: P,
     speed: i32,
-) -> Result<(Vec<f32>, Duration), KokoroError>
+    on_audio: &mut F,
+) -> Result<Duration, KokoroError>
 where
     P: AsRef<Vec<Vec<Vec<f32>>>>,
-    S: AsRef<str>,
+    F: AudioSink,
 {
     let model = model.upgrade().ok_or(KokoroError::ModelReleased)?;
-    let mut phonemes = get_token_ids(phonemes.as_ref(), true);
 
-    let mut ret = Vec::new();
     let mut elapsed = Duration::ZERO;
     while let p = phonemes.drain(..min(pack.as_ref().len(), phonemes.len()))
         && p.len() != 0
@@ -96,28 +156,70 @@ where
         let (_, audio) = kokoro_output["waveform"].try_extract_tensor::<f32>()?;
         let (_, _duration) = kokoro_output["duration"].try_extract_tensor::<i64>()?;
         // let _ = dbg!(duration.len());
-        ret.extend_from_slice(audio);
+        let written = on_audio.try_write(audio);
+        let rest = audio[written..].to_vec();
+        drop(kokoro_output);
+        drop(model);
+        if !rest.is_empty() {
+            on_audio.write(&rest).await?;
+        }
     }
 
-    Ok((ret, elapsed))
+    Ok(elapsed)
 }
 
-pub(super) async fn synth<P, S>(
+/// 合成语音，模型输出的每一段音频都会立即交给`on_audio`，不会等到整段文本合成完毕
+///
+/// `on_audio`写入完成之前不会开始下一段推理，下游可以在其中等待腾出空间；写入失败时合成立即终止。`phonemizers`中注册的前端会覆盖对应版本的内置前端。
+pub(super) async fn synth_with<P, S, F>(
     model: Weak<Mutex<Session>>,
-    text: S,
+    phonemizers: &PhonemizerChain,
+    input: Input<S>,
     pack: P,
     voice: Voice,
-) -> Result<(Vec<f32>, Duration), KokoroError>
+    mut on_audio: F,
+) -> Result<Duration, KokoroError>
 where
     P: AsRef<Vec<Vec<Vec<f32>>>>,
     S: AsRef<str>,
+    F: AudioSink,
 {
-    let phonemes = g2p(text.as_ref(), voice.is_v11_supported())?;
-    // #[cfg(debug_assertions)]
-    // println!("{}", phonemes);
+    let phonemes = match input {
+        Input::Text(text) => {
+            let mut chain = PhonemizerChain::new(voice.is_v11_supported());
+            chain.set_language(voice.language());
+            chain.extend(phonemizers);
+            let phonemes = chain.phonemize(text.as_ref())?;
+            // #[cfg(debug_assertions)]
+            // println!("{}", phonemes);
+            get_token_ids(&phonemes, voice.is_v11_supported())
+        }
+        Input::Tokens(tokens) => tokens,
+    };
     match voice {
-        v if v.is_v11_supported() => synth_v11(model, phonemes, pack, v.get_speed_v11()?).await,
-        v if v.is_v10_supported() => synth_v10(model, phonemes, pack, v.get_speed_v10()?).await,
+        v if v.is_v11_supported() => {
+            synth_v11(model, phonemes, pack, v.get_speed_v11()?, &mut on_audio).await
+        }
+        v if v.is_v10_supported() => {
+            synth_v10(model, phonemes, pack, v.get_speed_v10()?, &mut on_audio).await
+        }
         v => Err(KokoroError::VoiceVersionInvalid(v.get_name().to_owned())),
     }
 }
+
+pub(super) async fn synth<P, S>(
+    model: Weak<Mutex<Session>>,
+    phonemizers: &PhonemizerChain,
+    input: Input<S>,
+    pack: P,
+    voice: Voice,
+) -> Result<(Vec<f32>, Duration), KokoroError>
+where
+    P: AsRef<Vec<Vec<Vec<f32>>>>,
+    S: AsRef<str>,
+{
+    let mut ret = Vec::new();
+    let elapsed = synth_with(model, phonemizers, input, pack, voice, &mut ret).await?;
+
+    Ok((ret, elapsed))
+}
@@ -1,4 +1,5 @@
 use {
+    crate::KokoroError,
     log::warn,
     std::{collections::HashMap, sync::LazyLock},
 };
@@ -299,17 +300,25 @@ static VOCAB_V11: LazyLock<HashMap<char, u8>> = LazyLock::new(|| {
     map
 });
 
+/// v1.0模型一次能合成的最多词元数（含首尾的填充符），即语音包中风格向量的个数；v1.1会按语音包的长度分段合成，没有这个限制
+pub(crate) const MAX_TOKENS_V10: usize = 510;
+
+fn vocab(v11: bool) -> &'static HashMap<char, u8> {
+    if v11 { &VOCAB_V11 } else { &VOCAB_V10 }
+}
+
+/// 音标符号是否在词表中
+pub(crate) fn is_in_vocab(c: char, v11: bool) -> bool {
+    vocab(v11).contains_key(&c)
+}
+
+/// 把音标转换为模型的输入，词表中没有的符号会被跳过
 pub fn get_token_ids(phonemes: &str, v11: bool) -> Vec<i64> {
     let mut tokens = Vec::with_capacity(phonemes.len() + 2);
     tokens.push(0);
 
     for i in phonemes.chars() {
-        let v = if v11 {
-            VOCAB_V11.get(&i).copied()
-        } else {
-            VOCAB_V10.get(&i).copied()
-        };
-        match v {
+        match vocab(v11).get(&i).copied() {
             Some(t) => {
                 tokens.push(t as _);
             }
@@ -322,3 +331,105 @@ pub fn get_token_ids(phonemes: &str, v11: bool) -> Vec<i64> {
     tokens.push(0);
     tokens
 }
+
+/// 把音标转换为模型的输入，遇到词表中没有的符号时返回错误
+///
+/// 错误中的位置是该符号在`phonemes`中的字节偏移。v1.0的词元超过模型一次能合成的长度时返回`KokoroError::TooManyTokens`。
+///
+/// # 参数
+///
+/// * `phonemes` - Kokoro使用的音标。
+/// * `v11` - 是否使用v1.1模型的词表。
+///
+/// # 示例
+///
+/// ```rust
+/// use kokoro_tts::try_get_token_ids;
+///
+/// assert_eq!(try_get_token_ids(",.", false).unwrap(), vec![0, 3, 4, 0]);
+/// assert!(try_get_token_ids("a😀", false).is_err());
+/// ```
+///
+pub fn try_get_token_ids(phonemes: &str, v11: bool) -> Result<Vec<i64>, KokoroError> {
+    let vocab = vocab(v11);
+    let mut tokens = Vec::with_capacity(phonemes.len() + 2);
+    tokens.push(0);
+    for (position, i) in phonemes.char_indices() {
+        let Some(t) = vocab.get(&i) else {
+            return Err(KokoroError::UnknownPhoneme(i, position));
+        };
+        tokens.push(*t as _);
+    }
+    tokens.push(0);
+
+    check_len(tokens, v11)
+}
+
+/// 检查加上填充符后的词元数不超过v1.0模型的上限
+fn check_len(tokens: Vec<i64>, v11: bool) -> Result<Vec<i64>, KokoroError> {
+    if !v11 && tokens.len() > MAX_TOKENS_V10 {
+        return Err(KokoroError::TooManyTokens(tokens.len(), MAX_TOKENS_V10));
+    }
+    Ok(tokens)
+}
+
+/// 检查词元是否都在词表中且不超过模型的长度上限，并在首尾加上填充符
+pub(super) fn check_token_ids(tokens: &[i64], v11: bool) -> Result<Vec<i64>, KokoroError> {
+    let vocab = vocab(v11);
+    if let Some(t) = tokens
+        .iter()
+        .find(|t| !vocab.values().any(|v| *v as i64 == **t))
+    {
+        return Err(KokoroError::InvalidToken(*t));
+    }
+
+    let mut result = Vec::with_capacity(tokens.len() + 2);
+    result.push(0);
+    result.extend_from_slice(tokens);
+    result.push(0);
+    check_len(result, v11)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_ids() -> Result<(), KokoroError> {
+        assert_eq!(vec![0, 3, 4, 0], try_get_token_ids(",.", true)?);
+        assert!(matches!(
+            try_get_token_ids("ab\u{1F600}", false),
+            Err(KokoroError::UnknownPhoneme('\u{1F600}', 2))
+        ));
+        // 位置是字节偏移
+        assert!(matches!(
+            try_get_token_ids("ˈæ\u{1F600}", false),
+            Err(KokoroError::UnknownPhoneme('\u{1F600}', 4))
+        ));
+        // v1.0的长度上限包含首尾的填充符，v1.1分段合成
+        let long = "a".repeat(MAX_TOKENS_V10 - 1);
+        assert!(matches!(
+            try_get_token_ids(&long, false),
+            Err(KokoroError::TooManyTokens(511, MAX_TOKENS_V10))
+        ));
+        assert_eq!(MAX_TOKENS_V10, try_get_token_ids(&long[1..], false)?.len());
+        assert_eq!(MAX_TOKENS_V10 + 1, try_get_token_ids(&long, true)?.len());
+        assert!(matches!(
+            check_token_ids(&[3; MAX_TOKENS_V10], false),
+            Err(KokoroError::TooManyTokens(..))
+        ));
+        // 宽松的版本会跳过未知符号
+        assert_eq!(
+            get_token_ids("ab", false),
+            get_token_ids("a\u{1F600}b", false)
+        );
+
+        assert_eq!(vec![0, 3, 4, 0], check_token_ids(&[3, 4], false)?);
+        assert!(matches!(
+            check_token_ids(&[3, 0], false),
+            Err(KokoroError::InvalidToken(0))
+        ));
+
+        Ok(())
+    }
+}
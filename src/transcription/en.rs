@@ -1,9 +1,8 @@
-use regex::Regex;
 use std::{collections::HashMap, sync::LazyLock};
 
 static LETTERS_IPA_MAP: LazyLock<HashMap<char, &'static str>> = LazyLock::new(|| {
     let mut map = HashMap::new();
-    map.insert('a', "ɐ");
+    map.insert('a', "ˈA");
     map.insert('b', "bˈi");
     map.insert('c', "sˈi");
     map.insert('d', "dˈi");
@@ -106,7 +105,7 @@ static ARPA_IPA_MAP: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::n
 const SPECIAL_CASES: [(&str, &str); 3] = [("CLICK!", "ʘ"), ("TSK!", "ǀ"), ("TUT!", "ǁ")];
 
 pub fn arpa_to_ipa(arpa: &str) -> Result<String, regex::Error> {
-    let re = Regex::new(r"([A-Z!]+)(\d*)")?;
+    let re = regex!(r"([A-Z!]+)(\d*)")?;
 
     let Some(caps) = re.captures(arpa) else {
         return Ok(Default::default());
@@ -117,19 +116,23 @@ pub fn arpa_to_ipa(arpa: &str) -> Result<String, regex::Error> {
         return Ok(sc.1.to_string());
     }
 
-    // 获取IPA映射
-    let phoneme = ARPA_IPA_MAP
-        .get(&caps[1])
-        .map_or_else(|| letters_to_ipa(arpa), |i| i.to_string());
+    // 获取IPA映射，重读的AH和ER分别写作ʌ和ɝ，非重读的写作ə和ɚ
+    let phoneme = match (&caps[1], &caps[2]) {
+        ("AH", "1" | "2") => "ʌ".to_owned(),
+        ("ER", "0") => "ɚ".to_owned(),
+        (p, _) => ARPA_IPA_MAP
+            .get(p)
+            .map_or_else(|| letters_to_ipa(arpa), |i| i.to_string()),
+    };
 
     let mut result = String::with_capacity(arpa.len() * 2);
-    // 添加重音标记（支持三级重音）
-    result.push(match &caps[2] {
-        "1" => 'ˈ',
-        "2" => 'ˌ',
-        "3" => '˧', // 2025新增中级重音
-        _ => '\0',
-    });
+    // 添加重音标记（支持三级重音），没有重音时不加
+    match &caps[2] {
+        "1" => result.push('ˈ'),
+        "2" => result.push('ˌ'),
+        "3" => result.push('˧'), // 2025新增中级重音
+        _ => (),
+    }
 
     result.push_str(&phoneme);
 
@@ -130,14 +130,29 @@ typedef struct {
 	unsigned short start;
 } WORD_TAB;
 
-static const char *data_dictrules;     // language_1   translation rules file
-static int dict_condition;    // conditional apply some pronunciation rules and dict.lookups
-static const char *dict_hashtab[N_HASH_DICT];   // hash table to index dictionary lookup file
-static const char *groups1[256];         // translation rule lists, index by single letter
-static const char *groups2[N_RULE_GROUP2];   // translation rule lists, indexed by two-letter pairs
-static unsigned char groups2_count[256];    // number of 2 letter groups for this initial letter
-static unsigned int groups2_name[N_RULE_GROUP2];  // the two letter pairs for groups2[]
-static unsigned char groups2_start[256];    // index into groups2
+// All state of the translator. The dictionary part is filled in by CreateTranslator() and only read afterwards,
+// the rest is scratch space for one call of TextToPhonemes(). Use one translator per thread.
+typedef struct {
+	const char *data_dictrules;     // language_1   translation rules file
+	int dict_condition;    // conditional apply some pronunciation rules and dict.lookups
+	const char *dict_hashtab[N_HASH_DICT];   // hash table to index dictionary lookup file
+	const char *groups1[256];         // translation rule lists, index by single letter
+	const char *groups2[N_RULE_GROUP2];   // translation rule lists, indexed by two-letter pairs
+	unsigned char groups2_count[256];    // number of 2 letter groups for this initial letter
+	unsigned int groups2_name[N_RULE_GROUP2];  // the two letter pairs for groups2[]
+	unsigned char groups2_start[256];    // index into groups2
+	unsigned char letter_bits[256];
+	int n_groups2;              // number of groups2[] entries used
+	int n_ph_list2;
+	int n_phoneme_list;
+	PHONEME_LIST2 ph_list2[N_PHONEME_LIST]; // first stage of text->phonemes
+	PHONEME_LIST phoneme_list[N_PHONEME_LIST+1];
+	char source[N_TR_SOURCE+40]; // extra space for embedded command & voice change info at end
+	char word_phonemes[N_WORD_PHONEMES]; // a word translated into phoneme codes
+	char *phon_out_buf;   // passes the result of GetTranslatedPhonemeString()
+	unsigned int phon_out_size;
+} Translator;
+
 static const unsigned short ipa1[96] = {
 	0x20,  0x21,  0x22,  0x2b0, 0x24,  0x25,  0x0e6, 0x2c8, 0x28,  0x29,  0x27e, 0x2b,  0x2cc, 0x2d,  0x2e,  0x2f,
 	0x252, 0x31,  0x32,  0x25c, 0x34,  0x35,  0x36,  0x37,  0x275, 0x39,  0x2d0, 0x2b2, 0x3c,  0x3d,  0x3e,  0x294,
@@ -146,13 +161,6 @@ static const unsigned short ipa1[96] = {
 	0x60,  0x61,  0x62,  0x63,  0x64,  0x65,  0x66,  0x261, 0x68,  0x69,  0x6a,  0x6b,  0x6c,  0x6d,  0x6e,  0x6f,
 	0x70,  0x71,  0x72,  0x73,  0x74,  0x75,  0x76,  0x77,  0x78,  0x79,  0x7a,  0x7b,  0x7c,  0x7d,  0x303, 0x7f
 };
-static unsigned char letter_bits[256];
-static int n_groups2;              // number of groups2[] entries used
-static int n_ph_list2;
-static int n_phoneme_list = 0;
-PHONEME_LIST2 ph_list2[N_PHONEME_LIST]; // first stage of text->phonemes
-static char *phon_out_buf = NULL;   // passes the result of GetTranslatedPhonemeString()
-static unsigned int phon_out_size = 0;
 static const unsigned short phoneme_index[21658] = {
 	0x4801,0x1,0xa100,0x83d5,0x0,0x0,0xa200,0x8212,0x0,0x0,0xc000,0x2,0xb000,0x56,0xd02,0xc999,0x3410,0x2422,0xd01,0x2000,
 	0xa19,0xb000,0x87,0xb000,0xb8,0xb000,0xf9,0x526,0xb000,0x13a,0xb000,0x17b,0xb000,0x1bc,0xd03,0x6dcc,0xa900,0x2892,
@@ -1425,7 +1433,6 @@ static const unsigned short phoneme_index[21658] = {
 	0x2f9b,0xb000,0xe85a,0xb002,0x82b,0xb002,0x135,0xb002,0x88c,0xb001,0xf7b7,0xb002,0x8cd,0xb001,0x304,0xa100,0x319,
 	0x1ad,0xbcee,0xa200,0x1292,0x1ad,0xbd6e,0xc3c1,0x36b3,0x2b5c,0x6802,0x6002,0x140,0x9100,0xce,0x1,
 };
-static PHONEME_LIST phoneme_list[N_PHONEME_LIST+1];
 static PHONEME_TAB phoneme_tab[N_PHONEME_TAB] = {
 	{0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0},
 	{0x15f, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0},
@@ -1684,7 +1691,6 @@ static PHONEME_TAB phoneme_tab[N_PHONEME_TAB] = {
 	{0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0},
 	{0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0},
 };
-static char source[N_TR_SOURCE+40]; // extra space for embedded command & voice change info at end
 static const char stress_phonemes[] = {
 	phonSTRESS_D, phonSTRESS_U, phonSTRESS_2, phonSTRESS_3,
 	phonSTRESS_P, phonSTRESS_P2, phonSTRESS_TONIC
@@ -1709,7 +1715,6 @@ static const char transpose_map_latin[] = {
 	 0, 53,  0, 54,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0,  0, // 0x160
 	 0,  0,  0,  0,  0,  0,  0,  0,  0,  0, 55,  0, 56,  0, 57,  0, // 0x170
 };
-static char word_phonemes[N_WORD_PHONEMES]; // a word translated into phoneme codes
 
 static void strncpy0(char *to, const char *from, int size) {
 	// strcpy with limit, ensures a zero terminator
@@ -1717,13 +1722,13 @@ static void strncpy0(char *to, const char *from, int size) {
 	to[size-1] = 0;
 }
 
-static void SetLetterBits(int group, const char *string) {
+static void SetLetterBits(Translator *tr, int group, const char *string) {
 	int bits;
 	unsigned char c;
 
 	bits = (1L << group);
 	while ((c = *string++) != 0)
-		letter_bits[c] |= bits;
+		tr->letter_bits[c] |= bits;
 }
 
 static int utf8_out(unsigned int c, char *buf) {
@@ -1935,7 +1940,7 @@ static int HashDictionary(const char *string) {
 	return (hash+chars) & 0x3ff; // a 10 bit hash code
 }
 
-static const char *LookupDict2(const char *word, const char *word2, char *phonetic, unsigned int *flags) {
+static const char *LookupDict2(Translator *tr, const char *word, const char *word2, char *phonetic, unsigned int *flags) {
 	const char *p;
 	const char *next;
 	int hash;
@@ -1949,7 +1954,7 @@ static const char *LookupDict2(const char *word, const char *word2, char *phonet
 	wlen = TransposeAlphabet(word_buf); // bit 6 indicates compressed characters
 	word = word_buf;
 	hash = HashDictionary(word);
-	p = dict_hashtab[hash];
+	p = tr->dict_hashtab[hash];
 	// Find the first entry in the list for this hash value which matches.
 	// This corresponds to the last matching entry in the *_list file.
 
@@ -1978,6 +1983,23 @@ static const char *LookupDict2(const char *word, const char *word2, char *phonet
 			strcpy(phonetic, p);
 			p += (phoneme_len + 1);
 		}
+
+		// examine the flags which follow the phoneme string, skip the entry if its
+		// condition fails or it matches more than one word (flags 81 to 90)
+		bool skip = false;
+		while (!skip && (p < next)) {
+			int flag = *(const unsigned char *)p++;
+			if (flag >= 132)
+				skip = (tr->dict_condition & (1L << (flag-132))) == 0;
+			else if (flag >= 100)
+				skip = (tr->dict_condition & (1L << (flag-100))) != 0;
+			else if (flag > 80)
+				skip = true;
+		}
+		if (skip) {
+			p = next;
+			continue;
+		}
 		if (flags != NULL) {
 			flags[0] = dictionary_flags | FLAG_FOUND_ATTRIBUTES;
 		}
@@ -1990,11 +2012,11 @@ static const char *LookupDict2(const char *word, const char *word2, char *phonet
 	return 0;
 }
 
-static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flags);
+static int LookupDictList(Translator *tr, const char **wordptr, char *ph_out, unsigned int *flags);
 
-static int Lookup(const char *word, char *ph_out) {
+static int Lookup(Translator *tr, const char *word, char *ph_out) {
 	unsigned int flags[2] = { 0 };
-	if (LookupDictList(&word, ph_out, flags) != 0)
+	if (LookupDictList(tr, &word, ph_out, flags) != 0)
 		return flags[0];
 	return 0;
 }
@@ -2007,16 +2029,16 @@ static int LetterGroupNo(const char *rule) {
 	return groupNo;
 }
 
-static int IsLetter(int letter, int group) {
+static int IsLetter(Translator *tr, int letter, int group) {
 	if (group > 7)
 		return 0;
 	if ((letter >= 0) && (letter < 0x100))
-		return letter_bits[letter] & (1L << group);
+		return tr->letter_bits[letter] & (1L << group);
 
 	return 0;
 }
 
-static void MatchRule(char *word[], int group_length, const char *rule, MatchRecord *match_out, int word_flags) {
+static void MatchRule(Translator *tr, char *word[], int group_length, const char *rule, MatchRecord *match_out, int word_flags) {
 	unsigned char rb;     // current instuction from rule
 	unsigned char letter; // current letter from input word, single byte
 	int letter_w;         // current letter, wide character
@@ -2110,11 +2132,11 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 
 					if (condition_num >= 32) {
 						// allow the rule only if the condition number is NOT set
-						if ((dict_condition & (1L << (condition_num-32))) != 0)
+						if ((tr->dict_condition & (1L << (condition_num-32))) != 0)
 							failed = 1;
 					} else {
 						// allow the rule only if the condition number is set
-						if ((dict_condition & (1L << condition_num)) == 0)
+						if ((tr->dict_condition & (1L << condition_num)) == 0)
 							failed = 1;
 					}
 
@@ -2160,7 +2182,7 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 				{
 				case RULE_LETTERGP:
 					letter_group = LetterGroupNo(rule++);
-					if (IsLetter(letter_w, letter_group)) {
+					if (IsLetter(tr, letter_w, letter_group)) {
 						post_ptr += letter_xbytes;
 					} else
 						failed = 1;
@@ -2215,13 +2237,13 @@ static void MatchRule(char *word[], int group_length, const char *rule, MatchRec
 				{
 				case RULE_LETTERGP:
 					letter_group = LetterGroupNo(rule++);
-					if (IsLetter(letter_w, letter_group)) {
+					if (IsLetter(tr, letter_w, letter_group)) {
 						pre_ptr -= letter_xbytes;
 					} else
 						failed = 1;
 					break;
 				case RULE_NOTVOWEL:
-					if (!IsLetter(letter_w, 0)) {
+					if (!IsLetter(tr, letter_w, 0)) {
 						add_points = (20-distance_left);
 						pre_ptr -= letter_xbytes;
 					} else
@@ -2325,7 +2347,7 @@ static void AppendPhonemes(char *string, int size, const char *ph) {
 		strcat(string, ph);
 }
 
-static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_phonemes, int word_flags) {
+static int TranslateRules(Translator *tr, char *p_start, char *phonemes, int ph_size, char *end_phonemes, int word_flags) {
 	/* Translate a word bounded by space characters
 	   Append the result to 'phonemes' and any standard prefix/suffix in 'end_phonemes' */
 	unsigned char c, c2;
@@ -2342,7 +2364,7 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 	MatchRecord match1 = { 0 };
 	MatchRecord match2 = { 0 };
 	char word_copy[N_WORD_BYTES];
-	if (data_dictrules == NULL)
+	if (tr->data_dictrules == NULL)
 		return 0;
 	for (ix = 0; ix < (N_WORD_BYTES-1);) {
 		c = p_start[ix];
@@ -2359,25 +2381,25 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 		int wc_bytes = utf8_in2(&wc, p);
 		if (IsAlpha(wc))
 			any_alpha++;
-		int n = groups2_count[c];
+		int n = tr->groups2_count[c];
 		found = 0;
 		if (!found && (n > 0)) {
 			// there are some 2 byte chains for this initial letter
 			c2 = p[1];
 			c12 = c + (c2 << 8); // 2 characters
 
-			g1 = groups2_start[c];
+			g1 = tr->groups2_start[c];
 			for (g = g1; g < (g1+n); g++) {
-				if (groups2_name[g] == c12) {
+				if (tr->groups2_name[g] == c12) {
 					found = 1;
 
 					p2 = p;
-					MatchRule(&p2, 2, groups2[g], &match2, word_flags);
+					MatchRule(tr, &p2, 2, tr->groups2[g], &match2, word_flags);
 					if (match2.points > 0)
 						match2.points += 35; // to acount for 2 letters matching
 
 					// now see whether single letter chain gives a better match ?
-					MatchRule(&p, 1, groups1[c], &match1, word_flags);
+					MatchRule(tr, &p, 1, tr->groups1[c], &match1, word_flags);
 
 					if (match2.points >= match1.points) {
 						// use match from the 2-letter group
@@ -2390,11 +2412,11 @@ static int TranslateRules(char *p_start, char *phonemes, int ph_size, char *end_
 
 		if (!found) {
 			// alphabetic, single letter chain
-			if (groups1[c] != NULL)
-				MatchRule(&p, 1, groups1[c], &match1, word_flags);
+			if (tr->groups1[c] != NULL)
+				MatchRule(tr, &p, 1, tr->groups1[c], &match1, word_flags);
 			else {
 				// no group for this letter, use default group
-				MatchRule(&p, 0, groups1[0], &match1, word_flags);
+				MatchRule(tr, &p, 0, tr->groups1[0], &match1, word_flags);
 
 				if (match1.points == 0) {
 					n = utf8_in2(&letter, p-1)-1;
@@ -2647,7 +2669,7 @@ static void SetWordStress(char *output, unsigned int *dictionary_flags, int toni
 	return;
 }
 
-static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
+static void LookupLetter(Translator *tr, unsigned int letter, int next_byte, char *ph_buf1) {
 	// control, bit 0:  not the first letter of a word
 
 	int len;
@@ -2661,11 +2683,11 @@ static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
 
 	if (next_byte == -1) {
 		// speaking normal text, not individual characters
-		if (Lookup(&single_letter[2], ph_buf1) != 0)
+		if (Lookup(tr, &single_letter[2], ph_buf1) != 0)
 			return;
 
 		single_letter[1] = '_';
-		if (Lookup(&single_letter[1], ph_buf3) != 0)
+		if (Lookup(tr, &single_letter[1], ph_buf3) != 0)
 			return; // the character is specified as _* so ignore it when speaking normal text
 		return;
 	}
@@ -2673,7 +2695,7 @@ static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
 	if ((letter <= 32) || iswspace((wint_t) letter)) {
 		// lookup space as _&32 etc.
 		sprintf(&single_letter[1], "_#%d ", letter);
-		Lookup(&single_letter[1], ph_buf1);
+		Lookup(tr, &single_letter[1], ph_buf1);
 		return;
 	}
 	single_letter[3+len] = (char) next_byte; // follow by space-space if the end of the word, or space-31
@@ -2683,10 +2705,10 @@ static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
 	// if the $accent flag is set for this letter, use the accents table (below)
 	dict_flags[1] = 0;
 
-	if (Lookup(&single_letter[1], ph_buf3) == 0) {
+	if (Lookup(tr, &single_letter[1], ph_buf3) == 0) {
 		single_letter[1] = ' ';
-		if (Lookup(&single_letter[2], ph_buf3) == 0)
-			TranslateRules(&single_letter[2], ph_buf3, sizeof(ph_buf3), NULL, FLAG_NO_TRACE);
+		if (Lookup(tr, &single_letter[2], ph_buf3) == 0)
+			TranslateRules(tr, &single_letter[2], ph_buf3, sizeof(ph_buf3), NULL, FLAG_NO_TRACE);
 	}
 	strcpy(ph_buf1, ph_buf3);
 	if ((ph_buf1[0] == 0) || (ph_buf1[0] == phonSWITCH))
@@ -2697,7 +2719,7 @@ static void LookupLetter(unsigned int letter, int next_byte, char *ph_buf1) {
 	SetWordStress(ph_buf1, dict_flags, -1);
 }
 
-static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flags) {
+static int LookupDictList(Translator *tr, const char **wordptr, char *ph_out, unsigned int *flags) {
 	int length;
 	const char *found;
 	const char *word1;
@@ -2728,7 +2750,7 @@ static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flag
 	}
 	word[length] = 0;
 
-	found = LookupDict2(word, word1, ph_out, flags);
+	found = LookupDict2(tr, word, word1, ph_out, flags);
 	if (found == 0 && length >= 2) {
 		ph_out[0] = 0;
 	}
@@ -2741,7 +2763,7 @@ static int LookupDictList(const char **wordptr, char *ph_out, unsigned int *flag
 	return 0;
 }
 
-static int TranslateLetter(char *word, char *phonemes) {
+static int TranslateLetter(Translator *tr, char *word, char *phonemes) {
 	int n_bytes;
 	int letter;
 	int len;
@@ -2749,7 +2771,7 @@ static int TranslateLetter(char *word, char *phonemes) {
 	char ph_buf2[sizeof(ph_buf) + 1];
 	ph_buf[0] = 0;
 	n_bytes = utf8_in2(&letter, word);
-	LookupLetter(letter, word[n_bytes], ph_buf);
+	LookupLetter(tr, letter, word[n_bytes], ph_buf);
 	len = (int) strlen(phonemes);
 
 	sprintf(ph_buf2, "%c%s", 0xff, ph_buf); // the 0xff marker will be removed or replaced in SetSpellingStress()
@@ -2794,7 +2816,7 @@ static void SetSpellingStress(char *phonemes, int control, int n_chars) {
 	*phonemes = 0;
 }
 
-static int Unpronouncable(char *word, int posn) {
+static int Unpronouncable(Translator *tr, char *word, int posn) {
 	int c;
 	int vowel_posn = 9;
 	int index;
@@ -2814,7 +2836,7 @@ static int Unpronouncable(char *word, int posn) {
 			break; // "tv'" but not "l'"
 		count++;
 
-		if (IsLetter(c, LETTERGP_VOWEL2)) {
+		if (IsLetter(tr, c, LETTERGP_VOWEL2)) {
 			vowel_posn = count; // position of the first vowel
 			break;
 		}
@@ -2828,7 +2850,7 @@ static int Unpronouncable(char *word, int posn) {
 	return 0;
 }
 
-static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes[], size_t size_word_phonemes) {
+static int TranslateWord3(Translator *tr, char *word_start, WORD_TAB *wtab, char a_word_phonemes[], size_t size_word_phonemes) {
 	// word1 is terminated by space (0x20) character
 
 	const char *word1;
@@ -2874,7 +2896,7 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 	// try an initial lookup in the dictionary list, we may find a pronunciation specified, or
 	// we may just find some flags
 	if (!found)
-		found = LookupDictList(&word1, phonemes, dictionary_flags);   // the original word
+		found = LookupDictList(tr, &word1, phonemes, dictionary_flags);   // the original word
 	if (found == false) {
 		// word's pronunciation is not given in the dictionary list, although
 		// dictionary_flags may have ben set there
@@ -2886,10 +2908,10 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 		length = 999;
 		wordx = (char *) word1;
 
-		while (((length < 3) && (length > 0)) || (word_length > 1 && Unpronouncable(wordx, posn))) {
+		while (((length < 3) && (length > 0)) || (word_length > 1 && Unpronouncable(tr, wordx, posn))) {
 			if (wordx[0] == '\'')
 				break;
-			wordx += TranslateLetter(wordx, unpron_phonemes);
+			wordx += TranslateLetter(tr, wordx, unpron_phonemes);
 			posn++;
 			length = 0;
 			while (wordx[length] != ' ') length++;
@@ -2904,7 +2926,7 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 			}
 
 			// Translate the stem
-			TranslateRules(wordx, phonemes, N_WORD_PHONEMES, end_phonemes, wflags);
+			TranslateRules(tr, wordx, phonemes, N_WORD_PHONEMES, end_phonemes, wflags);
 			found = false;
 		}
 	}
@@ -2915,7 +2937,7 @@ static int TranslateWord3(char *word_start, WORD_TAB *wtab, char a_word_phonemes
 	return dictionary_flags[0];
 }
 
-static int TranslateWord2(char *word, WORD_TAB *wtab) {
+static int TranslateWord2(Translator *tr, char *word, WORD_TAB *wtab) {
 	int flags = 0;
 	int stress;
 	int next_stress;
@@ -2926,16 +2948,16 @@ static int TranslateWord2(char *word, WORD_TAB *wtab) {
 	int max_stress;
 	int prev_vowel = -1;
 	char word_copy[N_WORD_BYTES+1];
-	p = (unsigned char *)word_phonemes;
+	p = (unsigned char *)tr->word_phonemes;
 	int c2;
 	int ix = 0;
 	while (((c2 = word_copy[ix] = word[ix]) != ' ') && (c2 != 0) && (ix < N_WORD_BYTES)) ix++;
-	flags = TranslateWord3(word, wtab, word_phonemes, sizeof(word_phonemes));
+	flags = TranslateWord3(tr, word, wtab, tr->word_phonemes, sizeof(tr->word_phonemes));
 	stress = 0;
 	next_stress = 1;
 	srcix = 0;
 	max_stress = -1;
-	while (((ph_code = *p++) != 0) && (n_ph_list2 < N_PHONEME_LIST-3-2)) {
+	while (((ph_code = *p++) != 0) && (tr->n_ph_list2 < N_PHONEME_LIST-3-2)) {
 		if (ph_code == 255)
 			continue; // unknown phoneme
 
@@ -2954,44 +2976,44 @@ static int TranslateWord2(char *word, WORD_TAB *wtab) {
 				next_stress = ph->std_length;
 		} else if (ph_code == phonSYLLABIC) {
 			// mark the previous phoneme as a syllabic consonant
-			prev_vowel = n_ph_list2-1;
-			ph_list2[prev_vowel].synthflags |= SFLAG_SYLLABLE;
-			ph_list2[prev_vowel].stresslevel = (unsigned char) next_stress;
+			prev_vowel = tr->n_ph_list2-1;
+			tr->ph_list2[prev_vowel].synthflags |= SFLAG_SYLLABLE;
+			tr->ph_list2[prev_vowel].stresslevel = (unsigned char) next_stress;
 		} else {
-			ph_list2[n_ph_list2].phcode = ph_code;
-			ph_list2[n_ph_list2].synthflags = 0;
-			ph_list2[n_ph_list2].sourceix = (unsigned short) srcix;
+			tr->ph_list2[tr->n_ph_list2].phcode = ph_code;
+			tr->ph_list2[tr->n_ph_list2].synthflags = 0;
+			tr->ph_list2[tr->n_ph_list2].sourceix = (unsigned short) srcix;
 			srcix = 0;
 
 			if (ph->type == phVOWEL) {
 				stress = next_stress;
 				next_stress = 1; // default is 'unstressed'
-				if ((prev_vowel >= 0) && (n_ph_list2-1) != prev_vowel)
-					ph_list2[n_ph_list2-1].stresslevel = (unsigned char) stress; // set stress for previous consonant
+				if ((prev_vowel >= 0) && (tr->n_ph_list2-1) != prev_vowel)
+					tr->ph_list2[tr->n_ph_list2-1].stresslevel = (unsigned char) stress; // set stress for previous consonant
 
-				ph_list2[n_ph_list2].synthflags |= SFLAG_SYLLABLE;
-				prev_vowel = n_ph_list2;
+				tr->ph_list2[tr->n_ph_list2].synthflags |= SFLAG_SYLLABLE;
+				prev_vowel = tr->n_ph_list2;
 
 				if (stress > max_stress)
 					max_stress = stress;
 			}
 
-			ph_list2[n_ph_list2].stresslevel = (unsigned char) stress;
-			n_ph_list2++;
+			tr->ph_list2[tr->n_ph_list2].stresslevel = (unsigned char) stress;
+			tr->n_ph_list2++;
 		}
 	}
 	return flags;
 }
 
-static int SubstitutePhonemes(PHONEME_LIST *plist_out) {
+static int SubstitutePhonemes(Translator *tr, PHONEME_LIST *plist_out) {
 	// Copy the phonemes list and perform any substitutions that are required for the
 	// current voice
 	int ix;
 	int n_plist_out = 0;
 	PHONEME_LIST2 *plist2;
 	int deleted_sourceix = -1;
-	for (ix = 0; (ix < n_ph_list2) && (n_plist_out < N_PHONEME_LIST); ix++) {
-		plist2 = &ph_list2[ix];
+	for (ix = 0; (ix < tr->n_ph_list2) && (n_plist_out < N_PHONEME_LIST); ix++) {
+		plist2 = &tr->ph_list2[ix];
 		if (deleted_sourceix != -1) {
 			plist2->sourceix = (unsigned short) deleted_sourceix;
 			deleted_sourceix = -1;
@@ -3260,7 +3282,7 @@ static char *WritePhMnemonic(char *phon_out, PHONEME_TAB *ph, PHONEME_LIST *plis
 	return phon_out;
 }
 
-static const char *GetTranslatedPhonemeString() {
+static const char *GetTranslatedPhonemeString(Translator *tr) {
 	int ix;
 	unsigned int len;
 	int phon_out_ix = 0;
@@ -3274,17 +3296,17 @@ static const char *GetTranslatedPhonemeString() {
 	char phon_buf[30];
 	char phon_buf2[30];
 	PHONEME_LIST *plist;
-	if (phon_out_buf == NULL) {
-		phon_out_size = N_PHON_OUT;
-		if ((phon_out_buf = (char *)malloc(phon_out_size)) == NULL) {
-			phon_out_size = 0;
+	if (tr->phon_out_buf == NULL) {
+		tr->phon_out_size = N_PHON_OUT;
+		if ((tr->phon_out_buf = (char *)malloc(tr->phon_out_size)) == NULL) {
+			tr->phon_out_size = 0;
 			return "";
 		}
 	}
 	use_tie = 0;
-	for (ix = 1; ix < (n_phoneme_list-2); ix++) {
+	for (ix = 1; ix < (tr->n_phoneme_list-2); ix++) {
 		buf = phon_buf;
-		plist = &phoneme_list[ix];
+		plist = &tr->phoneme_list[ix];
 
 		WritePhMnemonic(phon_buf2, plist->ph, plist, &flags);
 		if (plist->synthflags & SFLAG_SYLLABLE) {
@@ -3320,31 +3342,31 @@ static const char *GetTranslatedPhonemeString() {
 		}
 
 		len = (unsigned int) (buf - phon_buf);
-		if ((phon_out_ix + len) >= phon_out_size) {
+		if ((phon_out_ix + len) >= tr->phon_out_size) {
 			// enlarge the phoneme buffer
-			phon_out_size = phon_out_ix + len + N_PHON_OUT;
-			char *new_phon_out_buf = (char *)realloc(phon_out_buf, phon_out_size);
+			tr->phon_out_size = phon_out_ix + len + N_PHON_OUT;
+			char *new_phon_out_buf = (char *)realloc(tr->phon_out_buf, tr->phon_out_size);
 			if (new_phon_out_buf == NULL) {
-				phon_out_size = 0;
+				tr->phon_out_size = 0;
 				return "";
 			} else
-				phon_out_buf = new_phon_out_buf;
+				tr->phon_out_buf = new_phon_out_buf;
 		}
 
 		phon_buf[len] = 0;
-		strcpy(&phon_out_buf[phon_out_ix], phon_buf);
+		strcpy(&tr->phon_out_buf[phon_out_ix], phon_buf);
 		phon_out_ix += len;
 	}
 
-	if (!phon_out_buf)
+	if (!tr->phon_out_buf)
 		return "";
 
-	phon_out_buf[phon_out_ix] = 0;
+	tr->phon_out_buf[phon_out_ix] = 0;
 
-	return phon_out_buf;
+	return tr->phon_out_buf;
 }
 
-void Initialize(const char *data_dictlist) {
+static bool Initialize(Translator *tr, const char *data_dictlist) {
 	int hash;
 	int *pw;
 	int length;
@@ -3353,41 +3375,41 @@ void Initialize(const char *data_dictlist) {
 	const char *p_name;
 	unsigned char c, c2;
 
-	dict_condition = 0;
-	data_dictrules = NULL; // language_1   translation rules file
-	memset(letter_bits, 0, sizeof(letter_bits));
+	tr->dict_condition = 0;
+	tr->data_dictrules = NULL; // language_1   translation rules file
+	memset(tr->letter_bits, 0, sizeof(tr->letter_bits));
 	// 0-6 sets of characters matched by A B C H F G Y  in pronunciation rules
 	// these may be set differently for different languages
-	SetLetterBits(0, "aeiou"); // A  vowels, except y
-	SetLetterBits(1, "bcdfgjklmnpqstvxz"); // B  hard consonants, excluding h,r,w
-	SetLetterBits(2, "bcdfghjklmnpqrstvwxz"); // C  all consonants
-	SetLetterBits(3, "hlmnr"); // H  'soft' consonants
-	SetLetterBits(4, "cfhkpqstx"); // F  voiceless consonants
-	SetLetterBits(5, "bdgjlmnrvwyz"); // G voiced
-	SetLetterBits(6, "eiy"); // Letter group Y, front vowels
-	SetLetterBits(7, "aeiouy"); // vowels, including y
+	SetLetterBits(tr, 0, "aeiou"); // A  vowels, except y
+	SetLetterBits(tr, 1, "bcdfgjklmnpqstvxz"); // B  hard consonants, excluding h,r,w
+	SetLetterBits(tr, 2, "bcdfghjklmnpqrstvwxz"); // C  all consonants
+	SetLetterBits(tr, 3, "hlmnr"); // H  'soft' consonants
+	SetLetterBits(tr, 4, "cfhkpqstx"); // F  voiceless consonants
+	SetLetterBits(tr, 5, "bdgjlmnrvwyz"); // G voiced
+	SetLetterBits(tr, 6, "eiy"); // Letter group Y, front vowels
+	SetLetterBits(tr, 7, "aeiouy"); // vowels, including y
 	pw = (int *)data_dictlist;
 	length = pw[1];
 	if ((pw[0] != N_HASH_DICT) ||
 	    (length <= 0) || (length > 0x8000000)) {
 		fprintf(stderr, "Bad data: 'en_dict' (%x length=%x)\n", pw[0], length);
-		return;
+		return false;
 	}
-	data_dictrules = &data_dictlist[length];
+	tr->data_dictrules = &data_dictlist[length];
 
-	n_groups2 = 0;
+	tr->n_groups2 = 0;
 	for (ix = 0; ix < 256; ix++) {
-		groups1[ix] = NULL;
-		groups2_count[ix] = 0;
-		groups2_start[ix] = 255; // indicates "not set"
+		tr->groups1[ix] = NULL;
+		tr->groups2_count[ix] = 0;
+		tr->groups2_start[ix] = 255; // indicates "not set"
 	}
-	p = data_dictrules;
+	p = tr->data_dictrules;
 	// If there are no rules in the dictionary, compile_dictrules will not
 	// write a RULE_GROUP_START (written in the for loop), but will write
 	// a RULE_GROUP_END.
 	if (*p != RULE_GROUP_END) while (*p != 0) {
 		if (*p != RULE_GROUP_START) {
-			fprintf(stderr, "Bad rules data in 'en_dict' at 0x%x (%c)\n", (unsigned int)(p - data_dictrules), *p);
+			fprintf(stderr, "Bad rules data in 'en_dict' at 0x%x (%c)\n", (unsigned int)(p - tr->data_dictrules), *p);
 			break;
 		}
 		p++;
@@ -3398,15 +3420,15 @@ void Initialize(const char *data_dictlist) {
 
 		p += (length + 1);
 		if (length == 1)
-			groups1[c] = p;
+			tr->groups1[c] = p;
 		else if (length == 0)
-			groups1[0] = p;
+			tr->groups1[0] = p;
 		else {
-			if (groups2_start[c] == 255)
-				groups2_start[c] = (unsigned char) n_groups2;
-			groups2_count[c]++;
-			groups2[n_groups2] = p;
-			groups2_name[n_groups2++] = (c + (c2 << 8));
+			if (tr->groups2_start[c] == 255)
+				tr->groups2_start[c] = (unsigned char) tr->n_groups2;
+			tr->groups2_count[c]++;
+			tr->groups2[tr->n_groups2] = p;
+			tr->groups2_name[tr->n_groups2++] = (c + (c2 << 8));
 		}
 
 		// skip over all the rules in this group
@@ -3418,14 +3440,37 @@ void Initialize(const char *data_dictlist) {
 	// set up hash table for data_dictlist
 	p = &data_dictlist[8];
 	for (hash = 0; hash < N_HASH_DICT; hash++) {
-		dict_hashtab[hash] = p;
+		tr->dict_hashtab[hash] = p;
 		while ((length = *(uint8_t *)p) != 0)
 			p += length;
 		p++; // skip over the zero which terminates the list for this hash value
 	}
+	return true;
 }
 
-const char *TextToPhonemes(const char *textptr) {
+// Create a translator for the dictionary data, which must stay valid until DestroyTranslator().
+// Returns NULL if the data is bad or memory runs out.
+Translator *CreateTranslator(const char *data_dictlist) {
+	Translator *tr = (Translator *)calloc(1, sizeof(Translator));
+	if (tr == NULL)
+		return NULL;
+	if (!Initialize(tr, data_dictlist)) {
+		free(tr);
+		return NULL;
+	}
+	return tr;
+}
+
+void DestroyTranslator(Translator *tr) {
+	if (tr == NULL)
+		return;
+	free(tr->phon_out_buf);
+	free(tr);
+}
+
+// The result is owned by the translator and valid until its next call.
+
+const char *TextToPhonemes(Translator *tr, const char *textptr) {
 	int ix;
 	int c;
 	unsigned int source_index = 0;
@@ -3449,16 +3494,16 @@ const char *TextToPhonemes(const char *textptr) {
 	PHONEME_LIST2 *plist2;
 	PHONEME_LIST worddata;
 
-	strcpy(source, textptr);
-	memset(&ph_list2[0], 0, sizeof(ph_list2[0]));
-	ph_list2[0].phcode = phonPAUSE_SHORT;
+	strncpy0(tr->source, textptr, sizeof(tr->source));
+	memset(&tr->ph_list2[0], 0, sizeof(tr->ph_list2[0]));
+	tr->ph_list2[0].phcode = phonPAUSE_SHORT;
 
-	n_ph_list2 = 1;
+	tr->n_ph_list2 = 1;
 	ix = 0;
 	word.start = (unsigned short) ix;
 	word.flags = 0;
 	while (!finished && (ix < (int)sizeof(sbuf) - 1)) {
-		source_index += utf8_in2(&c, &source[source_index]);
+		source_index += utf8_in2(&c, &tr->source[source_index]);
 		if (c == 0) {
 			finished = true;
 			c = ' ';
@@ -3466,14 +3511,14 @@ const char *TextToPhonemes(const char *textptr) {
 		ix += utf8_out(c, &sbuf[ix]);
 	}
 	sbuf[ix] = 0;
-	TranslateWord2(&sbuf[0], &word);
-	n_ph_list2 += 2;
+	TranslateWord2(tr, &sbuf[0], &word);
+	tr->n_ph_list2 += 2;
 	memset(&worddata, 0, sizeof(worddata));
-	plist2 = ph_list2;
-	end_sourceix = plist2[n_ph_list2-1].sourceix;
+	plist2 = tr->ph_list2;
+	end_sourceix = plist2[tr->n_ph_list2-1].sourceix;
 	// is the last word of the clause unstressed ?
 	max_stress = 0;
-	for (j = n_ph_list2-3; j >= 0; j--) {
+	for (j = tr->n_ph_list2-3; j >= 0; j--) {
 		// start with the last phoneme (before the terminating pauses) and move backwards
 		if ((plist2[j].stresslevel & 0x7f) > max_stress)
 			max_stress = plist2[j].stresslevel & 0x7f;
@@ -3492,7 +3537,7 @@ const char *TextToPhonemes(const char *textptr) {
 	// look for switch of phoneme tables
 	delete_count = 0;
 	int deleted_sourceix = -1;
-	for (j = 0; j < n_ph_list2; j++) {
+	for (j = 0; j < tr->n_ph_list2; j++) {
 		if (delete_count > 0) {
 			memcpy(&plist2[j-delete_count], &plist2[j], sizeof(plist2[0]));
 			if (deleted_sourceix != -1) {
@@ -3514,8 +3559,8 @@ const char *TextToPhonemes(const char *textptr) {
 			}
 		}
 	}
-	n_ph_list2 -= delete_count;
-	n_ph_list3 = SubstitutePhonemes(ph_list3) - 2;
+	tr->n_ph_list2 -= delete_count;
+	n_ph_list3 = SubstitutePhonemes(tr, ph_list3) - 2;
 	for (j = 0; (j < n_ph_list3) && (ix < N_PHONEME_LIST-3);) {
 		if (ph_list3[j].sourceix) {
 			// start of a word
@@ -3569,29 +3614,29 @@ const char *TextToPhonemes(const char *textptr) {
 		}
 		plist3[2].ph = &phoneme_tab[plist3[2].phcode];
 		if (deleted == false) {
-			phoneme_list[ix].ph = ph;
-			phoneme_list[ix].type = ph->type;
-			phoneme_list[ix].synthflags = plist3->synthflags;
-			phoneme_list[ix].stresslevel = plist3->stresslevel & 0xf;
-			phoneme_list[ix].wordstress = plist3->wordstress;
-			phoneme_list[ix].sourceix = 0;
-			phoneme_list[ix].phcode = ph->code;
+			tr->phoneme_list[ix].ph = ph;
+			tr->phoneme_list[ix].type = ph->type;
+			tr->phoneme_list[ix].synthflags = plist3->synthflags;
+			tr->phoneme_list[ix].stresslevel = plist3->stresslevel & 0xf;
+			tr->phoneme_list[ix].wordstress = plist3->wordstress;
+			tr->phoneme_list[ix].sourceix = 0;
+			tr->phoneme_list[ix].phcode = ph->code;
 
 			if (plist3->sourceix != 0) {
-				phoneme_list[ix].sourceix = plist3->sourceix;
+				tr->phoneme_list[ix].sourceix = plist3->sourceix;
 			}
 			ix++;
 		}
 	}
-	phoneme_list[ix].phcode = phonPAUSE;
-	phoneme_list[ix].sourceix = (unsigned short) end_sourceix;
-	phoneme_list[ix].synthflags = 0;
-	phoneme_list[ix++].ph = &phoneme_tab[phonPAUSE];
-	phoneme_list[ix].phcode = phonPAUSE;
-	phoneme_list[ix].length = 0;
-	phoneme_list[ix].sourceix = 0;
-	phoneme_list[ix].synthflags = 0;
-	phoneme_list[ix++].ph = &phoneme_tab[phonPAUSE_SHORT];
-	n_phoneme_list = ix;
-	return GetTranslatedPhonemeString();
+	tr->phoneme_list[ix].phcode = phonPAUSE;
+	tr->phoneme_list[ix].sourceix = (unsigned short) end_sourceix;
+	tr->phoneme_list[ix].synthflags = 0;
+	tr->phoneme_list[ix++].ph = &phoneme_tab[phonPAUSE];
+	tr->phoneme_list[ix].phcode = phonPAUSE;
+	tr->phoneme_list[ix].length = 0;
+	tr->phoneme_list[ix].sourceix = 0;
+	tr->phoneme_list[ix].synthflags = 0;
+	tr->phoneme_list[ix++].ph = &phoneme_tab[phonPAUSE_SHORT];
+	tr->n_phoneme_list = ix;
+	return GetTranslatedPhonemeString(tr);
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::KokoroError;
+use crate::{EnglishDialect, KokoroError, Language};
 
 //noinspection SpellCheckingInspection
 #[derive(Copy, Clone, Debug)]
@@ -329,6 +329,21 @@ impl Voice {
         }
     }
 
+    /// 语音的语言，由名称的第一个字母决定：`a`为美式英语，`b`为英式英语，`j`为日语，`e`为西班牙语，`i`为意大利语，`p`为巴西葡萄牙语，`f`为法语，`h`为印地语，`z`为普通话，其他语言暂时按美式英语处理
+    pub(super) fn language(&self) -> Language {
+        match self.get_name().chars().next() {
+            Some('b') => Language::English(EnglishDialect::British),
+            Some('j') => Language::Japanese,
+            Some('e') => Language::Spanish,
+            Some('i') => Language::Italian,
+            Some('p') => Language::Portuguese,
+            Some('f') => Language::French,
+            Some('h') => Language::Hindi,
+            Some('z') => Language::Mandarin,
+            _ => Language::English(EnglishDialect::American),
+        }
+    }
+
     pub(super) fn is_v10_supported(&self) -> bool {
         matches!(
             self,
@@ -0,0 +1,610 @@
+use {
+    crate::SAMPLE_RATE,
+    futures::Stream,
+    pin_project::pin_project,
+    std::{
+        pin::Pin,
+        sync::Arc,
+        task::{Context, Poll},
+        time::Duration,
+    },
+};
+
+/// 每个码片占用的样本数，码片速率为4kHz，能够经受8kHz的降采样
+const CHIP_LEN: usize = 6;
+/// 伪随机序列的周期（码片数）
+const SEQUENCE_LEN: usize = 512;
+/// 检测时估计局部电平的半窗长（码片数）
+const LEVEL_SPAN: usize = 16;
+/// 默认的水印强度，相对于语音包络约-30dB
+const DEFAULT_STRENGTH: f32 = 0.03;
+/// 默认的检测阈值（标准分数）
+const DEFAULT_THRESHOLD: f32 = 7.;
+/// 负载的符号数，每个符号是一个字节，由对应序列相对同步序列的循环移位表示
+const PAYLOAD_SYMBOLS: usize = 2;
+/// 同步序列与每个负载序列的幅度，能量之和为1
+const SYNC_GAIN: f32 = 0.8;
+const PAYLOAD_GAIN: f32 = 0.424;
+
+/// 由密钥生成±1伪随机序列
+fn sequence(key: &[u8]) -> Arc<[f32]> {
+    // FNV-1a，保证不同平台和版本得到相同的序列
+    let mut state = key.iter().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ *b as u64).wrapping_mul(0x100000001b3)
+    });
+    (0..SEQUENCE_LEN)
+        .map(|_| {
+            // xorshift64*
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            if state.wrapping_mul(0x2545f4914f6cdd1d) >> 63 == 0 {
+                1.
+            } else {
+                -1.
+            }
+        })
+        .collect()
+}
+
+/// 标准正态分布的右尾概率
+fn normal_tail(z: f64) -> f64 {
+    // Abramowitz-Stegun 7.1.26
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1. / (1. + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erfc = poly * (-x * x).exp();
+    if z >= 0. { erfc / 2. } else { 1. - erfc / 2. }
+}
+
+/// 音频水印
+///
+/// 该结构体用于在合成的语音中嵌入由密钥派生的扩频水印，并在之后检测音频中是否带有该水印；水印可以附带16位负载，检测时一并解出。水印的幅度跟随语音包络，在静音处不会嵌入，人耳难以察觉；同时水印能量集中在4kHz以下，可以经受重采样、μ律或Opus编码等处理。
+#[derive(Clone, Debug)]
+pub struct Watermark {
+    sequence: Arc<[f32]>,
+    // 承载负载的序列，与同步序列近似正交
+    payload_sequences: [Arc<[f32]>; PAYLOAD_SYMBOLS],
+    payload: u16,
+    strength: f32,
+    threshold: f32,
+}
+
+impl Watermark {
+    /// 创建水印
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 密钥，只有使用相同密钥才能检测到水印。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::Watermark;
+    ///
+    /// let watermark = Watermark::new("my deployment");
+    /// let audio = (0..48000)
+    ///     .map(|i| (i as f32 * 0.05).sin() * 0.3)
+    ///     .collect::<Vec<_>>();
+    /// let marked = watermark.embed(&audio);
+    /// assert!(watermark.detect(&marked).present);
+    /// assert!(!watermark.detect(&audio).present);
+    /// ```
+    ///
+    pub fn new<K>(key: K) -> Self
+    where
+        K: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        Self {
+            sequence: sequence(key),
+            payload_sequences: std::array::from_fn(|i| {
+                sequence(&[key, b"\0payload", &[i as u8]].concat())
+            }),
+            payload: 0,
+            strength: DEFAULT_STRENGTH,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// 设置嵌入的负载
+    ///
+    /// 负载是随水印一起嵌入的16位数据，例如部署或用户的编号，检测时可以从音频中解出。负载使用由密钥派生的序列调制，不知道密钥时无法读取。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use kokoro_tts::Watermark;
+    ///
+    /// let watermark = Watermark::new("my deployment").with_payload(0xBEEF);
+    /// let audio = (0..72000)
+    ///     .map(|i| (i as f32 * 0.05).sin() * 0.3)
+    ///     .collect::<Vec<_>>();
+    /// let marked = watermark.embed(&audio);
+    /// assert_eq!(Some(0xBEEF), watermark.detect(&marked).payload);
+    /// ```
+    ///
+    pub fn with_payload(mut self, payload: u16) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    /// 设置水印强度
+    ///
+    /// 强度是水印相对于语音包络的幅度，默认为0.03。强度越大越耐受有损处理，但也越容易被听到。
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength.max(0.);
+        self
+    }
+
+    /// 设置检测阈值
+    ///
+    /// 阈值是检测统计量的标准分数，默认为7。阈值越低越容易检测到经过严重处理的水印，但误报也会增加。
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// 创建流式嵌入器
+    pub fn embedder(&self) -> WatermarkEmbedder {
+        let chips = (0..SEQUENCE_LEN)
+            .map(|k| {
+                SYNC_GAIN * self.sequence[k]
+                    + self
+                        .payload_sequences
+                        .iter()
+                        .enumerate()
+                        .map(|(i, j)| {
+                            let shift = (self.payload >> (8 * i)) as u8 as usize;
+                            PAYLOAD_GAIN * j[(k + SEQUENCE_LEN - shift) % SEQUENCE_LEN]
+                        })
+                        .sum::<f32>()
+            })
+            .collect::<Vec<_>>();
+        // 归一化到单位能量，使水印强度与负载无关
+        let rms = (chips.iter().map(|i| i * i).sum::<f32>() / SEQUENCE_LEN as f32).sqrt();
+        let chips = chips.iter().map(|i| i / rms).collect();
+        WatermarkEmbedder {
+            chips,
+            strength: self.strength,
+            position: 0,
+            envelope: 0.,
+        }
+    }
+
+    /// 对一段完整的音频嵌入水印
+    ///
+    /// # 参数
+    ///
+    /// * `audio` - 要处理的音频数据，采样率应为`SAMPLE_RATE`。
+    pub fn embed(&self, audio: &[f32]) -> Vec<f32> {
+        let mut audio = audio.to_vec();
+        self.embedder().process(&mut audio);
+        audio
+    }
+
+    /// 检测音频中是否带有水印
+    ///
+    /// 检测不需要音频从头开始，会自动搜索水印的起始位置。经过重采样的音频需要先转换回`SAMPLE_RATE`。音频越长，检测越可靠，建议至少两秒。
+    ///
+    /// # 参数
+    ///
+    /// * `audio` - 要检测的音频数据。
+    pub fn detect(&self, audio: &[f32]) -> Detection {
+        // 分数、起始位置和对应的折叠结果及相位
+        let mut best = (0., 0);
+        let (mut best_folded, mut best_phase) = (vec![0f64; SEQUENCE_LEN], 0);
+        let mut chips = Vec::with_capacity(audio.len() / CHIP_LEN);
+        let (mut diffs, mut levels) = (Vec::new(), Vec::new());
+        let mut folded = vec![0f64; SEQUENCE_LEN];
+        for offset in 0..CHIP_LEN {
+            chips.clear();
+            chips.extend(
+                audio[offset.min(audio.len())..]
+                    .chunks_exact(CHIP_LEN)
+                    .map(|i| i.iter().map(|j| *j as f64).sum::<f64>()),
+            );
+            if chips.len() < 3 {
+                continue;
+            }
+
+            // 与相邻码片之差可以去掉语音中的低频成分，相当于白化
+            diffs.clear();
+            diffs.extend(chips.windows(3).map(|i| i[1] - (i[0] + i[2]) / 2.));
+            // 按局部电平归一化，让每个码片的贡献相当，避免检测被少数响亮的片段主导
+            levels.clear();
+            levels.push(0.);
+            for d in &diffs {
+                levels.push(levels[levels.len() - 1] + d * d);
+            }
+            let floor = (levels[diffs.len()] / diffs.len() as f64).sqrt() * 1e-3;
+            folded.fill(0.);
+            for (c, d) in diffs.iter().enumerate() {
+                let (start, end) = (
+                    c.saturating_sub(LEVEL_SPAN),
+                    (c + LEVEL_SPAN + 1).min(diffs.len()),
+                );
+                let level = ((levels[end] - levels[start]) / (end - start) as f64).sqrt();
+                folded[(c + 1) % SEQUENCE_LEN] += d / (level + floor);
+            }
+            let energy = folded.iter().map(|i| i * i).sum::<f64>();
+            if energy <= 0. || floor <= 0. {
+                continue;
+            }
+
+            for phase in 0..SEQUENCE_LEN {
+                let correlation = folded
+                    .iter()
+                    .enumerate()
+                    .map(|(k, d)| d * self.sequence[(k + phase) % SEQUENCE_LEN] as f64)
+                    .sum::<f64>();
+                let score = correlation / energy.sqrt();
+                if score > best.0 {
+                    best = (
+                        score,
+                        offset + CHIP_LEN * ((SEQUENCE_LEN - phase) % SEQUENCE_LEN),
+                    );
+                    best_folded.copy_from_slice(&folded);
+                    best_phase = phase;
+                }
+            }
+        }
+
+        // 按搜索过的假设数量修正误报概率
+        let hypotheses = (CHIP_LEN * SEQUENCE_LEN) as f64;
+        let false_alarm = (normal_tail(best.0) * hypotheses).min(1.);
+        let present = best.0 >= self.threshold as f64;
+        // 按同步序列对齐并减去同步分量，每个负载序列相关值最大的循环移位即为对应的字节
+        let sync = best_folded
+            .iter()
+            .enumerate()
+            .map(|(k, d)| d * self.sequence[(k + best_phase) % SEQUENCE_LEN] as f64)
+            .sum::<f64>()
+            / SEQUENCE_LEN as f64;
+        let residual = (0..SEQUENCE_LEN)
+            .map(|chip| {
+                let d = best_folded[(chip + SEQUENCE_LEN - best_phase) % SEQUENCE_LEN];
+                d - sync * self.sequence[chip] as f64
+            })
+            .collect::<Vec<_>>();
+        let payload =
+            self.payload_sequences
+                .iter()
+                .enumerate()
+                .fold(0, |payload, (i, sequence)| {
+                    let (_, byte) = (0..=u8::MAX)
+                        .map(|shift| {
+                            let correlation = residual
+                                .iter()
+                                .enumerate()
+                                .map(|(k, d)| {
+                                    d * sequence[(k + SEQUENCE_LEN - shift as usize) % SEQUENCE_LEN]
+                                        as f64
+                                })
+                                .sum::<f64>();
+                            (correlation, shift)
+                        })
+                        .fold((f64::MIN, 0), |a, b| if b.0 > a.0 { b } else { a });
+                    payload | (byte as u16) << (8 * i)
+                });
+        Detection {
+            present,
+            payload: present.then_some(payload),
+            score: best.0 as f32,
+            confidence: (1. - false_alarm) as f32,
+            offset: best.1,
+        }
+    }
+}
+
+/// 水印检测结果
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Detection {
+    /// 是否检测到水印
+    pub present: bool,
+    /// 解出的负载，没有检测到水印时为`None`
+    pub payload: Option<u16>,
+    /// 检测统计量的标准分数，没有水印时近似服从标准正态分布
+    pub score: f32,
+    /// 置信度，即1减去未加水印的音频得到不低于该分数的概率，范围为0到1
+    pub confidence: f32,
+    /// 水印序列在音频中的起始相位（样本数）
+    pub offset: usize,
+}
+
+/// 流式水印嵌入器
+///
+/// 该结构体可以对任意切分的音频块依次嵌入水印，块与块之间的水印序列和包络跟踪是连续的。
+pub struct WatermarkEmbedder {
+    // 同步序列与负载序列叠加后的码片
+    chips: Arc<[f32]>,
+    strength: f32,
+    // 已处理的样本数
+    position: usize,
+    envelope: f32,
+}
+
+impl WatermarkEmbedder {
+    /// 对一块音频原地嵌入水印
+    pub fn process(&mut self, audio: &mut [f32]) {
+        // 包络跟踪：1毫秒起音，20毫秒释音
+        let attack = (-1. / (0.001 * SAMPLE_RATE as f32)).exp();
+        let release = (-1. / (0.02 * SAMPLE_RATE as f32)).exp();
+        for sample in audio {
+            let level = sample.abs();
+            let coefficient = if level > self.envelope {
+                attack
+            } else {
+                release
+            };
+            self.envelope = level + (self.envelope - level) * coefficient;
+
+            let chip = self.chips[self.position / CHIP_LEN % SEQUENCE_LEN];
+            *sample += chip * self.strength * self.envelope;
+            self.position = (self.position + 1) % (CHIP_LEN * SEQUENCE_LEN);
+        }
+    }
+}
+
+/// 水印流
+///
+/// 该结构体包装一个语音合成流，对每一段合成结果嵌入水印。
+#[pin_project]
+pub struct WatermarkStream<S> {
+    #[pin]
+    inner: S,
+    embedder: WatermarkEmbedder,
+}
+
+impl<S> WatermarkStream<S> {
+    pub(super) fn new(inner: S, watermark: &Watermark) -> Self {
+        Self {
+            inner,
+            embedder: watermark.embedder(),
+        }
+    }
+}
+
+impl<S> Stream for WatermarkStream<S>
+where
+    S: Stream<Item = (Vec<f32>, Duration)>,
+{
+    type Item = (Vec<f32>, Duration);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        this.inner.poll_next(cx).map(|i| {
+            i.map(|(mut data, took)| {
+                this.embedder.process(&mut data);
+                (data, took)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        futures::{StreamExt, executor::block_on, stream::iter},
+        std::f32::consts::PI,
+    };
+
+    /// 模拟语音：带抖动的声门脉冲经过共振峰滤波，按音节调制并夹杂停顿
+    fn speech(seconds: f32, seed: u64) -> Vec<f32> {
+        let mut state = seed * 2 + 1;
+        let mut random = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 40) as f32 / (1u64 << 24) as f32
+        };
+        // 每个种子对应不同的说话人和语速
+        let (base, scale, rate) = (
+            90. + 120. * random(),
+            0.85 + 0.3 * random(),
+            3. + 2. * random(),
+        );
+        let len = (seconds * SAMPLE_RATE as f32) as usize;
+        let mut phase = 0.;
+        let mut glottal = Vec::with_capacity(len);
+        for i in 0..len {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let f0 = base * (1. + 0.2 * (2. * PI * 0.7 * t).sin()) + 5. * random();
+            phase += f0 / SAMPLE_RATE as f32;
+            let pulse = if phase >= 1. {
+                phase -= 1.;
+                1.
+            } else {
+                0.
+            };
+            glottal.push(pulse + 0.05 * (random() - 0.5));
+        }
+
+        let mut out = vec![0.; len];
+        for (formant, bandwidth, gain) in [(700., 130., 1.), (1220., 70., 0.5), (2600., 160., 0.25)]
+        {
+            let r = (-PI * bandwidth / SAMPLE_RATE as f32).exp();
+            let a1 = 2. * r * (2. * PI * formant * scale / SAMPLE_RATE as f32).cos();
+            let a2 = -r * r;
+            let (mut y1, mut y2) = (0., 0.);
+            for (o, x) in out.iter_mut().zip(&glottal) {
+                let y = x + a1 * y1 + a2 * y2;
+                *o += y * gain * 0.02;
+                (y2, y1) = (y1, y);
+            }
+        }
+
+        for (i, o) in out.iter_mut().enumerate() {
+            // 每个音节之间有短暂的停顿
+            let syllable = (i as f32 / SAMPLE_RATE as f32 * rate).fract();
+            *o *= if syllable < 0.8 {
+                (PI * syllable / 0.8).sin()
+            } else {
+                0.
+            };
+        }
+        out
+    }
+
+    /// 线性插值重采样
+    fn resample(audio: &[f32], from: u32, to: u32) -> Vec<f32> {
+        let len = audio.len() as u64 * to as u64 / from as u64;
+        (0..len)
+            .map(|i| {
+                let pos = i as f64 * from as f64 / to as f64;
+                let j = pos as usize;
+                let frac = (pos - j as f64) as f32;
+                let next = audio.get(j + 1).copied().unwrap_or_default();
+                audio[j] * (1. - frac) + next * frac
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_watermark_round_trip() {
+        let watermark = Watermark::new("kokoro");
+        let audio = speech(3., 1);
+        let marked = watermark.embed(&audio);
+
+        let detection = watermark.detect(&marked);
+        assert!(detection.present, "{:?}", detection);
+        assert!(detection.confidence > 0.999, "{:?}", detection);
+        assert_eq!(0, detection.offset);
+
+        // 截掉开头后仍然可以检测，并找到正确的相位
+        let detection = watermark.detect(&marked[1000..]);
+        assert!(detection.present, "{:?}", detection);
+        assert_eq!(CHIP_LEN * SEQUENCE_LEN - 1000, detection.offset);
+
+        // 水印应当远低于语音
+        let noise = marked
+            .iter()
+            .zip(&audio)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>();
+        let signal = audio.iter().map(|i| i * i).sum::<f32>();
+        assert!(10. * (signal / noise).log10() > 25.);
+    }
+
+    #[test]
+    fn test_watermark_robustness() {
+        let watermark = Watermark::new("kokoro");
+        let marked = watermark.embed(&speech(3., 2));
+
+        // 降采样到8kHz再还原，模拟电话信道
+        let narrow = resample(&resample(&marked, SAMPLE_RATE, 8000), 8000, SAMPLE_RATE);
+        let detection = watermark.detect(&narrow);
+        assert!(detection.present, "{:?}", detection);
+
+        // 8位μ律量化
+        let mu = 255f32;
+        let quantized = marked
+            .iter()
+            .map(|i| {
+                let code = ((1. + mu * i.abs()).ln() / (1. + mu).ln() * 127.).round() / 127.;
+                i.signum() * ((1. + mu).powf(code) - 1.) / mu
+            })
+            .collect::<Vec<_>>();
+        let detection = watermark.detect(&quantized);
+        assert!(detection.present, "{:?}", detection);
+
+        // 分块嵌入与整体嵌入结果一致
+        let audio = speech(1., 3);
+        let mut embedder = watermark.embedder();
+        let items = audio
+            .chunks(1234)
+            .map(|i| (i.to_vec(), Duration::ZERO))
+            .collect::<Vec<_>>();
+        let chunked = block_on(
+            WatermarkStream {
+                inner: iter(items),
+                embedder: watermark.embedder(),
+            }
+            .collect::<Vec<_>>(),
+        )
+        .into_iter()
+        .flat_map(|(i, _)| i)
+        .collect::<Vec<_>>();
+        let mut whole = audio.clone();
+        embedder.process(&mut whole);
+        assert_eq!(whole, chunked);
+    }
+
+    #[test]
+    fn test_watermark_payload() {
+        for seed in 1..6 {
+            let payload = 0x5EED + seed as u16;
+            let watermark = Watermark::new("kokoro").with_payload(payload);
+            let marked = watermark.embed(&speech(3., seed));
+            let detection = watermark.detect(&marked);
+            assert_eq!(Some(payload), detection.payload, "{:?}", detection);
+
+            // 截掉开头或经过电话信道后仍然可以解出
+            let detection = watermark.detect(&marked[1000..]);
+            assert_eq!(Some(payload), detection.payload, "{:?}", detection);
+            let narrow = resample(&resample(&marked, SAMPLE_RATE, 8000), 8000, SAMPLE_RATE);
+            let detection = watermark.detect(&narrow);
+            assert_eq!(Some(payload), detection.payload, "{:?}", detection);
+
+            // 检测不需要事先知道负载
+            let detection = Watermark::new("kokoro").detect(&marked);
+            assert_eq!(Some(payload), detection.payload);
+        }
+
+        assert_eq!(
+            None,
+            Watermark::new("kokoro").detect(&speech(3., 1)).payload
+        );
+    }
+
+    #[cfg(feature = "opus")]
+    #[test]
+    fn test_watermark_opus() -> Result<(), crate::KokoroError> {
+        use audiopus::{
+            Application, Channels, MutSignals, SampleRate,
+            coder::{Decoder, Encoder},
+            packet::Packet,
+        };
+
+        let watermark = Watermark::new("kokoro").with_payload(0xBEEF);
+        let marked = watermark.embed(&speech(4., 4));
+        let encoder = Encoder::new(SampleRate::Hz24000, Channels::Mono, Application::Voip)?;
+        let mut decoder = Decoder::new(SampleRate::Hz24000, Channels::Mono)?;
+        let mut decoded = Vec::with_capacity(marked.len());
+        let (mut packet, mut frame) = ([0; 4000], [0.; 480]);
+        for chunk in marked.chunks_exact(480) {
+            let len = encoder.encode_float(chunk, &mut packet)?;
+            let packet = Packet::try_from(&packet[..len])?;
+            let len =
+                decoder.decode_float(Some(packet), MutSignals::try_from(&mut frame[..])?, false)?;
+            decoded.extend_from_slice(&frame[..len]);
+        }
+
+        let detection = watermark.detect(&decoded);
+        assert!(detection.present, "{:?}", detection);
+        assert_eq!(Some(0xBEEF), detection.payload, "{:?}", detection);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_watermark_false_positive() {
+        let watermark = Watermark::new("kokoro");
+        for seed in 1..6 {
+            let audio = speech(3., seed);
+            let detection = watermark.detect(&audio);
+            assert!(!detection.present, "{:?}", detection);
+            assert!(detection.confidence < 0.99, "{:?}", detection);
+
+            // 其他密钥的水印也不应被误认
+            let other = Watermark::new("other").embed(&audio);
+            assert!(!watermark.detect(&other).present);
+        }
+
+        assert!(!watermark.detect(&[]).present);
+        assert!(!watermark.detect(&[0.; 48000]).present);
+    }
+}